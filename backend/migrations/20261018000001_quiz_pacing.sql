@@ -0,0 +1,15 @@
+-- Per-question time limits and quiz pacing modes
+-- time_limit (seconds) overrides the default deadline of a single question.
+ALTER TABLE quiz_questions
+    ADD COLUMN IF NOT EXISTS time_limit INTEGER DEFAULT NULL;
+
+-- pacing_mode: 'self_paced' (each player at their own pace),
+-- 'host_paced' (host advances, server enforces deadlines) or
+-- 'synchronized' (server advances everyone when the countdown ends)
+ALTER TABLE quiz_configs
+    ADD COLUMN IF NOT EXISTS pacing_mode VARCHAR(20) NOT NULL DEFAULT 'self_paced';
+
+-- Server-side quiz clock per room
+ALTER TABLE game_rooms
+    ADD COLUMN IF NOT EXISTS quiz_question_index INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS quiz_question_started_at TIMESTAMPTZ;
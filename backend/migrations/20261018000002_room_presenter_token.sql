@@ -0,0 +1,4 @@
+-- Presenter token: grants the host role in the room WebSocket to a second device
+-- (e.g. the teacher's projector) without sharing the host's login.
+ALTER TABLE game_rooms
+    ADD COLUMN IF NOT EXISTS presenter_token VARCHAR(64);
@@ -0,0 +1,8 @@
+-- Optional time limit (seconds) for a room; NULL keeps the room open-ended
+ALTER TABLE game_rooms
+    ADD COLUMN IF NOT EXISTS time_limit INTEGER DEFAULT NULL;
+
+-- Why the timer is paused: 'host' (paused by the host, only the host resumes)
+-- or 'empty' (all players left, resumed by the next connection)
+ALTER TABLE game_rooms
+    ADD COLUMN IF NOT EXISTS pause_reason VARCHAR(20) DEFAULT NULL;
@@ -0,0 +1,30 @@
+-- Explicit room lifecycle: lobby, countdown, running, paused, finished, archived.
+-- The column and its backfill go together so the backfill runs only once: on later
+-- boots status already exists and rooms legitimately back in 'lobby' are left alone.
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'game_rooms' AND column_name = 'status'
+    ) THEN
+        ALTER TABLE game_rooms ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'lobby';
+
+        -- Backfill from the columns the state used to be inferred from
+        UPDATE game_rooms SET status = CASE
+            WHEN is_active = false THEN 'archived'
+            WHEN completed_at IS NOT NULL THEN 'finished'
+            WHEN paused_at IS NOT NULL THEN 'paused'
+            WHEN started_at IS NOT NULL THEN 'running'
+            ELSE 'lobby'
+        END;
+
+        -- Rooms paused before pause_reason existed were paused because everyone left
+        UPDATE game_rooms SET pause_reason = 'empty'
+        WHERE status = 'paused' AND pause_reason IS NULL;
+    END IF;
+END
+$$;
+
+ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS countdown_ends_at TIMESTAMPTZ DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_game_rooms_status ON game_rooms(status);
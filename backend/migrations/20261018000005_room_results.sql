@@ -0,0 +1,16 @@
+-- Final results of closed rooms (expired or closed by the host).
+-- Kept after anonymous participant data is purged.
+CREATE TABLE IF NOT EXISTS room_results (
+    room_id             INTEGER PRIMARY KEY REFERENCES game_rooms(id) ON DELETE CASCADE,
+    game_id             INTEGER NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    room_name           VARCHAR(100) NOT NULL,
+    results             JSONB NOT NULL DEFAULT '[]',
+    total_score         INTEGER NOT NULL DEFAULT 0,
+    completion_time     INTEGER,
+    close_reason        VARCHAR(20) NOT NULL,
+    closed_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    anonymous_purged_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_results_game ON room_results(game_id);
+CREATE INDEX IF NOT EXISTS idx_game_rooms_expires ON game_rooms(expires_at) WHERE is_active = true;
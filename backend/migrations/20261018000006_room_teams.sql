@@ -0,0 +1,19 @@
+-- Team mode: teams defined by the host; players are assigned (chosen or auto-balanced)
+CREATE TABLE IF NOT EXISTS room_teams (
+    id         SERIAL PRIMARY KEY,
+    room_id    INTEGER NOT NULL REFERENCES game_rooms(id) ON DELETE CASCADE,
+    name       VARCHAR(50) NOT NULL,
+    color      VARCHAR(7)  NOT NULL,
+    position   INTEGER     NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ DEFAULT now(),
+    CONSTRAINT room_teams_room_name_key UNIQUE (room_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_teams_room ON room_teams(room_id);
+
+-- Team of each participant, and the team credited with each score / found word
+ALTER TABLE room_participants  ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL;
+ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL;
+ALTER TABLE room_found_words   ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_room_participants_team ON room_participants(team_id);
@@ -0,0 +1,15 @@
+-- Word search room mode: cooperative (shared board), race (one board per player) or steal
+-- (one board per player, only the first finder scores the word)
+ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS word_search_mode VARCHAR(20) NOT NULL DEFAULT 'cooperative';
+
+-- Board the found word belongs to: '' = shared claim (cooperative, first finder in steal),
+-- otherwise the player's score key (race, later finders in steal)
+ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS board_key VARCHAR(255) NOT NULL DEFAULT '';
+ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS points INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS elapsed_seconds INTEGER;
+
+ALTER TABLE room_found_words DROP CONSTRAINT IF EXISTS room_found_words_room_id_word_key;
+CREATE UNIQUE INDEX IF NOT EXISTS room_found_words_room_word_board_key ON room_found_words(room_id, word, board_key);
+
+-- Game time of the player's latest word (race ranking tie-break)
+ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS last_found_seconds INTEGER;
@@ -0,0 +1,2 @@
+-- Scoring curve and bonuses of each word search (NULL = default stepped curve)
+ALTER TABLE word_search_configs ADD COLUMN IF NOT EXISTS scoring JSONB DEFAULT NULL;
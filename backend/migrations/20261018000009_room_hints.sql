@@ -0,0 +1,16 @@
+-- Word search hints requested in rooms (one charge per player, word and hint kind)
+CREATE TABLE IF NOT EXISTS room_hints (
+    id          SERIAL PRIMARY KEY,
+    room_id     INTEGER      NOT NULL REFERENCES game_rooms(id) ON DELETE CASCADE,
+    session_id  VARCHAR(255) NOT NULL,
+    player_name VARCHAR(255) NOT NULL,
+    word        VARCHAR(255) NOT NULL,
+    kind        VARCHAR(20)  NOT NULL,
+    penalty     INTEGER      NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ  DEFAULT now(),
+    CONSTRAINT room_hints_player_word_kind_key UNIQUE (room_id, session_id, word, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_hints_room ON room_hints(room_id);
+
+ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS hints_used INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Per-game option to strip accents (ç, ã, é...) from the words when saving
+ALTER TABLE word_search_configs ADD COLUMN IF NOT EXISTS strip_accents BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,5 @@
+-- Solo results of anonymous players are linked to the device that played them
+ALTER TABLE game_results ADD COLUMN IF NOT EXISTS device_session_id VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_game_results_device ON game_results(device_session_id, game_id) WHERE device_session_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_game_results_user_created ON game_results(user_id, created_at);
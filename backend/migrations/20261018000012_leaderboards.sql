@@ -0,0 +1,68 @@
+-- One row per scored play, from solo results, word search rooms and quiz rooms.
+-- Refreshed in the background (LEADERBOARD_REFRESH_SECS); leaderboards read from here.
+-- Solo results only count when completed in a plausible time (at least 5 seconds),
+-- and quiz answers only once the room's match has ended.
+CREATE MATERIALIZED VIEW IF NOT EXISTS leaderboard_entries AS
+SELECT
+    'solo_' || gr.id AS entry_key,
+    gr.game_id,
+    NULL::INTEGER AS room_id,
+    COALESCE('user_' || gr.user_id, gr.device_session_id, 'result_' || gr.id) AS player_key,
+    gr.user_id,
+    CASE WHEN gr.user_id IS NULL THEN COALESCE(gr.device_session_id, 'result_' || gr.id) END AS anonymous_key,
+    COALESCE(u.name, 'Jogador anônimo') AS player_name,
+    gr.score,
+    gr.time_seconds,
+    gr.created_at::timestamptz AS played_at
+FROM game_results gr
+LEFT JOIN users u ON u.id = gr.user_id
+WHERE gr.room_id IS NULL AND gr.completed AND gr.time_seconds >= 5
+UNION ALL
+SELECT
+    'room_' || rps.id,
+    r.game_id,
+    rps.room_id,
+    rps.session_id,
+    CASE WHEN rps.session_id ~ '^user_[0-9]+$' THEN substring(rps.session_id FROM 6)::INTEGER END,
+    CASE WHEN rps.session_id !~ '^user_[0-9]+$' THEN rps.session_id END,
+    rps.player_name,
+    rps.total_score,
+    rps.last_found_seconds,
+    rps.last_updated
+FROM room_player_scores rps
+JOIN game_rooms r ON r.id = rps.room_id
+UNION ALL
+SELECT
+    'quiz_' || qp.id,
+    r.game_id,
+    qp.room_id,
+    regexp_replace(qp.user_identifier, '^session_', ''),
+    CASE WHEN qp.user_identifier ~ '^user_[0-9]+$' THEN substring(qp.user_identifier FROM 6)::INTEGER END,
+    CASE WHEN qp.user_identifier !~ '^user_[0-9]+$' THEN regexp_replace(qp.user_identifier, '^session_', '') END,
+    COALESCE(qp.progress_data->>'player_name', 'Jogador'),
+    COALESCE((
+        SELECT SUM(q.points)::INTEGER
+        FROM jsonb_each_text(CASE WHEN jsonb_typeof(qp.progress_data->'answers') = 'object'
+                                  THEN qp.progress_data->'answers' ELSE '{}'::jsonb END) AS a(idx, answer)
+        JOIN (
+            SELECT qq.points, TRIM(qq.correct_option) AS correct_option,
+                   (ROW_NUMBER() OVER (ORDER BY qq.id) - 1)::TEXT AS idx
+            FROM quiz_questions qq
+            JOIN quiz_configs qc ON qc.id = qq.quiz_config_id
+            WHERE qc.game_id = r.game_id
+        ) q ON q.idx = a.idx AND q.correct_option = a.answer
+    ), 0),
+    NULL::INTEGER,
+    qp.updated_at::timestamptz
+FROM quiz_progress qp
+JOIN game_rooms r ON r.id = qp.room_id
+WHERE r.status IN ('finished', 'archived') AND r.completed_at IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_leaderboard_entries_key ON leaderboard_entries(entry_key);
+CREATE INDEX IF NOT EXISTS idx_leaderboard_entries_game ON leaderboard_entries(game_id, played_at);
+
+-- Anonymous players (device/session id) that asked to stay off the leaderboards
+CREATE TABLE IF NOT EXISTS leaderboard_opt_outs (
+    anonymous_key VARCHAR(255) PRIMARY KEY,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Room events too large for a NOTIFY payload: the notification carries only the row id
+CREATE TABLE IF NOT EXISTS room_event_payloads (
+    id         BIGSERIAL PRIMARY KEY,
+    payload    TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_room_event_payloads_created ON room_event_payloads(created_at);
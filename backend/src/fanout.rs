@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::room_hub::RoomRegistry;
+use crate::websocket::WsMessage;
+
+// Canal do Postgres usado para repassar eventos de sala entre instâncias
+const NOTIFY_CHANNEL: &str = "room_events";
+// Limite do payload do NOTIFY no Postgres (8000 bytes)
+const MAX_NOTIFY_PAYLOAD: usize = 7900;
+// Eventos grandes ficam em room_event_payloads por este tempo (as instâncias leem logo após o NOTIFY)
+const STORED_PAYLOAD_TTL_SECS: i64 = 300;
+
+// Backend de distribuição de eventos entre instâncias do servidor.
+// A entrega para os sockets desta instância é sempre local; o backend só repassa para as demais.
+pub trait FanoutBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn publish(&self, room_id: i32, message: &WsMessage);
+}
+
+pub type Fanout = Arc<dyn FanoutBackend>;
+
+// Padrão: uma única instância, nada a repassar
+pub struct InMemoryFanout;
+
+impl FanoutBackend for InMemoryFanout {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn publish(&self, _room_id: i32, _message: &WsMessage) {}
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FanoutEnvelope {
+    instance: String,
+    room_id: i32,
+    message: WsMessage,
+}
+
+// Conteúdo do NOTIFY: o evento inteiro ou, se não couber, o id da linha em room_event_payloads
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum FanoutNotice {
+    Event(FanoutEnvelope),
+    Stored { instance: String, room_id: i32, payload_id: i64 },
+}
+
+// O que a task de publicação envia: o NOTIFY pronto ou um envelope a gravar antes
+enum Outgoing {
+    Notify(String),
+    Store { instance: String, room_id: i32, envelope: String },
+}
+
+// Repassa eventos via LISTEN/NOTIFY. Os NOTIFY são enviados por uma única task
+// para manter a ordem dos eventos de cada sala.
+pub struct PostgresFanout {
+    instance_id: String,
+    sender: mpsc::UnboundedSender<Outgoing>,
+}
+
+impl FanoutBackend for PostgresFanout {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn publish(&self, room_id: i32, message: &WsMessage) {
+        let envelope = FanoutEnvelope {
+            instance: self.instance_id.clone(),
+            room_id,
+            message: message.clone(),
+        };
+        let payload = match serde_json::to_string(&envelope) {
+            Ok(p) => p,
+            Err(e) => {
+                log::error!("Failed to serialize fan-out payload for room {}: {}", room_id, e);
+                return;
+            }
+        };
+
+        // Grande demais para o NOTIFY: grava o envelope e notifica só a referência
+        let outgoing = if payload.len() > MAX_NOTIFY_PAYLOAD {
+            Outgoing::Store { instance: self.instance_id.clone(), room_id, envelope: payload }
+        } else {
+            Outgoing::Notify(payload)
+        };
+
+        if self.sender.send(outgoing).is_err() {
+            log::error!("Fan-out publisher stopped, event for room {} not relayed", room_id);
+        }
+    }
+}
+
+// Eventos recebidos das outras instâncias (room_id, mensagem)
+pub type RemoteEvents = mpsc::UnboundedReceiver<(i32, WsMessage)>;
+
+// Escolhe o backend pela variável WS_FANOUT ("memory" por padrão ou "postgres").
+// No modo postgres também retorna o canal de eventos remotos, a ser ligado com deliver_remote.
+pub fn from_env(pool: sqlx::PgPool) -> (Fanout, Option<RemoteEvents>) {
+    let backend = std::env::var("WS_FANOUT").unwrap_or_else(|_| "memory".to_string());
+
+    let (fanout, remote): (Fanout, _) = match backend.as_str() {
+        "postgres" => {
+            let instance_id = uuid::Uuid::new_v4().to_string();
+            let (sender, receiver) = mpsc::unbounded_channel();
+            let (remote_sender, remote_receiver) = mpsc::unbounded_channel();
+
+            actix::spawn(run_publisher(pool.clone(), receiver));
+            actix::spawn(run_listener(pool, instance_id.clone(), remote_sender));
+
+            log::info!("Fan-out instance id: {}", instance_id);
+            (Arc::new(PostgresFanout { instance_id, sender }), Some(remote_receiver))
+        }
+        other => {
+            if other != "memory" {
+                log::warn!("Unknown WS_FANOUT '{}', using in-memory fan-out", other);
+            }
+            (Arc::new(InMemoryFanout), None)
+        }
+    };
+
+    log::info!("WebSocket fan-out backend: {}", fanout.name());
+    (fanout, remote)
+}
+
+// Entrega aos sockets locais os eventos vindos das outras instâncias
+pub async fn deliver_remote(mut events: RemoteEvents, registry: RoomRegistry) {
+    while let Some((room_id, message)) = events.recv().await {
+        registry.broadcast_local(room_id, &message);
+    }
+}
+
+async fn run_publisher(pool: sqlx::PgPool, mut receiver: mpsc::UnboundedReceiver<Outgoing>) {
+    while let Some(outgoing) = receiver.recv().await {
+        let payload = match outgoing {
+            Outgoing::Notify(payload) => payload,
+            Outgoing::Store { instance, room_id, envelope } => match store_payload(&pool, &envelope).await {
+                Ok(payload_id) => {
+                    let notice = FanoutNotice::Stored { instance, room_id, payload_id };
+                    match serde_json::to_string(&notice) {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            log::error!("Failed to serialize fan-out reference for room {}: {}", room_id, e);
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to store large room event for room {}: {}", room_id, e);
+                    continue;
+                }
+            },
+        };
+
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(NOTIFY_CHANNEL)
+            .bind(&payload)
+            .execute(&pool)
+            .await
+        {
+            log::error!("Failed to publish room event: {}", e);
+        }
+    }
+}
+
+// Grava um evento grande e remove os antigos, já lidos pelas outras instâncias
+async fn store_payload(pool: &sqlx::PgPool, envelope: &str) -> Result<i64, sqlx::Error> {
+    let payload_id = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO room_event_payloads (payload) VALUES ($1) RETURNING id"
+    )
+    .bind(envelope)
+    .fetch_one(pool)
+    .await?;
+
+    sqlx::query("DELETE FROM room_event_payloads WHERE created_at < NOW() - make_interval(secs => $1)")
+        .bind(STORED_PAYLOAD_TTL_SECS as f64)
+        .execute(pool)
+        .await?;
+
+    Ok(payload_id)
+}
+
+async fn load_payload(pool: &sqlx::PgPool, payload_id: i64) -> Option<FanoutEnvelope> {
+    let payload = match sqlx::query_scalar::<_, String>("SELECT payload FROM room_event_payloads WHERE id = $1")
+        .bind(payload_id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(Some(payload)) => payload,
+        Ok(None) => {
+            log::warn!("Stored room event {} not found", payload_id);
+            return None;
+        }
+        Err(e) => {
+            log::error!("Failed to load stored room event {}: {}", payload_id, e);
+            return None;
+        }
+    };
+
+    serde_json::from_str(&payload)
+        .map_err(|e| log::warn!("Invalid stored fan-out payload {}: {}", payload_id, e))
+        .ok()
+}
+
+// Recebe eventos das outras instâncias e repassa para deliver_remote
+async fn run_listener(pool: sqlx::PgPool, instance_id: String, remote: mpsc::UnboundedSender<(i32, WsMessage)>) {
+    loop {
+        let mut listener = match sqlx::postgres::PgListener::connect_with(&pool).await {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("Fan-out listener connection failed: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = listener.listen(NOTIFY_CHANNEL).await {
+            log::error!("Fan-out LISTEN failed: {}", e);
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
+        log::info!("Fan-out listener subscribed to '{}'", NOTIFY_CHANNEL);
+
+        loop {
+            let notification = match listener.recv().await {
+                Ok(n) => n,
+                Err(e) => {
+                    log::error!("Fan-out listener error: {}", e);
+                    break;
+                }
+            };
+
+            let notice = match serde_json::from_str::<FanoutNotice>(notification.payload()) {
+                Ok(notice) => notice,
+                Err(e) => {
+                    log::warn!("Invalid fan-out payload: {}", e);
+                    continue;
+                }
+            };
+
+            // Eventos desta instância já foram entregues localmente
+            let envelope = match notice {
+                FanoutNotice::Event(envelope) if envelope.instance != instance_id => envelope,
+                FanoutNotice::Stored { instance, payload_id, .. } if instance != instance_id => {
+                    // Lido aqui mesmo para não passar na frente dos eventos seguintes da sala
+                    match load_payload(&pool, payload_id).await {
+                        Some(envelope) => envelope,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            if remote.send((envelope.room_id, envelope.message)).is_err() {
+                return;
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
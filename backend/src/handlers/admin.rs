@@ -34,7 +34,8 @@ pub async fn get_dashboard_stats(
         .await
         .unwrap_or(0);
 
-    let total_sessions = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM game_sessions")
+    // As sessões agora são salas (game_rooms)
+    let total_sessions = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM game_rooms")
         .fetch_one(pool.get_ref())
         .await
         .unwrap_or(0);
@@ -147,12 +148,13 @@ pub async fn delete_game(
     };
 
     // First delete related records
-    let _ = sqlx::query("DELETE FROM game_results WHERE session_id IN (SELECT id FROM game_sessions WHERE game_id = $1)")
+    let _ = sqlx::query("DELETE FROM game_results WHERE game_id = $1")
         .bind(*game_id)
         .execute(pool.get_ref())
         .await;
 
-    let _ = sqlx::query("DELETE FROM game_sessions WHERE game_id = $1")
+    // Salas (as antigas sessões); participantes, pontuações e progresso saem em cascata
+    let _ = sqlx::query("DELETE FROM game_rooms WHERE game_id = $1")
         .bind(*game_id)
         .execute(pool.get_ref())
         .await;
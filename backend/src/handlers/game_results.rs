@@ -2,6 +2,8 @@ use actix_web::{web, HttpResponse, Result, HttpRequest};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 
+use crate::word_search_scoring::{self, ScoringConfig};
+
 #[derive(Debug, Deserialize)]
 pub struct CreateGameResult {
     pub game_id: i32,
@@ -11,11 +13,11 @@ pub struct CreateGameResult {
     pub completed: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct GameResult {
     pub id: i32,
     pub game_id: i32,
-    pub user_id: i32,
+    pub user_id: Option<i32>,
     pub room_id: Option<i32>,
     pub time_seconds: i32,
     pub score: i32,
@@ -23,6 +25,89 @@ pub struct GameResult {
     pub created_at: chrono::NaiveDateTime,
 }
 
+// Resumo das partidas individuais de um jogador em um jogo
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ResultStats {
+    pub attempts: i64,
+    pub completed_attempts: i64,
+    // Recordes pessoais (só partidas concluídas)
+    pub best_score: Option<i32>,
+    pub best_time_seconds: Option<i32>,
+    pub average_score: Option<f64>,
+    pub average_time_seconds: Option<f64>,
+    pub last_played_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct GameHistory {
+    pub game_id: i32,
+    pub game_name: String,
+    pub game_type: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub stats: ResultStats,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+}
+
+// Máximo de partidas listadas no histórico de um jogo
+const HISTORY_LIMIT: i64 = 100;
+
+const STATS_COLUMNS: &str = "
+    COUNT(*) AS attempts,
+    COUNT(*) FILTER (WHERE completed) AS completed_attempts,
+    MAX(score) FILTER (WHERE completed) AS best_score,
+    MIN(time_seconds) FILTER (WHERE completed) AS best_time_seconds,
+    AVG(score)::float8 AS average_score,
+    AVG(time_seconds) FILTER (WHERE completed)::float8 AS average_time_seconds,
+    MAX(created_at) AS last_played_at";
+
+// Dono das partidas individuais: usuário logado ou, para anônimos, o aparelho
+// (header X-Device-Id, ou X-Session-Id de quem já entrou em sala)
+struct ResultOwner {
+    user_id: Option<i32>,
+    device_session_id: Option<String>,
+    // Só o X-Device-Id: o X-Session-Id de uma sala não prova que o aparelho é do usuário
+    device_id: Option<String>,
+}
+
+fn header_key(req: &HttpRequest, header: &str) -> Option<String> {
+    req.headers().get(header)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && value.len() <= 255)
+        .map(str::to_string)
+}
+
+fn result_owner(req: &HttpRequest) -> Option<ResultOwner> {
+    let user_id = extract_user_id(req);
+    let device_id = header_key(req, "X-Device-Id");
+    let device_session_id = device_id.clone().or_else(|| header_key(req, "X-Session-Id"));
+
+    if user_id.is_none() && device_session_id.is_none() {
+        return None;
+    }
+    Some(ResultOwner { user_id, device_session_id, device_id })
+}
+
+// Usuário que fez login no aparelho herda as partidas anônimas feitas nele (pelo X-Device-Id)
+async fn claim_device_results(pool: &PgPool, owner: &ResultOwner) -> Result<(), sqlx::Error> {
+    if let (Some(user_id), Some(device_id)) = (owner.user_id, &owner.device_id) {
+        sqlx::query(
+            "UPDATE game_results SET user_id = $1
+             WHERE device_session_id = $2 AND user_id IS NULL AND room_id IS NULL"
+        )
+        .bind(user_id)
+        .bind(device_id)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
 // Extrair user_id do token ou session_id
 fn extract_user_id(req: &HttpRequest) -> Option<i32> {
     // Tentar extrair do header Authorization (JWT)
@@ -31,24 +116,8 @@ fn extract_user_id(req: &HttpRequest) -> Option<i32> {
             if auth_str.starts_with("Bearer ") {
                 let token = &auth_str[7..];
                 // Decodificar JWT para pegar user_id
-                use jsonwebtoken::{decode, DecodingKey, Validation};
-                use serde::{Deserialize};
-                
-                #[derive(Debug, Deserialize)]
-                struct Claims {
-                    sub: String,
-                    exp: usize,
-                }
-                
-                let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string());
-                if let Ok(token_data) = decode::<Claims>(
-                    token,
-                    &DecodingKey::from_secret(secret.as_bytes()),
-                    &Validation::default(),
-                ) {
-                    if let Ok(user_id) = token_data.claims.sub.parse::<i32>() {
-                        return Some(user_id);
-                    }
+                if let Some(user_id) = crate::middleware::user_id_from_token(token) {
+                    return Some(user_id);
                 }
             }
         }
@@ -72,8 +141,10 @@ pub async fn create_game_result(
     req: HttpRequest,
     data: web::Json<CreateGameResult>,
 ) -> Result<HttpResponse> {
-    // Extrair user_id ou usar None para jogadores anônimos
-    let user_id = extract_user_id(&req);
+    // Extrair user_id ou usar None para jogadores anônimos (ligados ao aparelho)
+    let owner = result_owner(&req);
+    let user_id = owner.as_ref().and_then(|owner| owner.user_id);
+    let device_session_id = owner.as_ref().and_then(|owner| owner.device_session_id.clone());
     
     // Verificar se já existe resultado
     let existing = if let Some(room_id) = data.room_id {
@@ -124,27 +195,22 @@ pub async fn create_game_result(
     }
     
     // Se não existe, criar novo resultado
-    // Para caça-palavras, calcular score baseado no tempo (mesma lógica do multiplayer)
-    let calculated_score = if data.time_seconds <= 60 {
-        100  // Primeiro minuto: 100 pontos
-    } else if data.time_seconds <= 300 {
-        // De 1 a 5 minutos: diminui de 100 para 50
-        let elapsed = data.time_seconds - 60;  // 0 a 240 segundos
-        let reduction = (elapsed as f32 / 240.0) * 50.0;  // 0 a 50 pontos de redução
-        (100.0 - reduction).floor() as i32
-    } else if data.time_seconds <= 600 {
-        // De 5 a 10 minutos: diminui de 50 para 20
-        let elapsed = data.time_seconds - 300;  // 0 a 300 segundos
-        let reduction = (elapsed as f32 / 300.0) * 30.0;  // 0 a 30 pontos de redução
-        (50.0 - reduction).floor() as i32
-    } else {
-        10  // Acima de 10 minutos: pontuação mínima
+    // Para caça-palavras, calcular score pela curva do jogo (mesma do multiplayer)
+    let scoring = word_search_scoring::load_game_scoring(pool.get_ref(), data.game_id)
+        .await
+        .map_err(|e| {
+            eprintln!("Erro ao buscar pontuação do jogo: {:?}", e);
+            actix_web::error::ErrorInternalServerError("Failed to load game scoring")
+        })?;
+    let calculated_score = match scoring {
+        Some((words, hidden, scoring)) => scoring.result_points(&words, data.time_seconds, data.completed, hidden),
+        None => ScoringConfig::default().curve.points(Some(data.time_seconds)),
     };
     
     let result = sqlx::query(
         r#"
-        INSERT INTO game_results (game_id, user_id, room_id, time_seconds, score, completed)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO game_results (game_id, user_id, room_id, time_seconds, score, completed, device_session_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING id, game_id, user_id, room_id, time_seconds, score, completed, created_at
         "#
     )
@@ -154,6 +220,7 @@ pub async fn create_game_result(
     .bind(data.time_seconds)
     .bind(calculated_score)  // Usar o score calculado pelo backend
     .bind(data.completed)
+    .bind(&device_session_id)
     .fetch_one(pool.get_ref())
     .await
     .map_err(|e| {
@@ -265,10 +332,122 @@ pub async fn get_game_result(
     }
 }
 
+// Histórico individual do jogador em todos os jogos, com recordes e médias
+pub async fn get_results_history(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    let owner = match result_owner(&req) {
+        Some(owner) => owner,
+        None => return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Login or X-Device-Id header required"
+        }))),
+    };
+    claim_device_results(pool.get_ref(), &owner).await.map_err(|e| {
+        eprintln!("Erro ao vincular resultados do aparelho: {:?}", e);
+        actix_web::error::ErrorInternalServerError("Failed to fetch results history")
+    })?;
+
+    let games = sqlx::query_as::<_, GameHistory>(&format!(
+        "SELECT g.game_id, g.game_name, g.game_type, {}
+         FROM game_results gr
+         -- Só as colunas usadas (games também tem created_at, usado em STATS_COLUMNS)
+         JOIN (SELECT id AS game_id, name AS game_name, game_type FROM games) g ON g.game_id = gr.game_id
+         WHERE gr.room_id IS NULL
+           AND (gr.user_id = $1 OR ($1::int IS NULL AND gr.device_session_id = $2))
+         GROUP BY g.game_id, g.game_name, g.game_type
+         ORDER BY last_played_at DESC",
+        STATS_COLUMNS
+    ))
+    .bind(owner.user_id)
+    .bind(&owner.device_session_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        eprintln!("Erro ao buscar histórico: {:?}", e);
+        actix_web::error::ErrorInternalServerError("Failed to fetch results history")
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "games": games })))
+}
+
+// Histórico individual do jogador em um jogo: partidas, recorde pessoal e médias
+pub async fn get_game_history(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    game_id: web::Path<i32>,
+    query: web::Query<HistoryQuery>,
+) -> Result<HttpResponse> {
+    let game_id = game_id.into_inner();
+    let owner = match result_owner(&req) {
+        Some(owner) => owner,
+        None => return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Login or X-Device-Id header required"
+        }))),
+    };
+    let map_err = |e: sqlx::Error| {
+        eprintln!("Erro ao buscar histórico do jogo: {:?}", e);
+        actix_web::error::ErrorInternalServerError("Failed to fetch game history")
+    };
+    claim_device_results(pool.get_ref(), &owner).await.map_err(map_err)?;
+
+    let owner_filter = "room_id IS NULL AND game_id = $1
+         AND (user_id = $2 OR ($2::int IS NULL AND device_session_id = $3))";
+
+    let stats = sqlx::query_as::<_, ResultStats>(&format!(
+        "SELECT {} FROM game_results WHERE {}", STATS_COLUMNS, owner_filter
+    ))
+    .bind(game_id)
+    .bind(owner.user_id)
+    .bind(&owner.device_session_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(map_err)?;
+
+    // Recorde pessoal: maior pontuação e, no empate, menor tempo
+    let personal_best = sqlx::query_as::<_, GameResult>(&format!(
+        "SELECT id, game_id, user_id, room_id, time_seconds, score, completed, created_at
+         FROM game_results WHERE {} AND completed
+         ORDER BY score DESC, time_seconds ASC, created_at ASC
+         LIMIT 1",
+        owner_filter
+    ))
+    .bind(game_id)
+    .bind(owner.user_id)
+    .bind(&owner.device_session_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(map_err)?;
+
+    let results = sqlx::query_as::<_, GameResult>(&format!(
+        "SELECT id, game_id, user_id, room_id, time_seconds, score, completed, created_at
+         FROM game_results WHERE {}
+         ORDER BY created_at DESC
+         LIMIT $4",
+        owner_filter
+    ))
+    .bind(game_id)
+    .bind(owner.user_id)
+    .bind(&owner.device_session_id)
+    .bind(query.limit.unwrap_or(HISTORY_LIMIT).clamp(1, HISTORY_LIMIT))
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(map_err)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "game_id": game_id,
+        "stats": stats,
+        "personal_best": personal_best,
+        "results": results,
+    })))
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/game-results")
             .route("", web::post().to(create_game_result))
+            .route("/history", web::get().to(get_results_history))
+            .route("/{game_id}/history", web::get().to(get_game_history))
             .route("/{game_id}", web::get().to(get_game_result))
             .route("/{game_id}/{room_id}", web::get().to(get_game_result))
     );
@@ -84,13 +84,12 @@ pub struct KahootQuestion {
     pub options: Vec<KahootOption>,
 }
 
+// Visão de jogo: sem is_correct (a autoria usa get_game_for_edit)
 #[derive(Debug, Serialize)]
 pub struct KahootOption {
     pub id: i32,
     pub option_text: String,
     pub option_order: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub is_correct: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -319,7 +318,6 @@ pub async fn get_game(
             id: row.get("id"),
             option_text: row.get("option_text"),
             option_order: row.get("option_order"),
-            is_correct: None,
         }).collect();
 
         kahoot_questions.push(KahootQuestion {
@@ -489,7 +487,6 @@ pub async fn get_question(
         id: row.get("id"),
         option_text: row.get("option_text"),
         option_order: row.get("option_order"),
-        is_correct: None,
     }).collect();
 
     HttpResponse::Ok().json(KahootQuestion {
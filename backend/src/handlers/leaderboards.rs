@@ -0,0 +1,177 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::leaderboard::{self, LeaderboardFilter, LeaderboardPeriod};
+
+// Tamanho padrão e máximo de página
+const DEFAULT_PER_PAGE: i64 = 20;
+const MAX_PER_PAGE: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    // "all" (padrão) ou "week"
+    pub period: Option<String>,
+    // Salas de uma turma, separadas por vírgula (ex.: ?rooms=3,4,7)
+    pub rooms: Option<String>,
+    // false esconde os jogadores sem login
+    pub include_anonymous: Option<bool>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+struct Page {
+    page: i64,
+    per_page: i64,
+}
+
+impl Page {
+    fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
+
+fn parse_query(query: &LeaderboardQuery) -> Result<(LeaderboardFilter, Page), String> {
+    let period = match query.period.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        None => LeaderboardPeriod::default(),
+        Some(value) => LeaderboardPeriod::parse(value)
+            .ok_or_else(|| format!("period must be one of: {}", LeaderboardPeriod::ALL.join(", ")))?,
+    };
+
+    let room_ids = match query.rooms.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        None => None,
+        Some(rooms) => Some(
+            rooms.split(',')
+                .map(|id| id.trim().parse::<i32>().map_err(|_| format!("Invalid room id \"{}\"", id.trim())))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+    };
+
+    let page = Page {
+        page: query.page.unwrap_or(1).max(1),
+        per_page: query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+    };
+
+    Ok((
+        LeaderboardFilter { period, room_ids, include_anonymous: query.include_anonymous.unwrap_or(true) },
+        page,
+    ))
+}
+
+// Chaves do jogador anônimo (aparelho do jogo individual e sessão das salas)
+fn anonymous_keys(req: &HttpRequest) -> Vec<String> {
+    ["X-Device-Id", "X-Session-Id"].iter()
+        .filter_map(|header| req.headers().get(*header))
+        .filter_map(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && value.len() <= 255)
+        .map(str::to_string)
+        .collect()
+}
+
+// GET /api/leaderboards - placar geral (soma das melhores pontuações em cada jogo)
+pub async fn get_global_leaderboard(
+    pool: web::Data<PgPool>,
+    query: web::Query<LeaderboardQuery>,
+) -> Result<HttpResponse> {
+    let (filter, page) = match parse_query(&query) {
+        Ok(parsed) => parsed,
+        Err(message) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))),
+    };
+
+    let (entries, total) = leaderboard::global_leaderboard(pool.get_ref(), &filter, page.per_page, page.offset())
+        .await
+        .map_err(|e| {
+            log::error!("Erro ao buscar placar geral: {:?}", e);
+            actix_web::error::ErrorInternalServerError("Failed to fetch leaderboard")
+        })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "period": filter.period.as_str(),
+        "page": page.page,
+        "per_page": page.per_page,
+        "total": total,
+        "entries": entries,
+    })))
+}
+
+// GET /api/leaderboards/{game_id} - melhor partida de cada jogador no jogo
+pub async fn get_game_leaderboard(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    query: web::Query<LeaderboardQuery>,
+) -> Result<HttpResponse> {
+    let game_id = game_id.into_inner();
+    let (filter, page) = match parse_query(&query) {
+        Ok(parsed) => parsed,
+        Err(message) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))),
+    };
+
+    let game_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM games WHERE id = $1)")
+        .bind(game_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            log::error!("Erro ao buscar jogo: {:?}", e);
+            actix_web::error::ErrorInternalServerError("Failed to fetch game")
+        })?;
+    if !game_exists {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({ "error": "Game not found" })));
+    }
+
+    let (entries, total) = leaderboard::game_leaderboard(pool.get_ref(), game_id, &filter, page.per_page, page.offset())
+        .await
+        .map_err(|e| {
+            log::error!("Erro ao buscar placar do jogo: {:?}", e);
+            actix_web::error::ErrorInternalServerError("Failed to fetch leaderboard")
+        })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "game_id": game_id,
+        "period": filter.period.as_str(),
+        "rooms": filter.room_ids,
+        "page": page.page,
+        "per_page": page.per_page,
+        "total": total,
+        "entries": entries,
+    })))
+}
+
+// POST /api/leaderboards/opt-out - jogador anônimo sai dos placares
+pub async fn opt_out(pool: web::Data<PgPool>, req: HttpRequest) -> Result<HttpResponse> {
+    set_opt_out(pool.get_ref(), &req, true).await
+}
+
+// DELETE /api/leaderboards/opt-out - volta a aparecer nos placares
+pub async fn opt_in(pool: web::Data<PgPool>, req: HttpRequest) -> Result<HttpResponse> {
+    set_opt_out(pool.get_ref(), &req, false).await
+}
+
+async fn set_opt_out(pool: &PgPool, req: &HttpRequest, opted_out: bool) -> Result<HttpResponse> {
+    let keys = anonymous_keys(req);
+    if keys.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "X-Device-Id or X-Session-Id header required"
+        })));
+    }
+
+    // Só chaves de jogadores anônimos que existem (sessão de sala ou aparelho com partidas):
+    // quem conhece a chave é o próprio jogador
+    let keys = leaderboard::known_anonymous_keys(pool, &keys).await.map_err(|e| {
+        log::error!("Erro ao conferir jogador anônimo: {:?}", e);
+        actix_web::error::ErrorInternalServerError("Failed to update leaderboard opt-out")
+    })?;
+    if keys.is_empty() {
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Unknown anonymous player"
+        })));
+    }
+
+    leaderboard::set_opt_out(pool, &keys, opted_out).await.map_err(|e| {
+        log::error!("Erro ao atualizar opt-out do placar: {:?}", e);
+        actix_web::error::ErrorInternalServerError("Failed to update leaderboard opt-out")
+    })?;
+
+    // A view só é recalculada pelo job, mas o filtro de opt-out é aplicado na leitura
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "opted_out": opted_out })))
+}
@@ -0,0 +1,761 @@
+use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use bcrypt::{hash, DEFAULT_COST};
+
+use crate::models::{Game, WordSearchConfig, QuizConfig, QuizQuestion, ErrorResponse};
+use crate::quiz_clock::PacingMode;
+use crate::word_search_clues;
+use crate::word_search_words;
+use crate::word_search_scoring::ScoringConfig;
+
+// Identificação do pacote exportado
+pub const PACKAGE_FORMAT: &str = "ceps-space-package";
+pub const PACKAGE_VERSION: i32 = 1;
+
+// Pacote portátil de um jogo. Não inclui ids, dono nem senhas;
+// a importação recria tudo na conta de quem importa.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GamePackage {
+    pub format: String,
+    pub version: i32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub content: PackageContent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PackageContent {
+    // Jogos da tabela games (caça-palavras e quiz)
+    Game {
+        game: GameExport,
+        #[serde(default)]
+        word_search: Option<Box<WordSearchExport>>,
+        #[serde(default)]
+        quiz: Option<Box<QuizExport>>,
+    },
+    Kahoot {
+        kahoot: KahootExport,
+    },
+    OpenQuestion {
+        open_question: OpenQuestionExport,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameExport {
+    pub name: String,
+    pub game_type: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub is_active: Option<bool>,
+    #[serde(default)]
+    pub end_screen_text: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_text: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_url: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_new_tab: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordSearchExport {
+    pub grid_size: i32,
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub time_limit: Option<i32>,
+    #[serde(default)]
+    pub allowed_directions: Option<serde_json::Value>,
+    #[serde(default)]
+    pub concepts: Option<serde_json::Value>,
+    #[serde(default)]
+    pub hide_words: bool,
+    #[serde(default)]
+    pub scoring: Option<ScoringConfig>,
+    #[serde(default)]
+    pub strip_accents: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizExport {
+    #[serde(default)]
+    pub time_limit: Option<i32>,
+    #[serde(default)]
+    pub end_screen_text: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_text: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_url: Option<String>,
+    #[serde(default)]
+    pub end_screen_button_new_tab: Option<bool>,
+    #[serde(default)]
+    pub min_players: Option<i32>,
+    #[serde(default)]
+    pub pacing_mode: Option<String>,
+    pub questions: Vec<QuizQuestionExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizQuestionExport {
+    pub question: String,
+    pub option_a: String,
+    pub option_b: String,
+    pub option_c: String,
+    pub option_d: String,
+    pub correct_option: String,
+    #[serde(default)]
+    pub justification: Option<String>,
+    #[serde(default)]
+    pub points: Option<i32>,
+    #[serde(default)]
+    pub time_limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KahootExport {
+    pub title: String,
+    pub description: Option<String>,
+    pub questions: Vec<KahootQuestionExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KahootQuestionExport {
+    pub question_text: String,
+    pub question_order: i32,
+    #[serde(default)]
+    pub time_limit: Option<i32>,
+    #[serde(default)]
+    pub points: Option<i32>,
+    pub options: Vec<KahootOptionExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KahootOptionExport {
+    pub option_text: String,
+    pub option_order: i32,
+    pub is_correct: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenQuestionExport {
+    pub title: String,
+    pub description: Option<String>,
+    pub questions: Vec<String>,
+}
+
+// Senhas não viajam no pacote: Kahoot exige presenter_password e room_password,
+// Pergunta Aberta exige presenter_password e game_password.
+#[derive(Debug, Deserialize)]
+pub struct ImportPackageRequest {
+    pub package: GamePackage,
+    pub presenter_password: Option<String>,
+    pub room_password: Option<String>,
+    pub game_password: Option<String>,
+}
+
+fn wrap(content: PackageContent) -> GamePackage {
+    GamePackage {
+        format: PACKAGE_FORMAT.to_string(),
+        version: PACKAGE_VERSION,
+        exported_at: Utc::now(),
+        content,
+    }
+}
+
+fn attachment(package: GamePackage, filename: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .json(package)
+}
+
+// GET /api/protected/games/{id}/export
+pub async fn export_game(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let game = match sqlx::query_as::<_, Game>(
+        "SELECT * FROM games WHERE id = $1 AND created_by = $2"
+    )
+    .bind(*game_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(Some(g)) => g,
+        Ok(None) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Game not found or unauthorized".to_string(),
+        }),
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let word_search = match sqlx::query_as::<_, WordSearchConfig>(
+        "SELECT * FROM word_search_configs WHERE game_id = $1"
+    )
+    .bind(game.id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(config) => config.map(|c| Box::new(WordSearchExport {
+            grid_size: c.grid_size,
+            words: c.words,
+            time_limit: c.time_limit,
+            allowed_directions: c.allowed_directions,
+            concepts: c.concepts,
+            hide_words: c.hide_words,
+            scoring: c.scoring.and_then(|scoring| serde_json::from_value(scoring).ok()),
+            strip_accents: c.strip_accents,
+        })),
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let quiz_config = match sqlx::query_as::<_, QuizConfig>(
+        "SELECT * FROM quiz_configs WHERE game_id = $1"
+    )
+    .bind(game.id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(config) => config,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let quiz = match quiz_config {
+        Some(config) => {
+            let questions = match sqlx::query_as::<_, QuizQuestion>(
+                "SELECT * FROM quiz_questions WHERE quiz_config_id = $1 ORDER BY id"
+            )
+            .bind(config.id)
+            .fetch_all(pool.get_ref())
+            .await {
+                Ok(q) => q,
+                Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+                    error: format!("Database error: {}", e),
+                }),
+            };
+
+            Some(Box::new(QuizExport {
+                time_limit: config.time_limit,
+                end_screen_text: config.end_screen_text,
+                end_screen_button_text: config.end_screen_button_text,
+                end_screen_button_url: config.end_screen_button_url,
+                end_screen_button_new_tab: config.end_screen_button_new_tab,
+                min_players: config.min_players,
+                pacing_mode: Some(config.pacing_mode),
+                questions: questions.into_iter().map(|q| QuizQuestionExport {
+                    question: q.question,
+                    option_a: q.option_a,
+                    option_b: q.option_b,
+                    option_c: q.option_c,
+                    option_d: q.option_d,
+                    correct_option: q.correct_option.trim().to_string(),
+                    justification: q.justification,
+                    points: Some(q.points),
+                    time_limit: q.time_limit,
+                }).collect(),
+            }))
+        }
+        None => None,
+    };
+
+    let filename = format!("{}-{}.json", game.game_type, game.id);
+    attachment(wrap(PackageContent::Game {
+        game: GameExport {
+            name: game.name,
+            game_type: game.game_type,
+            description: game.description,
+            is_active: Some(game.is_active),
+            end_screen_text: game.end_screen_text,
+            end_screen_button_text: game.end_screen_button_text,
+            end_screen_button_url: game.end_screen_button_url,
+            end_screen_button_new_tab: game.end_screen_button_new_tab,
+        },
+        word_search,
+        quiz,
+    }), filename)
+}
+
+// GET /api/protected/kahoot/games/{id}/export
+pub async fn export_kahoot(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let game_row = match sqlx::query(
+        "SELECT id, title, description FROM kahoot_games WHERE id = $1 AND user_id = $2"
+    )
+    .bind(*game_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(Some(row)) => row,
+        Ok(None) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Game not found or unauthorized".to_string(),
+        }),
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let question_rows = match sqlx::query(
+        "SELECT id, question_text, question_order, time_limit, points
+         FROM kahoot_questions WHERE game_id = $1 ORDER BY question_order"
+    )
+    .bind(*game_id)
+    .fetch_all(pool.get_ref())
+    .await {
+        Ok(rows) => rows,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let mut questions = Vec::with_capacity(question_rows.len());
+    for q_row in &question_rows {
+        let option_rows = match sqlx::query(
+            "SELECT option_text, option_order, is_correct
+             FROM kahoot_options WHERE question_id = $1 ORDER BY option_order"
+        )
+        .bind(q_row.get::<i32, _>("id"))
+        .fetch_all(pool.get_ref())
+        .await {
+            Ok(rows) => rows,
+            Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            }),
+        };
+
+        questions.push(KahootQuestionExport {
+            question_text: q_row.get("question_text"),
+            question_order: q_row.get("question_order"),
+            time_limit: q_row.get("time_limit"),
+            points: q_row.get("points"),
+            options: option_rows.iter().map(|o| KahootOptionExport {
+                option_text: o.get("option_text"),
+                option_order: o.get("option_order"),
+                is_correct: o.get("is_correct"),
+            }).collect(),
+        });
+    }
+
+    let filename = format!("kahoot-{}.json", *game_id);
+    attachment(wrap(PackageContent::Kahoot {
+        kahoot: KahootExport {
+            title: game_row.get("title"),
+            description: game_row.get("description"),
+            questions,
+        },
+    }), filename)
+}
+
+// GET /api/protected/open-question/games/{id}/export
+pub async fn export_open_question(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let game_row = match sqlx::query(
+        "SELECT id, title, description FROM open_question_games WHERE id = $1 AND user_id = $2"
+    )
+    .bind(*game_id)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(Some(row)) => row,
+        Ok(None) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Game not found or unauthorized".to_string(),
+        }),
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let questions = match sqlx::query_scalar::<_, String>(
+        "SELECT question_text FROM open_question_questions WHERE game_id = $1 ORDER BY order_index ASC"
+    )
+    .bind(*game_id)
+    .fetch_all(pool.get_ref())
+    .await {
+        Ok(q) => q,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let filename = format!("open-question-{}.json", *game_id);
+    attachment(wrap(PackageContent::OpenQuestion {
+        open_question: OpenQuestionExport {
+            title: game_row.get("title"),
+            description: game_row.get("description"),
+            questions,
+        },
+    }), filename)
+}
+
+// POST /api/protected/packages/import
+pub async fn import_package(
+    pool: web::Data<PgPool>,
+    body: web::Json<ImportPackageRequest>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let body = body.into_inner();
+
+    if body.package.format != PACKAGE_FORMAT {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Unknown package format: {}", body.package.format),
+        });
+    }
+    if body.package.version > PACKAGE_VERSION {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Unsupported package version: {}", body.package.version),
+        });
+    }
+
+    if let Err(message) = validate_package(&body) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: message });
+    }
+
+    // Hash das senhas do Kahoot fora da transação (bcrypt é lento)
+    let kahoot_hashes = if let PackageContent::Kahoot { .. } = body.package.content {
+        let presenter = hash(body.presenter_password.as_deref().unwrap_or_default(), DEFAULT_COST);
+        let room = hash(body.room_password.as_deref().unwrap_or_default(), DEFAULT_COST);
+        match (presenter, room) {
+            (Ok(p), Ok(r)) => Some((p, r)),
+            _ => return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: "Error hashing password".to_string(),
+            }),
+        }
+    } else {
+        None
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let result = match &body.package.content {
+        PackageContent::Game { game, word_search, quiz } => {
+            insert_game(&mut tx, user_id, game, word_search.as_deref(), quiz.as_deref())
+                .await
+                .map(|id| ("game", id))
+        }
+        PackageContent::Kahoot { kahoot } => {
+            let (presenter_hash, room_hash) = kahoot_hashes.unwrap_or_default();
+            insert_kahoot(&mut tx, user_id, kahoot, &presenter_hash, &room_hash)
+                .await
+                .map(|id| ("kahoot", id))
+        }
+        PackageContent::OpenQuestion { open_question } => {
+            insert_open_question(
+                &mut tx,
+                user_id,
+                open_question,
+                body.game_password.as_deref().unwrap_or_default(),
+                body.presenter_password.as_deref().unwrap_or_default(),
+            )
+            .await
+            .map(|id| ("open_question", id))
+        }
+    };
+
+    let (kind, id) = match result {
+        Ok(r) => r,
+        Err(e) => {
+            log::error!("Error importing package: {}", e);
+            let _ = tx.rollback().await;
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Error committing package import: {}", e);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        });
+    }
+
+    log::info!("User {} imported {} package as id {}", user_id, kind, id);
+
+    HttpResponse::Created().json(serde_json::json!({
+        "kind": kind,
+        "id": id,
+        "message": "Package imported successfully"
+    }))
+}
+
+fn validate_package(body: &ImportPackageRequest) -> Result<(), String> {
+    let has = |value: &Option<String>| value.as_deref().map(|v| !v.trim().is_empty()).unwrap_or(false);
+
+    match &body.package.content {
+        PackageContent::Game { game, word_search, quiz } => {
+            let name_len = game.name.chars().count();
+            if !(3..=100).contains(&name_len) {
+                return Err("Game name must have between 3 and 100 characters".to_string());
+            }
+            match game.game_type.as_str() {
+                "word_search" => {
+                    let ws = word_search.as_ref().ok_or("Word search package is missing its configuration")?;
+                    if !(10..=20).contains(&ws.grid_size) {
+                        return Err("Word search grid_size must be between 10 and 20".to_string());
+                    }
+                    if ws.words.is_empty() || ws.words.len() > 20 {
+                        return Err("Word search must have between 1 and 20 words".to_string());
+                    }
+                    if let Some(scoring) = &ws.scoring {
+                        scoring.validate()?;
+                    }
+                    let words = word_search_words::validate_words(&ws.words, ws.grid_size, ws.allowed_directions.as_ref(), ws.strip_accents)?;
+                    word_search_clues::validate_concepts(&words, ws.concepts.as_ref(), ws.hide_words, ws.strip_accents)?;
+                }
+                "quiz" => {
+                    let quiz = quiz.as_ref().ok_or("Quiz package is missing its configuration")?;
+                    if let Some(mode) = &quiz.pacing_mode {
+                        if PacingMode::parse(mode).is_none() {
+                            return Err(format!("Invalid pacing_mode '{}'", mode));
+                        }
+                    }
+                    for (idx, q) in quiz.questions.iter().enumerate() {
+                        if !["A", "B", "C", "D"].contains(&q.correct_option.trim()) {
+                            return Err(format!("Question {} has an invalid correct_option", idx + 1));
+                        }
+                        if q.time_limit.is_some_and(|t| !(5..=600).contains(&t)) {
+                            return Err(format!("Question {} has an invalid time_limit", idx + 1));
+                        }
+                    }
+                }
+                other => return Err(format!("Unsupported game type: {}", other)),
+            }
+        }
+        PackageContent::Kahoot { kahoot } => {
+            if kahoot.title.trim().is_empty() {
+                return Err("Title is required".to_string());
+            }
+            if !has(&body.presenter_password) || !has(&body.room_password) {
+                return Err("presenter_password and room_password are required to import a Kahoot game".to_string());
+            }
+        }
+        PackageContent::OpenQuestion { open_question } => {
+            if open_question.title.trim().is_empty() {
+                return Err("Title is required".to_string());
+            }
+            if open_question.questions.is_empty() {
+                return Err("At least one question is required".to_string());
+            }
+            if !has(&body.presenter_password) || !has(&body.game_password) {
+                return Err("presenter_password and game_password are required to import an open question game".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn insert_game(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i32,
+    game: &GameExport,
+    word_search: Option<&WordSearchExport>,
+    quiz: Option<&QuizExport>,
+) -> Result<i32, sqlx::Error> {
+    let game_id: i32 = sqlx::query_scalar(
+        "INSERT INTO games (name, game_type, description, created_by, is_active, end_screen_text, end_screen_button_text, end_screen_button_url, end_screen_button_new_tab)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id"
+    )
+    .bind(&game.name)
+    .bind(&game.game_type)
+    .bind(&game.description)
+    .bind(user_id)
+    .bind(game.is_active.unwrap_or(true))
+    .bind(&game.end_screen_text)
+    .bind(&game.end_screen_button_text)
+    .bind(&game.end_screen_button_url)
+    .bind(game.end_screen_button_new_tab)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if let Some(ws) = word_search.filter(|_| game.game_type == "word_search") {
+        // Validado em validate_package; aqui só se aplica a normalização
+        let words = word_search_words::validate_words(&ws.words, ws.grid_size, ws.allowed_directions.as_ref(), ws.strip_accents)
+            .unwrap_or_else(|_| ws.words.clone());
+        sqlx::query(
+            "INSERT INTO word_search_configs (game_id, grid_size, words, time_limit, allowed_directions, concepts, hide_words, scoring, strip_accents)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
+        )
+        .bind(game_id)
+        .bind(ws.grid_size)
+        .bind(&words)
+        .bind(ws.time_limit)
+        .bind(&ws.allowed_directions)
+        .bind(word_search_clues::validate_concepts(&words, ws.concepts.as_ref(), ws.hide_words, ws.strip_accents).ok())
+        .bind(ws.hide_words)
+        .bind(ws.scoring.as_ref().and_then(|scoring| serde_json::to_value(scoring).ok()))
+        .bind(ws.strip_accents)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    if let Some(quiz) = quiz.filter(|_| game.game_type == "quiz") {
+        let quiz_config_id: i32 = sqlx::query_scalar(
+            "INSERT INTO quiz_configs (game_id, time_limit, end_screen_text, end_screen_button_text, end_screen_button_url, end_screen_button_new_tab, min_players, pacing_mode)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id"
+        )
+        .bind(game_id)
+        .bind(quiz.time_limit)
+        .bind(&quiz.end_screen_text)
+        .bind(&quiz.end_screen_button_text)
+        .bind(&quiz.end_screen_button_url)
+        .bind(quiz.end_screen_button_new_tab)
+        .bind(quiz.min_players)
+        .bind(quiz.pacing_mode.as_deref().unwrap_or("self_paced"))
+        .fetch_one(&mut **tx)
+        .await?;
+
+        for q in &quiz.questions {
+            sqlx::query(
+                "INSERT INTO quiz_questions
+                 (quiz_config_id, question, option_a, option_b, option_c, option_d, correct_option, justification, points, time_limit)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
+            )
+            .bind(quiz_config_id)
+            .bind(&q.question)
+            .bind(&q.option_a)
+            .bind(&q.option_b)
+            .bind(&q.option_c)
+            .bind(&q.option_d)
+            .bind(q.correct_option.trim())
+            .bind(&q.justification)
+            .bind(q.points.unwrap_or(100))
+            .bind(q.time_limit)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    Ok(game_id)
+}
+
+async fn insert_kahoot(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i32,
+    kahoot: &KahootExport,
+    presenter_password_hash: &str,
+    room_password_hash: &str,
+) -> Result<i32, sqlx::Error> {
+    let game_id: i32 = sqlx::query_scalar(
+        "INSERT INTO kahoot_games (user_id, title, description, presenter_password, room_password)
+         VALUES ($1, $2, $3, $4, $5) RETURNING id"
+    )
+    .bind(user_id)
+    .bind(&kahoot.title)
+    .bind(&kahoot.description)
+    .bind(presenter_password_hash)
+    .bind(room_password_hash)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    for question in &kahoot.questions {
+        let question_id: i32 = sqlx::query_scalar(
+            "INSERT INTO kahoot_questions (game_id, question_text, question_order, time_limit, points)
+             VALUES ($1, $2, $3, $4, $5) RETURNING id"
+        )
+        .bind(game_id)
+        .bind(&question.question_text)
+        .bind(question.question_order)
+        .bind(question.time_limit.unwrap_or(30))
+        .bind(question.points.unwrap_or(100))
+        .fetch_one(&mut **tx)
+        .await?;
+
+        for option in &question.options {
+            sqlx::query(
+                "INSERT INTO kahoot_options (question_id, option_text, option_order, is_correct)
+                 VALUES ($1, $2, $3, $4)"
+            )
+            .bind(question_id)
+            .bind(&option.option_text)
+            .bind(option.option_order)
+            .bind(option.is_correct)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    Ok(game_id)
+}
+
+async fn insert_open_question(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i32,
+    open_question: &OpenQuestionExport,
+    game_password: &str,
+    presenter_password: &str,
+) -> Result<i32, sqlx::Error> {
+    let game_id: i32 = sqlx::query_scalar(
+        "INSERT INTO open_question_games (user_id, title, description, game_password, presenter_password)
+         VALUES ($1, $2, $3, $4, $5) RETURNING id"
+    )
+    .bind(user_id)
+    .bind(&open_question.title)
+    .bind(&open_question.description)
+    .bind(game_password)
+    .bind(presenter_password)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    for (index, question_text) in open_question.questions.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO open_question_questions (game_id, question_text, order_index, is_open)
+             VALUES ($1, $2, $3, false)"
+        )
+        .bind(game_id)
+        .bind(question_text)
+        .bind(index as i32)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(game_id)
+}
+
+fn extract_user_id(req: &HttpRequest) -> Option<i32> {
+    req.extensions().get::<i32>().copied()
+}
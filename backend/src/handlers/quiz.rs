@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::models::{
     QuizConfig, QuizQuestion, ErrorResponse, Game,
 };
+use crate::quiz_clock::PacingMode;
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateQuizConfigRequest {
@@ -17,6 +18,7 @@ pub struct CreateQuizConfigRequest {
     pub end_screen_button_url: Option<String>,
     pub end_screen_button_new_tab: Option<bool>,
     pub min_players: Option<i32>,
+    pub pacing_mode: Option<String>,  // self_paced, host_paced ou synchronized
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -30,6 +32,8 @@ pub struct CreateQuestionRequest {
     pub points: Option<i32>,
     pub order_number: Option<i32>,
     pub justification: Option<String>,
+    #[validate(range(min = 5, max = 600))]
+    pub time_limit: Option<i32>,
 }
 
 pub async fn create_quiz_config(
@@ -70,9 +74,13 @@ pub async fn create_quiz_config(
         });
     }
 
+    if let Err(e) = validate_pacing_mode(body.pacing_mode.as_deref()) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
     // Create quiz config
     let config = match sqlx::query_as::<_, QuizConfig>(
-        "INSERT INTO quiz_configs (game_id, time_limit, end_screen_text, end_screen_button_text, end_screen_button_url, end_screen_button_new_tab, min_players) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *"
+        "INSERT INTO quiz_configs (game_id, time_limit, end_screen_text, end_screen_button_text, end_screen_button_url, end_screen_button_new_tab, min_players, pacing_mode) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *"
     )
     .bind(body.game_id)
     .bind(body.time_limit)
@@ -81,6 +89,7 @@ pub async fn create_quiz_config(
     .bind(&body.end_screen_button_url)
     .bind(body.end_screen_button_new_tab)
     .bind(body.min_players)
+    .bind(body.pacing_mode.as_deref().unwrap_or("self_paced"))
     .fetch_one(pool.get_ref())
     .await {
         Ok(c) => c,
@@ -157,8 +166,8 @@ pub async fn create_question(
 
     let question = match sqlx::query_as::<_, QuizQuestion>(
         "INSERT INTO quiz_questions 
-         (quiz_config_id, question, option_a, option_b, option_c, option_d, correct_option, justification, points)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *"
+         (quiz_config_id, question, option_a, option_b, option_c, option_d, correct_option, justification, points, time_limit)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *"
     )
     .bind(quiz_config_id)
     .bind(&body.question_text)
@@ -169,6 +178,7 @@ pub async fn create_question(
     .bind(correct_option)
     .bind(&body.justification)
     .bind(body.points.unwrap_or(100))
+    .bind(body.time_limit)
     .fetch_one(pool.get_ref())
     .await {
         Ok(q) => q,
@@ -209,6 +219,7 @@ pub struct UpdateQuizConfigRequest {
     pub end_screen_button_url: Option<String>,
     pub end_screen_button_new_tab: Option<bool>,
     pub min_players: Option<i32>,
+    pub pacing_mode: Option<String>,
 }
 
 pub async fn update_quiz_config(
@@ -251,6 +262,10 @@ pub async fn update_quiz_config(
         }
     }
 
+    if let Err(e) = validate_pacing_mode(body.pacing_mode.as_deref()) {
+        return HttpResponse::BadRequest().json(ErrorResponse { error: e });
+    }
+
     // Update quiz config
     let config = match sqlx::query_as::<_, QuizConfig>(
         "UPDATE quiz_configs 
@@ -258,8 +273,9 @@ pub async fn update_quiz_config(
              end_screen_button_text = $2, 
              end_screen_button_url = $3,
              end_screen_button_new_tab = $4,
-             min_players = $5
-         WHERE game_id = $6 
+             min_players = $5,
+             pacing_mode = COALESCE($6, pacing_mode)
+         WHERE game_id = $7 
          RETURNING *"
     )
     .bind(&body.end_screen_text)
@@ -267,6 +283,7 @@ pub async fn update_quiz_config(
     .bind(&body.end_screen_button_url)
     .bind(body.end_screen_button_new_tab)
     .bind(body.min_players)
+    .bind(&body.pacing_mode)
     .bind(*game_id)
     .fetch_one(pool.get_ref())
     .await {
@@ -282,10 +299,45 @@ pub async fn update_quiz_config(
     HttpResponse::Ok().json(config)
 }
 
+// Visão de autoria (com correct_option e justification): apenas o dono do jogo
 pub async fn get_quiz_questions(
     pool: web::Data<PgPool>,
     game_id: web::Path<i32>,
+    req: HttpRequest,
 ) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let game_check = sqlx::query_scalar::<_, i32>(
+        "SELECT created_by FROM games WHERE id = $1"
+    )
+    .bind(*game_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match game_check {
+        Ok(Some(owner_id)) if owner_id == user_id => {},
+        Ok(Some(_)) => {
+            return HttpResponse::Forbidden().json(ErrorResponse {
+                error: "You don't have permission to view these questions".to_string(),
+            });
+        }
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: "Game not found".to_string(),
+            });
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    }
+
     // First get the quiz config
     let config = match sqlx::query_as::<_, QuizConfig>(
         "SELECT * FROM quiz_configs WHERE game_id = $1"
@@ -315,6 +367,168 @@ pub async fn get_quiz_questions(
     HttpResponse::Ok().json(questions)
 }
 
+// Pergunta como o jogador vê: sem correct_option e sem justification
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PlayQuizQuestion {
+    pub id: i32,
+    pub question: String,
+    pub option_a: String,
+    pub option_b: String,
+    pub option_c: String,
+    pub option_d: String,
+    pub points: i32,
+    pub time_limit: Option<i32>,
+}
+
+// GET /api/quiz/{game_id}/questions e /api/quiz/{game_id}/play
+pub async fn get_play_questions(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+) -> HttpResponse {
+    let config = match sqlx::query_as::<_, QuizConfig>(
+        "SELECT * FROM quiz_configs WHERE game_id = $1"
+    )
+    .bind(game_id.into_inner())
+    .fetch_one(pool.get_ref())
+    .await {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Quiz config not found".to_string(),
+        }),
+    };
+
+    let questions = match sqlx::query_as::<_, PlayQuizQuestion>(
+        "SELECT id, question, option_a, option_b, option_c, option_d, points, time_limit
+         FROM quiz_questions WHERE quiz_config_id = $1 ORDER BY id"
+    )
+    .bind(config.id)
+    .fetch_all(pool.get_ref())
+    .await {
+        Ok(q) => q,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    HttpResponse::Ok().json(questions)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuizReviewRequest {
+    // Respostas por índice da pergunta ("0" -> "A")
+    pub answers: serde_json::Map<String, serde_json::Value>,
+}
+
+// POST /api/quiz/{game_id}/review - Correção do quiz jogado fora de sala
+pub async fn review_quiz_answers(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    body: web::Json<QuizReviewRequest>,
+) -> HttpResponse {
+    let questions = match sqlx::query_as::<_, QuizQuestion>(
+        "SELECT qq.* FROM quiz_questions qq
+         JOIN quiz_configs qc ON qc.id = qq.quiz_config_id
+         WHERE qc.game_id = $1
+         ORDER BY qq.id"
+    )
+    .bind(*game_id)
+    .fetch_all(pool.get_ref())
+    .await {
+        Ok(q) => q,
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    if questions.is_empty() {
+        return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Quiz not found".to_string(),
+        });
+    }
+
+    // Sem nenhuma resposta não há correção (evita ler o gabarito antes de jogar)
+    if body.answers.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "No answers submitted".to_string(),
+        });
+    }
+
+    // Fora de sala não há tentativa registrada no servidor: só o total de acertos. A correção
+    // por pergunta permitiria montar o gabarito reenviando respostas diferentes.
+    let (correct_answers, total_score) = questions.iter().enumerate()
+        .filter(|(idx, q)| {
+            body.answers.get(&idx.to_string()).and_then(|v| v.as_str()) == Some(q.correct_option.trim())
+        })
+        .fold((0, 0), |(correct, score), (_, q)| (correct + 1, score + q.points));
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "total_questions": questions.len(),
+        "correct_answers": correct_answers,
+        "total_score": total_score,
+    }))
+}
+
+// Quais perguntas da revisão trazem correct_option e justification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewReveal {
+    // As respondidas pelo jogador
+    Answered,
+    // Todas (partida encerrada)
+    All,
+}
+
+// Monta a revisão: resposta correta, justificativa e resposta do jogador por pergunta
+pub fn build_quiz_review(
+    questions: &[QuizQuestion],
+    answers: Option<&serde_json::Map<String, serde_json::Value>>,
+    reveal: ReviewReveal,
+) -> serde_json::Value {
+    let mut total_score = 0;
+    let mut correct_answers = 0;
+
+    let review: Vec<serde_json::Value> = questions.iter().enumerate().map(|(idx, q)| {
+        let correct_option = q.correct_option.trim();
+        let player_answer = answers
+            .and_then(|a| a.get(&idx.to_string()))
+            .and_then(|v| v.as_str());
+        let is_correct = player_answer == Some(correct_option);
+        let points_earned = if is_correct { q.points } else { 0 };
+
+        if is_correct {
+            total_score += q.points;
+            correct_answers += 1;
+        }
+
+        let reveal = match reveal {
+            ReviewReveal::Answered => player_answer.is_some(),
+            ReviewReveal::All => true,
+        };
+
+        serde_json::json!({
+            "question_index": idx,
+            "question_id": q.id,
+            "question": q.question,
+            "option_a": q.option_a,
+            "option_b": q.option_b,
+            "option_c": q.option_c,
+            "option_d": q.option_d,
+            "correct_option": reveal.then_some(correct_option),
+            "justification": if reveal { q.justification.as_deref() } else { None },
+            "player_answer": player_answer,
+            "is_correct": is_correct,
+            "points": q.points,
+            "points_earned": points_earned,
+        })
+    }).collect();
+
+    serde_json::json!({
+        "total_questions": questions.len(),
+        "correct_answers": correct_answers,
+        "total_score": total_score,
+        "questions": review,
+    })
+}
+
 pub async fn delete_all_questions(
     pool: web::Data<PgPool>,
     game_id: web::Path<i32>,
@@ -374,6 +588,17 @@ pub async fn delete_all_questions(
     }
 }
 
+fn validate_pacing_mode(pacing_mode: Option<&str>) -> Result<(), String> {
+    match pacing_mode {
+        Some(mode) if PacingMode::parse(mode).is_none() => Err(format!(
+            "Invalid pacing_mode '{}'. Expected one of: {}",
+            mode,
+            PacingMode::ALL.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn extract_user_id(req: &HttpRequest) -> Option<i32> {
     req.extensions().get::<i32>().copied()
 }
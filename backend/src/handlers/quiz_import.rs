@@ -0,0 +1,818 @@
+use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
+use sqlx::PgPool;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{QuizQuestion, ErrorResponse};
+
+// Formatos aceitos na importação:
+// - "csv":   cabeçalho obrigatório com question, option_a, option_b, option_c, option_d,
+//            correct_option (A-D) e opcionalmente justification, points e time_limit. Separador "," ou ";".
+// - "json":  { "questions": [ { question_text, options: [4], correct_answer: 0-3, points?, justification?, time_limit? } ] }
+//            (mesmo formato do POST /quiz/{game_id}/questions)
+// - "gift":  formato GIFT do Moodle, apenas múltipla escolha com 4 alternativas
+// - "aiken": formato Aiken do Moodle, com 4 alternativas (A-D) e linha "ANSWER: X"
+#[derive(Debug, Deserialize)]
+pub struct ImportQuestionsRequest {
+    pub format: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ImportError {
+    // Linha do arquivo onde a pergunta começa (para JSON, a posição do item na lista)
+    line: usize,
+    message: String,
+}
+
+#[derive(Debug, Clone)]
+struct ParsedQuestion {
+    line: usize,
+    question: String,
+    options: Vec<String>,
+    correct_option: String,
+    justification: Option<String>,
+    points: Option<i32>,
+    time_limit: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonImport {
+    questions: Vec<JsonQuestion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonQuestion {
+    question_text: String,
+    options: Vec<String>,
+    correct_answer: i32,
+    points: Option<i32>,
+    justification: Option<String>,
+    time_limit: Option<i32>,
+}
+
+const OPTION_LETTERS: [&str; 4] = ["A", "B", "C", "D"];
+
+// POST /api/protected/quiz/{game_id}/import
+pub async fn import_questions(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    body: web::Json<ImportQuestionsRequest>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    // Verificar se o jogo existe e pertence ao usuário
+    let game_check = sqlx::query_scalar::<_, i32>(
+        "SELECT created_by FROM games WHERE id = $1"
+    )
+    .bind(*game_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match game_check {
+        Ok(Some(owner_id)) if owner_id == user_id => {},
+        Ok(Some(_)) => {
+            return HttpResponse::Forbidden().json(ErrorResponse {
+                error: "You don't have permission to import questions into this quiz".to_string(),
+            });
+        }
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: "Game not found".to_string(),
+            });
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    }
+
+    let quiz_config_id: i32 = match sqlx::query_scalar(
+        "SELECT id FROM quiz_configs WHERE game_id = $1"
+    )
+    .bind(*game_id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(Some(id)) => id,
+        Ok(None) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Quiz configuration not found".to_string(),
+        }),
+        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        }),
+    };
+
+    let parsed = match body.format.to_lowercase().as_str() {
+        "csv" => parse_csv(&body.content),
+        "json" => parse_json(&body.content),
+        "gift" => parse_gift(&body.content),
+        "aiken" => parse_aiken(&body.content),
+        other => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Unsupported import format: {}", other),
+        }),
+    };
+
+    let (questions, mut errors) = parsed;
+    for question in &questions {
+        errors.extend(validate_question(question));
+    }
+
+    if questions.is_empty() && errors.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "No questions found in the imported content".to_string(),
+        });
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Import failed: {} error(s) found", errors.len()),
+            "errors": errors,
+        }));
+    }
+
+    // Inserir todas as perguntas em uma única transação
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            log::error!("Error starting import transaction: {}", e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Database error: {}", e),
+            });
+        }
+    };
+
+    let mut created = Vec::with_capacity(questions.len());
+    for question in &questions {
+        let result = sqlx::query_as::<_, QuizQuestion>(
+            "INSERT INTO quiz_questions
+             (quiz_config_id, question, option_a, option_b, option_c, option_d, correct_option, justification, points, time_limit)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *"
+        )
+        .bind(quiz_config_id)
+        .bind(&question.question)
+        .bind(&question.options[0])
+        .bind(&question.options[1])
+        .bind(&question.options[2])
+        .bind(&question.options[3])
+        .bind(&question.correct_option)
+        .bind(&question.justification)
+        .bind(question.points.unwrap_or(100))
+        .bind(question.time_limit)
+        .fetch_one(&mut *tx)
+        .await;
+
+        match result {
+            Ok(q) => created.push(q),
+            Err(e) => {
+                log::error!("Error importing question from line {}: {}", question.line, e);
+                let _ = tx.rollback().await;
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Database error: {}", e),
+                    "errors": [ImportError {
+                        line: question.line,
+                        message: "Failed to save question".to_string(),
+                    }],
+                }));
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        log::error!("Error committing quiz import: {}", e);
+        return HttpResponse::InternalServerError().json(ErrorResponse {
+            error: format!("Database error: {}", e),
+        });
+    }
+
+    log::info!("Imported {} questions into quiz {} ({})", created.len(), game_id, body.format);
+
+    HttpResponse::Created().json(serde_json::json!({
+        "imported": created.len(),
+        "questions": created,
+    }))
+}
+
+// Mesmas regras de CreateQuestionRequest
+fn validate_question(question: &ParsedQuestion) -> Vec<ImportError> {
+    let mut errors = Vec::new();
+    let mut push = |message: String| errors.push(ImportError { line: question.line, message });
+
+    let length = question.question.chars().count();
+    if !(5..=500).contains(&length) {
+        push("Question text must have between 5 and 500 characters".to_string());
+    }
+    if question.options.len() != 4 {
+        push(format!("Must provide exactly 4 options, found {}", question.options.len()));
+    } else if question.options.iter().any(|o| o.trim().is_empty()) {
+        push("Options must not be empty".to_string());
+    }
+    if !OPTION_LETTERS.contains(&question.correct_option.as_str()) {
+        push(format!("Invalid correct option '{}', expected A, B, C or D", question.correct_option));
+    }
+    if let Some(points) = question.points {
+        if points < 0 {
+            push("Points must not be negative".to_string());
+        }
+    }
+    if let Some(time_limit) = question.time_limit {
+        if !(5..=600).contains(&time_limit) {
+            push("Time limit must be between 5 and 600 seconds".to_string());
+        }
+    }
+
+    errors
+}
+
+fn answer_letter(index: usize) -> String {
+    OPTION_LETTERS.get(index).map(|l| l.to_string()).unwrap_or_default()
+}
+
+// ============= CSV =============
+
+// Divide o conteúdo em registros respeitando aspas (campos podem ter quebras de linha).
+// Retorna a linha inicial de cada registro junto com os campos.
+fn split_csv_records(content: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, ImportError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(ImportError {
+            line: record_line,
+            message: "Unterminated quoted field".to_string(),
+        });
+    }
+
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        records.push((record_line, fields));
+    }
+
+    Ok(records)
+}
+
+fn parse_csv(content: &str) -> (Vec<ParsedQuestion>, Vec<ImportError>) {
+    let content = content.trim_start_matches('\u{feff}');
+    let header_line = content.lines().next().unwrap_or("");
+    // Planilhas em pt-BR exportam CSV com ";"
+    let delimiter = if header_line.matches(';').count() > header_line.matches(',').count() { ';' } else { ',' };
+
+    let records = match split_csv_records(content, delimiter) {
+        Ok(r) => r,
+        Err(e) => return (Vec::new(), vec![e]),
+    };
+
+    let mut iter = records.into_iter();
+    let (header_line, header) = match iter.next() {
+        Some(h) => h,
+        None => return (Vec::new(), Vec::new()),
+    };
+
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+
+    let required = ["question", "option_a", "option_b", "option_c", "option_d", "correct_option"];
+    let missing: Vec<&str> = required.iter().copied().filter(|name| column(name).is_none()).collect();
+    if !missing.is_empty() {
+        return (Vec::new(), vec![ImportError {
+            line: header_line,
+            message: format!("Missing required columns: {}", missing.join(", ")),
+        }]);
+    }
+
+    let question_col = column("question").unwrap();
+    let option_cols: Vec<usize> = ["option_a", "option_b", "option_c", "option_d"]
+        .iter()
+        .map(|name| column(name).unwrap())
+        .collect();
+    let correct_col = column("correct_option").unwrap();
+    let justification_col = column("justification");
+    let points_col = column("points");
+    let time_limit_col = column("time_limit");
+
+    let mut questions = Vec::new();
+    let mut errors = Vec::new();
+
+    for (line, fields) in iter {
+        let get = |idx: usize| fields.get(idx).map(|f| f.trim().to_string()).unwrap_or_default();
+
+        let points = match points_col.map(get).filter(|p| !p.is_empty()) {
+            Some(p) => match p.parse::<i32>() {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    errors.push(ImportError { line, message: format!("Invalid points value '{}'", p) });
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        let time_limit = match time_limit_col.map(get).filter(|t| !t.is_empty()) {
+            Some(t) => match t.parse::<i32>() {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    errors.push(ImportError { line, message: format!("Invalid time_limit value '{}'", t) });
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        questions.push(ParsedQuestion {
+            line,
+            question: get(question_col),
+            options: option_cols.iter().map(|&idx| get(idx)).collect(),
+            correct_option: get(correct_col).to_uppercase(),
+            justification: justification_col.map(get).filter(|j| !j.is_empty()),
+            points,
+            time_limit,
+        });
+    }
+
+    (questions, errors)
+}
+
+// ============= JSON =============
+
+fn parse_json(content: &str) -> (Vec<ParsedQuestion>, Vec<ImportError>) {
+    let import = match serde_json::from_str::<JsonImport>(content) {
+        Ok(i) => i,
+        Err(e) => return (Vec::new(), vec![ImportError {
+            line: e.line(),
+            message: format!("Invalid JSON: {}", e),
+        }]),
+    };
+
+    let questions = import.questions.into_iter().enumerate().map(|(idx, q)| {
+        ParsedQuestion {
+            line: idx + 1,
+            question: q.question_text.trim().to_string(),
+            options: q.options.into_iter().map(|o| o.trim().to_string()).collect(),
+            correct_option: usize::try_from(q.correct_answer).map(answer_letter).unwrap_or_default(),
+            justification: q.justification.filter(|j| !j.trim().is_empty()),
+            points: q.points,
+            time_limit: q.time_limit,
+        }
+    }).collect();
+
+    (questions, Vec::new())
+}
+
+// ============= GIFT =============
+
+// Remove escapes do GIFT (\~ \= \# \{ \} \:)
+fn gift_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(next) => out.push(next),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim().to_string()
+}
+
+// Encontra o próximo caractere especial não escapado
+fn gift_find_unescaped(text: &str, targets: &[char], from: usize) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in text[from..].char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if targets.contains(&c) {
+            return Some(from + idx);
+        }
+    }
+    None
+}
+
+fn parse_gift_block(line: usize, block: &str) -> Result<ParsedQuestion, ImportError> {
+    let error = |message: &str| ImportError { line, message: message.to_string() };
+
+    let mut text = block.trim();
+
+    // Título opcional ::Título::
+    if let Some(rest) = text.strip_prefix("::") {
+        match rest.find("::") {
+            Some(end) => text = rest[end + 2..].trim_start(),
+            None => return Err(error("Unterminated question title")),
+        }
+    }
+
+    // Formato de texto opcional [html], [moodle], [plain], [markdown]
+    if text.starts_with('[') {
+        if let Some(end) = text.find(']') {
+            text = text[end + 1..].trim_start();
+        }
+    }
+
+    let open = gift_find_unescaped(text, &['{'], 0).ok_or_else(|| error("Missing answer block '{...}'"))?;
+    let close = gift_find_unescaped(text, &['}'], open + 1).ok_or_else(|| error("Unterminated answer block"))?;
+
+    let question = format!("{} {}", gift_unescape(&text[..open]), gift_unescape(&text[close + 1..]))
+        .trim()
+        .to_string();
+    let mut answers = text[open + 1..close].trim();
+
+    // Feedback geral (####) vira a justificativa
+    let mut justification = None;
+    if let Some(idx) = answers.find("####") {
+        justification = Some(gift_unescape(&answers[idx + 4..])).filter(|j| !j.is_empty());
+        answers = answers[..idx].trim();
+    }
+
+    if answers.is_empty() || answers.eq_ignore_ascii_case("T") || answers.eq_ignore_ascii_case("F")
+        || answers.eq_ignore_ascii_case("TRUE") || answers.eq_ignore_ascii_case("FALSE")
+        || answers.starts_with('#')
+    {
+        return Err(error("Only multiple choice questions are supported"));
+    }
+
+    let mut options = Vec::new();
+    let mut correct = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = gift_find_unescaped(answers, &['=', '~'], pos) {
+        let end = gift_find_unescaped(answers, &['=', '~'], start + 1).unwrap_or(answers.len());
+        let marker = &answers[start..start + 1];
+        let mut option = &answers[start + 1..end];
+
+        // Feedback por alternativa (#texto) é descartado
+        if let Some(feedback) = gift_find_unescaped(option, &['#'], 0) {
+            option = &option[..feedback];
+        }
+
+        // Peso percentual (~%100%texto): 100% conta como correta
+        let mut is_correct = marker == "=";
+        let option = option.trim_start();
+        let option = if let Some(rest) = option.strip_prefix('%') {
+            match rest.find('%') {
+                Some(end) => {
+                    if rest[..end].trim() == "100" {
+                        is_correct = true;
+                    }
+                    &rest[end + 1..]
+                }
+                None => return Err(error("Invalid answer weight")),
+            }
+        } else {
+            option
+        };
+
+        if option.contains("->") {
+            return Err(error("Matching questions are not supported"));
+        }
+
+        if is_correct {
+            correct.push(options.len());
+        }
+        options.push(gift_unescape(option));
+        pos = end;
+    }
+
+    if options.is_empty() {
+        return Err(error("Only multiple choice questions are supported"));
+    }
+    if correct.len() != 1 {
+        return Err(error("Question must have exactly one correct answer"));
+    }
+
+    Ok(ParsedQuestion {
+        line,
+        question,
+        options,
+        correct_option: answer_letter(correct[0]),
+        justification,
+        points: None,
+        time_limit: None,
+    })
+}
+
+fn parse_gift(content: &str) -> (Vec<ParsedQuestion>, Vec<ImportError>) {
+    let mut questions = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut block = String::new();
+    let mut block_line = 0;
+
+    let mut flush = |block: &mut String, block_line: usize| {
+        if block.trim().is_empty() {
+            block.clear();
+            return;
+        }
+        match parse_gift_block(block_line, block) {
+            Ok(q) => questions.push(q),
+            Err(e) => errors.push(e),
+        }
+        block.clear();
+    };
+
+    for (idx, raw) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        let trimmed = raw.trim();
+        // Comentários e diretivas como $CATEGORY: são ignorados
+        if trimmed.starts_with("//") || trimmed.starts_with("$CATEGORY") {
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut block, block_line);
+            continue;
+        }
+        if block.is_empty() {
+            block_line = idx + 1;
+        }
+        block.push_str(raw);
+        block.push('\n');
+    }
+    flush(&mut block, block_line);
+
+    (questions, errors)
+}
+
+// ============= AIKEN =============
+
+fn parse_aiken_block(lines: &[(usize, &str)]) -> Result<ParsedQuestion, ImportError> {
+    let line = lines[0].0;
+    let error = |line: usize, message: String| ImportError { line, message };
+
+    let mut question_lines = Vec::new();
+    let mut options: Vec<(char, String)> = Vec::new();
+    let mut answer = None;
+
+    for &(line_no, text) in lines {
+        let upper = text.to_uppercase();
+        if let Some(rest) = upper.strip_prefix("ANSWER:") {
+            answer = Some((line_no, rest.trim().to_string()));
+            continue;
+        }
+
+        let mut chars = text.chars();
+        let letter = chars.next().map(|c| c.to_ascii_uppercase());
+        let separator = chars.next();
+        let is_option = matches!(letter, Some('A'..='Z'))
+            && matches!(separator, Some('.') | Some(')'))
+            && text[2..].starts_with(' ');
+
+        if is_option {
+            options.push((letter.unwrap(), text[2..].trim().to_string()));
+        } else if options.is_empty() {
+            question_lines.push(text.trim());
+        } else {
+            return Err(error(line_no, format!("Unexpected line after options: '{}'", text.trim())));
+        }
+    }
+
+    let (answer_line, answer) = answer.ok_or_else(|| error(line, "Missing 'ANSWER:' line".to_string()))?;
+
+    for (idx, (letter, _)) in options.iter().enumerate() {
+        if *letter as usize != 'A' as usize + idx {
+            return Err(error(line, format!("Options must be lettered in order, found '{}'", letter)));
+        }
+    }
+
+    let correct_index = options.iter().position(|(letter, _)| letter.to_string() == answer)
+        .ok_or_else(|| error(answer_line, format!("Answer '{}' does not match any option", answer)))?;
+
+    Ok(ParsedQuestion {
+        line,
+        question: question_lines.join(" "),
+        options: options.into_iter().map(|(_, text)| text).collect(),
+        correct_option: answer_letter(correct_index),
+        justification: None,
+        points: None,
+        time_limit: None,
+    })
+}
+
+fn parse_aiken(content: &str) -> (Vec<ParsedQuestion>, Vec<ImportError>) {
+    let mut questions = Vec::new();
+    let mut errors = Vec::new();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+
+    for (idx, raw) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        let text = raw.trim_end();
+        if text.trim().is_empty() {
+            continue;
+        }
+        block.push((idx + 1, text.trim_start()));
+
+        // No Aiken, cada pergunta termina na linha ANSWER:
+        if text.trim_start().to_uppercase().starts_with("ANSWER:") {
+            match parse_aiken_block(&block) {
+                Ok(q) => questions.push(q),
+                Err(e) => errors.push(e),
+            }
+            block.clear();
+        }
+    }
+
+    if !block.is_empty() {
+        errors.push(ImportError {
+            line: block[0].0,
+            message: "Missing 'ANSWER:' line".to_string(),
+        });
+    }
+
+    (questions, errors)
+}
+
+fn extract_user_id(req: &HttpRequest) -> Option<i32> {
+    req.extensions().get::<i32>().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (linha, mensagem) de cada erro
+    fn errors(errors: &[ImportError]) -> Vec<(usize, &str)> {
+        errors.iter().map(|e| (e.line, e.message.as_str())).collect()
+    }
+
+    #[test]
+    fn csv_quoted_fields() {
+        let content = "question,option_a,option_b,option_c,option_d,correct_option,points\n\
+            \"Qual é a capital, do Brasil?\",Brasília,\"Rio de \"\"Janeiro\"\"\",São Paulo,Salvador,a,10\n\
+            \"Pergunta em\nduas linhas\",1,2,3,4,B,\n\
+            Quanto é 2+2?,3,4,5,6,B,dez\n";
+        let (questions, errs) = parse_csv(content);
+
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].line, 2);
+        assert_eq!(questions[0].question, "Qual é a capital, do Brasil?");
+        assert_eq!(questions[0].options, vec!["Brasília", "Rio de \"Janeiro\"", "São Paulo", "Salvador"]);
+        assert_eq!(questions[0].correct_option, "A");
+        assert_eq!(questions[0].points, Some(10));
+        assert_eq!(questions[1].line, 3);
+        assert_eq!(questions[1].question, "Pergunta em\nduas linhas");
+        assert_eq!(questions[1].points, None);
+        // A pergunta de duas linhas ocupa as linhas 3 e 4
+        assert_eq!(errors(&errs), vec![(5, "Invalid points value 'dez'")]);
+    }
+
+    #[test]
+    fn csv_semicolon_and_errors() {
+        let (questions, errs) = parse_csv("question;option_a;option_b;option_c;option_d;correct_option\nCor do céu?;Azul;Verde;Roxo;Preto;A\n");
+        assert!(errs.is_empty());
+        assert_eq!(questions[0].options, vec!["Azul", "Verde", "Roxo", "Preto"]);
+
+        let (_, errs) = parse_csv("question,option_a,option_b\nPergunta?,1,2\n");
+        assert_eq!(errors(&errs), vec![(1, "Missing required columns: option_c, option_d, correct_option")]);
+
+        let (_, errs) = parse_csv("question,option_a,option_b,option_c,option_d,correct_option\nOk?,1,2,3,4,A\n\"Sem fim,1,2,3,4,A\n");
+        assert_eq!(errors(&errs), vec![(3, "Unterminated quoted field")]);
+    }
+
+    #[test]
+    fn gift_options_and_comments() {
+        let content = "// Comentário antes da pergunta\n\
+            $CATEGORY: geografia\n\
+            \n\
+            ::Capital::Qual é a capital do Brasil? {\n\
+            ~São Paulo#Não é a capital\n\
+            =Brasília\n\
+            ~Rio de Janeiro\n\
+            ~Salvador\n\
+            ####Brasília é a capital desde 1960.\n\
+            }\n\
+            \n\
+            // Peso de 100% também marca a correta\n\
+            Quanto é 2 \\= 2? {~%0%1 ~%100%4 ~2 ~3}\n";
+        let (questions, errs) = parse_gift(content);
+
+        assert!(errs.is_empty(), "{:?}", errs);
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].line, 4);
+        assert_eq!(questions[0].question, "Qual é a capital do Brasil?");
+        assert_eq!(questions[0].options, vec!["São Paulo", "Brasília", "Rio de Janeiro", "Salvador"]);
+        assert_eq!(questions[0].correct_option, "B");
+        assert_eq!(questions[0].justification.as_deref(), Some("Brasília é a capital desde 1960."));
+        assert_eq!(questions[1].line, 13);
+        assert_eq!(questions[1].question, "Quanto é 2 = 2?");
+        assert_eq!(questions[1].options, vec!["1", "4", "2", "3"]);
+        assert_eq!(questions[1].correct_option, "B");
+    }
+
+    #[test]
+    fn gift_errors() {
+        let content = "Duas corretas? {=A =B ~C ~D}\n\
+            \n\
+            // Verdadeiro ou falso não é aceito\n\
+            O céu é azul. {T}\n\
+            \n\
+            Sem respostas\n";
+        let (questions, errs) = parse_gift(content);
+
+        assert!(questions.is_empty());
+        assert_eq!(errors(&errs), vec![
+            (1, "Question must have exactly one correct answer"),
+            (4, "Only multiple choice questions are supported"),
+            (6, "Missing answer block '{...}'"),
+        ]);
+    }
+
+    #[test]
+    fn aiken_questions() {
+        let content = "Qual é a capital do Brasil?\n\
+            A. São Paulo\n\
+            B. Brasília\n\
+            C) Rio de Janeiro\n\
+            D. Salvador\n\
+            ANSWER: B\n\
+            \n\
+            Quanto é 2+2?\n\
+            A. 3\n\
+            B. 4\n\
+            C. 5\n\
+            D. 6\n\
+            ANSWER: E\n";
+        let (questions, errs) = parse_aiken(content);
+
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].line, 1);
+        assert_eq!(questions[0].options, vec!["São Paulo", "Brasília", "Rio de Janeiro", "Salvador"]);
+        assert_eq!(questions[0].correct_option, "B");
+        assert_eq!(errors(&errs), vec![(13, "Answer 'E' does not match any option")]);
+    }
+
+    #[test]
+    fn aiken_missing_answer() {
+        // Sem ANSWER:, a pergunta seguinte cai no mesmo bloco
+        let content = "Primeira pergunta?\n\
+            A. 1\n\
+            B. 2\n\
+            C. 3\n\
+            D. 4\n\
+            \n\
+            Segunda pergunta?\n\
+            A. 1\n\
+            B. 2\n\
+            C. 3\n\
+            D. 4\n\
+            ANSWER: A\n\
+            \n\
+            Terceira pergunta?\n\
+            A. 1\n\
+            B. 2\n";
+        let (questions, errs) = parse_aiken(content);
+
+        assert!(questions.is_empty());
+        assert_eq!(errors(&errs), vec![
+            (7, "Unexpected line after options: 'Segunda pergunta?'"),
+            (14, "Missing 'ANSWER:' line"),
+        ]);
+    }
+}
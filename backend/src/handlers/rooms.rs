@@ -4,13 +4,23 @@ use validator::Validate;
 use serde::Deserialize;
 use rand::Rng;
 use chrono::{Utc, Duration};
+use std::collections::HashMap;
 
 use crate::models::{
     CreateRoomRequest, JoinRoomRequest, JoinRoomAnonymousRequest, 
     AnonymousSessionResponse, GameRoom, RoomParticipant, 
     RoomAnswer, SubmitRoomAnswerRequest, RoomDetailsResponse,
-    RoomParticipantInfo, Game, User, RoomFoundWord, RoomPlayerScore
+    RoomParticipantInfo, Game, User, 
+    SpectateRoomRequest, SpectatorClaims, SpectatorSessionResponse, UpdateRoomTeamsRequest
 };
+use crate::handlers::quiz::ReviewReveal;
+use crate::middleware;
+use crate::room_expiry;
+use crate::room_hub::RoomRegistry;
+use crate::room_status::{self, RoomStatus, StatusChange, StatusError};
+use crate::room_teams::{self, TeamAssignment, TeamError};
+use crate::websocket::WsMessage;
+use crate::word_search_mode::{self, WordSearchMode};
 
 // Cores disponíveis para jogadores
 const PLAYER_COLORS: &[&str] = &[
@@ -30,13 +40,13 @@ fn extract_user_id(req: &HttpRequest) -> Option<i32> {
 }
 
 // Atribuir cor ao jogador baseada no user_id (determinístico, sem race condition)
-fn assign_player_color(user_id: i32) -> String {
+pub fn assign_player_color(user_id: i32) -> String {
     let index = (user_id as usize) % PLAYER_COLORS.len();
     PLAYER_COLORS[index].to_string()
 }
 
 // Atribuir cor a jogador anônimo baseada no hash do session_id (sem race condition)
-fn assign_player_color_anon(session_id: &str) -> String {
+pub fn assign_player_color_anon(session_id: &str) -> String {
     let hash: usize = session_id.bytes().fold(0usize, |acc, b| acc.wrapping_add(b as usize));
     PLAYER_COLORS[hash % PLAYER_COLORS.len()].to_string()
 }
@@ -96,35 +106,63 @@ pub async fn create_room(
         })),
     };
 
-    let user = match get_user_from_id(user_id, pool.get_ref()).await {
+    match insert_room(pool.get_ref(), user_id, &body).await {
+        Ok(room) => HttpResponse::Ok().json(room),
+        Err(response) => response,
+    }
+}
+
+// Cria a sala com o usuário como host (também usada pelas sessões legadas)
+pub async fn insert_room(pool: &PgPool, user_id: i32, body: &CreateRoomRequest) -> Result<GameRoom, HttpResponse> {
+    let user = match get_user_from_id(user_id, pool).await {
         Ok(user) => user,
-        Err(e) => return e,
+        Err(e) => return Err(e),
     };
 
     if let Err(e) = body.validate() {
-        return HttpResponse::BadRequest().json(serde_json::json!({
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
             "error": format!("Validation error: {}", e)
-        }));
+        })));
+    }
+
+    if let Err(e) = room_teams::check_teams(body.teams.as_deref().unwrap_or_default()) {
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        })));
     }
 
+    let mode = match body.word_search_mode.as_deref() {
+        None => WordSearchMode::default(),
+        Some(mode) => match WordSearchMode::parse(mode) {
+            Some(mode) => mode,
+            None => return Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!(
+                    "Invalid word_search_mode '{}'. Expected one of: {}",
+                    mode,
+                    WordSearchMode::ALL.join(", ")
+                )
+            }))),
+        },
+    };
+
     // Verificar se o jogo existe
     let game = sqlx::query_as::<_, Game>(
         "SELECT * FROM games WHERE id = $1 AND is_active = true"
     )
     .bind(body.game_id)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(pool)
     .await;
 
     if let Err(e) = game {
-        return HttpResponse::InternalServerError().json(serde_json::json!({
+        return Err(HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Database error: {}", e)
-        }));
+        })));
     }
 
     if game.unwrap().is_none() {
-        return HttpResponse::NotFound().json(serde_json::json!({
+        return Err(HttpResponse::NotFound().json(serde_json::json!({
             "error": "Game not found or inactive"
-        }));
+        })));
     }
 
     // Verificar se já existe sala com esse nome no mesmo jogo
@@ -133,16 +171,16 @@ pub async fn create_room(
     )
     .bind(body.game_id)
     .bind(&body.room_name)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(pool)
     .await;
 
     match existing_name {
-        Ok(Some(_)) => return HttpResponse::Conflict().json(serde_json::json!({
+        Ok(Some(_)) => return Err(HttpResponse::Conflict().json(serde_json::json!({
             "error": "Já existe uma sala ativa com esse nome neste jogo"
-        })),
-        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+        }))),
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Database error: {}", e)
-        })),
+        }))),
         _ => {}
     }
 
@@ -153,15 +191,15 @@ pub async fn create_room(
             "SELECT * FROM game_rooms WHERE room_code = $1"
         )
         .bind(&room_code)
-        .fetch_optional(pool.get_ref())
+        .fetch_optional(pool)
         .await;
 
         match existing {
             Ok(None) => break,
             Ok(Some(_)) => room_code = generate_room_code(),
-            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Database error: {}", e)
-            })),
+            }))),
         }
     }
 
@@ -169,9 +207,9 @@ pub async fn create_room(
     let password_hash = if let Some(password) = &body.password {
         match bcrypt::hash(password, bcrypt::DEFAULT_COST) {
             Ok(hash) => Some(hash),
-            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Password hashing error: {}", e)
-            })),
+            }))),
         }
     } else {
         None
@@ -187,8 +225,8 @@ pub async fn create_room(
 
     // Criar sala
     let room = sqlx::query_as::<_, GameRoom>(
-        "INSERT INTO game_rooms (game_id, room_code, room_name, password_hash, max_players, created_by, expires_at, game_seed)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "INSERT INTO game_rooms (game_id, room_code, room_name, password_hash, max_players, created_by, expires_at, game_seed, time_limit, word_search_mode)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
          RETURNING *"
     )
     .bind(body.game_id)
@@ -199,7 +237,9 @@ pub async fn create_room(
     .bind(user.id)
     .bind(expires_at)
     .bind(&game_seed)
-    .fetch_one(pool.get_ref())
+    .bind(body.time_limit)
+    .bind(mode.as_str())
+    .fetch_one(pool)
     .await;
 
     match room {
@@ -214,16 +254,26 @@ pub async fn create_room(
             .bind(room.id)
             .bind(user.id)
             .bind(&color)
-            .execute(pool.get_ref())
+            .execute(pool)
             .await;
 
-            HttpResponse::Ok().json(room)
+            // Modo equipes
+            if let Some(teams) = &body.teams {
+                if let Err(e) = room_teams::replace_teams(pool, room.id, teams).await {
+                    log::error!("Failed to create teams for room {}: {}", room.id, e.message());
+                    return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": format!("Failed to create teams: {}", e.message())
+                    })));
+                }
+            }
+
+            Ok(room)
         },
         Err(e) => {
             log::error!("Failed to create room: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Failed to create room: {}", e)
-            }))
+            })))
         },
     }
 }
@@ -233,6 +283,7 @@ pub async fn join_room(
     req: HttpRequest,
     body: web::Json<JoinRoomRequest>,
     pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
 ) -> HttpResponse {
     let user_id = match extract_user_id(&req) {
         Some(id) => id,
@@ -319,34 +370,74 @@ pub async fn join_room(
     .fetch_one(pool.get_ref())
     .await;
 
-    match result {
-        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-            "message": "Joined room successfully",
-            "room": room
-        })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+    let participant = match result {
+        Ok(participant) => participant,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Failed to join room: {}", e)
         })),
+    };
+
+    let team = match assign_team(pool.get_ref(), registry.get_ref(), room.id, participant.id, body.team_id).await {
+        Ok(team) => team,
+        Err(response) => return response,
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "message": "Joined room successfully",
+        "room": room,
+        "team": team
+    }))
+}
+
+// Modo equipes: coloca o participante na equipe escolhida (ou na menor) e avisa a sala
+async fn assign_team(
+    pool: &PgPool,
+    registry: &RoomRegistry,
+    room_id: i32,
+    participant_id: i32,
+    requested: Option<i32>,
+) -> Result<Option<TeamAssignment>, HttpResponse> {
+    match room_teams::join_team(pool, room_id, participant_id, requested).await {
+        Ok(Some((assignment, changed))) => {
+            if changed {
+                publish_teams(pool, registry, room_id, vec![assignment.clone()]).await;
+            }
+            Ok(Some(assignment))
+        },
+        Ok(None) => Ok(None),
+        Err(TeamError::InvalidTeam) => Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": TeamError::InvalidTeam.message()
+        }))),
+        Err(TeamError::Locked) => Err(HttpResponse::Conflict().json(serde_json::json!({
+            "error": TeamError::Locked.message(),
+            "code": "teams_locked"
+        }))),
+        Err(TeamError::Database(e)) => Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to assign team: {}", e)
+        }))),
     }
 }
 
-// Entrar em sala anonimamente (sem cadastro)
-pub async fn join_room_anonymous(
-    body: web::Json<JoinRoomAnonymousRequest>,
+// TeamsUpdated para a sala (equipes atuais e jogadores que mudaram de equipe)
+async fn publish_teams(pool: &PgPool, registry: &RoomRegistry, room_id: i32, assignments: Vec<TeamAssignment>) {
+    match room_teams::load_teams(pool, room_id).await {
+        Ok(teams) => registry.publish(room_id, &WsMessage::TeamsUpdated { teams, assignments }),
+        Err(e) => log::error!("Failed to load teams for room {}: {}", room_id, e),
+    }
+}
+
+// Acompanhar sala como espectador (projetor): não entra em room_participants
+// nem conta para max_players. Retorna um token para o WebSocket (?spectator_token=).
+pub async fn spectate_room(
+    body: web::Json<SpectateRoomRequest>,
     pool: web::Data<PgPool>,
-    room_manager: web::Data<crate::websocket::RoomManager>,
 ) -> HttpResponse {
-    log::info!("join_room_anonymous called with room_code: {}, player_name: {}", 
-        body.room_code, body.player_name);
-    
     if let Err(e) = body.validate() {
-        log::error!("Validation error: {}", e);
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": format!("Validation error: {}", e)
         }));
     }
 
-    // Buscar sala pelo código
     let room = sqlx::query_as::<_, GameRoom>(
         "SELECT * FROM game_rooms WHERE room_code = $1"
     )
@@ -364,14 +455,12 @@ pub async fn join_room_anonymous(
         })),
     };
 
-    // Verificar se sala está ativa
     if !room.is_active {
         return HttpResponse::Gone().json(serde_json::json!({
             "error": "Room is closed"
         }));
     }
 
-    // Verificar expiração
     if let Some(expires_at) = room.expires_at {
         if expires_at < Utc::now() {
             return HttpResponse::Gone().json(serde_json::json!({
@@ -380,6 +469,115 @@ pub async fn join_room_anonymous(
         }
     }
 
+    // Mesma senha dos jogadores
+    if let Some(password_hash) = &room.password_hash {
+        let password = body.password.as_deref().unwrap_or("");
+        match bcrypt::verify(password, password_hash) {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid password"
+            })),
+            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Password verification error: {}", e)
+            })),
+        }
+    }
+
+    // Válido até a sala expirar (no máximo 12 horas)
+    let max_expiration = Utc::now() + Duration::hours(12);
+    let expiration = room.expires_at
+        .map(|expires_at| expires_at.min(max_expiration))
+        .unwrap_or(max_expiration)
+        .timestamp() as usize;
+
+    let claims = SpectatorClaims {
+        room_id: room.id,
+        role: "spectator".to_string(),
+        exp: expiration,
+    };
+
+    let jwt_secret = middleware::jwt_secret();
+    let token = match jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(jwt_secret.as_bytes()),
+    ) {
+        Ok(token) => token,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Error creating token: {}", e)
+        })),
+    };
+
+    HttpResponse::Ok().json(SpectatorSessionResponse {
+        spectator_token: token,
+        room_id: room.id,
+        room_code: room.room_code,
+        game_id: room.game_id,
+    })
+}
+
+// Entrar em sala anonimamente (sem cadastro)
+pub async fn join_room_anonymous(
+    body: web::Json<JoinRoomAnonymousRequest>,
+    pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
+) -> HttpResponse {
+    match join_anonymous(pool.get_ref(), registry.get_ref(), &body).await {
+        Ok(session) => HttpResponse::Ok().json(session),
+        Err(response) => response,
+    }
+}
+
+// Cria (ou retoma) a sessão do jogador anônimo (também usada pelas sessões legadas)
+pub async fn join_anonymous(
+    pool: &PgPool,
+    registry: &RoomRegistry,
+    body: &JoinRoomAnonymousRequest,
+) -> Result<AnonymousSessionResponse, HttpResponse> {
+    log::info!("join_room_anonymous called with room_code: {}, player_name: {}", 
+        body.room_code, body.player_name);
+    
+    if let Err(e) = body.validate() {
+        log::error!("Validation error: {}", e);
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        })));
+    }
+
+    // Buscar sala pelo código
+    let room = sqlx::query_as::<_, GameRoom>(
+        "SELECT * FROM game_rooms WHERE room_code = $1"
+    )
+    .bind(&body.room_code)
+    .fetch_optional(pool)
+    .await;
+
+    let room = match room {
+        Ok(Some(room)) => room,
+        Ok(None) => return Err(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Room not found"
+        }))),
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        }))),
+    };
+
+    // Verificar se sala está ativa
+    if !room.is_active {
+        return Err(HttpResponse::Gone().json(serde_json::json!({
+            "error": "Room is closed"
+        })));
+    }
+
+    // Verificar expiração
+    if let Some(expires_at) = room.expires_at {
+        if expires_at < Utc::now() {
+            return Err(HttpResponse::Gone().json(serde_json::json!({
+                "error": "Room has expired"
+            })));
+        }
+    }
+
     // Verificar senha
     if let Some(password_hash) = &room.password_hash {
         let empty_password = String::new();
@@ -391,49 +589,57 @@ pub async fn join_room_anonymous(
             },
             Ok(false) => {
                 log::warn!("Invalid password for room {}", room.room_code);
-                return HttpResponse::Unauthorized().json(serde_json::json!({
+                return Err(HttpResponse::Unauthorized().json(serde_json::json!({
                     "error": "Invalid password"
-                }));
+                })));
             },
             Err(e) => {
                 log::error!("Password verification error: {}", e);
-                return HttpResponse::InternalServerError().json(serde_json::json!({
+                return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                     "error": format!("Password verification error: {}", e)
-                }));
+                })));
             },
         }
     }
 
     // Verificar se é uma reconexão via existing_session_id
     if let Some(ref existing_id) = body.existing_session_id {
-        let existing_session = sqlx::query_as::<_, (String, String)>(
-            "SELECT session_id, player_color 
+        let existing_session = sqlx::query_as::<_, (i32, String, String)>(
+            "SELECT id, session_id, player_color 
              FROM room_participants 
              WHERE room_id = $1 AND session_id = $2
              LIMIT 1"
         )
         .bind(room.id)
         .bind(existing_id)
-        .fetch_optional(pool.get_ref())
+        .fetch_optional(pool)
         .await;
 
         // Se encontrou sessão existente válida, retornar ela
-        if let Ok(Some((session_id, player_color))) = existing_session {
-            return HttpResponse::Ok().json(AnonymousSessionResponse {
+        if let Ok(Some((player_id, session_id, player_color))) = existing_session {
+            let team = match assign_team(pool, registry, room.id, player_id, body.team_id).await {
+                Ok(team) => team,
+                Err(response) => return Err(response),
+            };
+            let (player_color, team_id) = match team {
+                Some(team) => (team.player_color, team.team_id),
+                None => (player_color, None),
+            };
+
+            return Ok(AnonymousSessionResponse {
                 session_id,
+                player_id,
                 room_id: room.id,
                 room_code: room.room_code,
                 player_name: body.player_name.clone(),
                 player_color,
+                team_id,
             });
         }
     }
 
-    // Verificar limite de participantes baseado em conexões WebSocket ativas
-    let active_connections = {
-        let manager = room_manager.lock().unwrap();
-        manager.get(&room.id).map(|conns| conns.len()).unwrap_or(0)
-    };
+    // Conexões WebSocket ativas nesta instância (só para o início do timer)
+    let active_connections = registry.player_count(room.id);
 
     log::info!("Room {} has {} active WebSocket connections out of {} max", 
         room.room_code, active_connections, room.max_players);
@@ -445,7 +651,7 @@ pub async fn join_room_anonymous(
             "SELECT paused_at, total_pause_duration FROM game_rooms WHERE id = $1"
         )
         .bind(room.id)
-        .fetch_one(pool.get_ref())
+        .fetch_one(pool)
         .await;
 
         if let Ok((paused_at, total_pause)) = pause_info {
@@ -462,7 +668,7 @@ pub async fn join_room_anonymous(
                 )
                 .bind(total_pause_duration)
                 .bind(room.id)
-                .execute(pool.get_ref())
+                .execute(pool)
                 .await;
                 
                 log::info!("Room {} resumed. Pause duration: {}s, Total pause: {}s", 
@@ -473,7 +679,7 @@ pub async fn join_room_anonymous(
                     "UPDATE game_rooms SET started_at = NOW() WHERE id = $1 AND started_at IS NULL"
                 )
                 .bind(room.id)
-                .execute(pool.get_ref())
+                .execute(pool)
                 .await;
                 
                 log::info!("Room {} timer started", room.room_code);
@@ -481,11 +687,25 @@ pub async fn join_room_anonymous(
         }
     }
 
-    if active_connections >= room.max_players as usize {
-        log::warn!("Room {} is full ({}/{})", room.room_code, active_connections, room.max_players);
-        return HttpResponse::Forbidden().json(serde_json::json!({
+    // Limite de jogadores pelos participantes gravados (vale para todas as instâncias)
+    let participants = match sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM room_participants WHERE room_id = $1"
+    )
+    .bind(room.id)
+    .fetch_one(pool)
+    .await
+    {
+        Ok(count) => count,
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        }))),
+    };
+
+    if participants >= room.max_players as i64 {
+        log::warn!("Room {} is full ({}/{})", room.room_code, participants, room.max_players);
+        return Err(HttpResponse::Forbidden().json(serde_json::json!({
             "error": "Room is full"
-        }));
+        })));
     }
 
     // Gerar session_id único
@@ -494,40 +714,60 @@ pub async fn join_room_anonymous(
     // Atribuir cor ao jogador anônimo pelo hash do session_id (sem race condition)
     let player_color = assign_player_color_anon(&session_id);
 
-    // Adicionar participante anônimo (opcional, apenas para histórico)
-    let result = sqlx::query(
+    // Adicionar participante anônimo (o id vira o player_id no WebSocket)
+    let result = sqlx::query_scalar::<_, i32>(
         "INSERT INTO room_participants (room_id, session_id, player_name, is_host, player_color)
-         VALUES ($1, $2, $3, false, $4)"
+         VALUES ($1, $2, $3, false, $4)
+         RETURNING id"
     )
     .bind(room.id)
     .bind(&session_id)
     .bind(&body.player_name)
     .bind(&player_color)
-    .execute(pool.get_ref())
+    .fetch_one(pool)
     .await;
 
-    if result.is_err() {
-        return HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to join room: {}", result.unwrap_err())
-        }));
-    }
+    let player_id = match result {
+        Ok(id) => id,
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to join room: {}", e)
+        }))),
+    };
+
+    // Modo equipes: a cor do jogador passa a ser a da equipe
+    let team = match assign_team(pool, registry, room.id, player_id, body.team_id).await {
+        Ok(team) => team,
+        Err(response) => {
+            let _ = sqlx::query("DELETE FROM room_participants WHERE id = $1")
+                .bind(player_id)
+                .execute(pool)
+                .await;
+            return Err(response);
+        }
+    };
+    let (player_color, team_id) = match team {
+        Some(team) => (team.player_color, team.team_id),
+        None => (player_color, None),
+    };
 
     // Criar registro inicial em room_player_scores para exibir o jogador com 0 pontos
     log::info!("🎯 Criando registro inicial de score - room_id: {}, session_id: {}, player_name: {}, color: {}", 
         room.id, session_id, body.player_name, player_color);
     
     let score_insert_result = sqlx::query(
-        "INSERT INTO room_player_scores (room_id, session_id, player_name, player_color, words_found, total_score)
-         VALUES ($1, $2, $3, $4, 0, 0)
+        "INSERT INTO room_player_scores (room_id, session_id, player_name, player_color, words_found, total_score, team_id)
+         VALUES ($1, $2, $3, $4, 0, 0, $5)
          ON CONFLICT (room_id, session_id) DO UPDATE SET 
             player_name = EXCLUDED.player_name,
-            player_color = EXCLUDED.player_color"
+            player_color = EXCLUDED.player_color,
+            team_id = EXCLUDED.team_id"
     )
     .bind(room.id)
     .bind(&session_id)
     .bind(&body.player_name)
     .bind(&player_color)
-    .execute(pool.get_ref())
+    .bind(team_id)
+    .execute(pool)
     .await;
 
     match score_insert_result {
@@ -535,12 +775,14 @@ pub async fn join_room_anonymous(
         Err(e) => log::error!("❌ Erro ao criar registro de score: {}", e),
     }
 
-    HttpResponse::Ok().json(AnonymousSessionResponse {
+    Ok(AnonymousSessionResponse {
         session_id,
+        player_id,
         room_id: room.id,
         room_code: room.room_code,
         player_name: body.player_name.clone(),
         player_color,
+        team_id,
     })
 }
 
@@ -582,9 +824,13 @@ pub async fn get_room_info_public(
         })),
     };
 
+    // Equipes (modo equipes) para o jogador escolher ao entrar
+    let teams = room_teams::load_teams(pool.get_ref(), room.id).await.unwrap_or_default();
+
     HttpResponse::Ok().json(serde_json::json!({
         "room": room,
-        "game": game
+        "game": game,
+        "teams": teams
     }))
 }
 
@@ -626,9 +872,13 @@ pub async fn get_room_info_by_id_public(
         })),
     };
 
+    // Equipes (modo equipes) para o jogador escolher ao entrar
+    let teams = room_teams::load_teams(pool.get_ref(), room.id).await.unwrap_or_default();
+
     HttpResponse::Ok().json(serde_json::json!({
         "room": room,
-        "game": game
+        "game": game,
+        "teams": teams
     }))
 }
 
@@ -954,6 +1204,7 @@ pub async fn close_room(
     req: HttpRequest,
     room_id: web::Path<i32>,
     pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
 ) -> HttpResponse {
     let user_id = match extract_user_id(&req) {
         Some(id) => id,
@@ -986,24 +1237,294 @@ pub async fn close_room(
         _ => {}
     }
 
-    // Desativar sala
+    // Finalizar a partida, guardar os resultados e desativar a sala (estado archived)
+    match room_expiry::close_room(pool.get_ref(), registry.get_ref(), *room_id, room_status::REASON_HOST, &user.name).await {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "message": "Room closed successfully"
+        })),
+        Err(e) => status_error_response(e),
+    }
+}
+
+fn status_error_response(error: StatusError) -> HttpResponse {
+    match error {
+        StatusError::NotFound => HttpResponse::NotFound().json(serde_json::json!({
+            "error": error.message()
+        })),
+        StatusError::InvalidTransition { .. } => HttpResponse::Conflict().json(serde_json::json!({
+            "error": error.message()
+        })),
+        StatusError::Database(_) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": error.message()
+        })),
+    }
+}
+
+// Criador da sala ou participante marcado como host
+async fn is_room_host(pool: &PgPool, room_id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM game_rooms WHERE id = $1 AND created_by = $2)
+             OR EXISTS(SELECT 1 FROM room_participants WHERE room_id = $1 AND user_id = $2 AND is_host = true)"
+    )
+    .bind(room_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StartRoomRequest {
+    countdown_seconds: Option<i32>,
+}
+
+// Iniciar a partida (lobby -> countdown/running)
+pub async fn start_room(
+    req: HttpRequest,
+    room_id: web::Path<i32>,
+    body: Option<web::Json<StartRoomRequest>>,
+    pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
+) -> HttpResponse {
+    let countdown_seconds = body.and_then(|b| b.into_inner().countdown_seconds).unwrap_or(0);
+    let change = if countdown_seconds > 0 {
+        StatusChange::Countdown { seconds: countdown_seconds.min(room_status::MAX_COUNTDOWN_SECS) }
+    } else {
+        StatusChange::Start
+    };
+    host_status_change(req, *room_id, change, pool, registry).await
+}
+
+// Encerrar a partida (running/paused -> finished)
+pub async fn end_room(
+    req: HttpRequest,
+    room_id: web::Path<i32>,
+    pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
+) -> HttpResponse {
+    host_status_change(req, *room_id, StatusChange::End { reason: room_status::REASON_HOST }, pool, registry).await
+}
+
+async fn host_status_change(
+    req: HttpRequest,
+    room_id: i32,
+    change: StatusChange,
+    pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Unauthorized"
+        })),
+    };
+
+    match change_status_as_host(pool.get_ref(), registry.get_ref(), user_id, room_id, change).await {
+        Ok(previous) => HttpResponse::Ok().json(serde_json::json!({
+            "room_id": room_id,
+            "status": change.target().as_str(),
+            "previous_status": previous.as_str()
+        })),
+        Err(response) => response,
+    }
+}
+
+// Transição pedida pelo host da sala; retorna o estado anterior (também usada pelas sessões legadas)
+pub async fn change_status_as_host(
+    pool: &PgPool,
+    registry: &RoomRegistry,
+    user_id: i32,
+    room_id: i32,
+    change: StatusChange,
+) -> Result<RoomStatus, HttpResponse> {
+    let user = get_user_from_id(user_id, pool).await?;
+
+    match is_room_host(pool, room_id, user.id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You are not the host of this room"
+        }))),
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        }))),
+    }
+
+    room_status::change(pool, registry, room_id, change, &user.name).await
+        .map_err(status_error_response)
+}
+
+// Equipes da sala (vazio = sem modo equipes)
+pub async fn get_room_teams(
+    room_id: web::Path<i32>,
+    pool: web::Data<PgPool>,
+) -> HttpResponse {
+    let teams = match room_teams::load_teams(pool.get_ref(), *room_id).await {
+        Ok(teams) => teams,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    };
+
+    let members = sqlx::query_as::<_, (i32, i64)>(
+        "SELECT team_id, COUNT(*) FROM room_participants WHERE room_id = $1 AND team_id IS NOT NULL GROUP BY team_id"
+    )
+    .bind(*room_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+
+    let teams = teams.iter().map(|team| serde_json::json!({
+        "id": team.id,
+        "name": team.name,
+        "color": team.color,
+        "position": team.position,
+        "players": members.get(&team.id).copied().unwrap_or(0),
+    })).collect::<Vec<_>>();
+
+    HttpResponse::Ok().json(teams)
+}
+
+// Definir as equipes da sala (host, antes do início). Os jogadores são redistribuídos.
+pub async fn update_room_teams(
+    req: HttpRequest,
+    room_id: web::Path<i32>,
+    body: web::Json<UpdateRoomTeamsRequest>,
+    pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Unauthorized"
+        })),
+    };
+
+    if let Err(e) = body.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    // Uma equipe só não faz sentido: 0 (desativar) ou 2+
+    if body.teams.len() == 1 {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Defina pelo menos duas equipes (ou nenhuma para desativar)"
+        }));
+    }
+
+    if let Err(e) = room_teams::check_teams(&body.teams) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    match is_room_host(pool.get_ref(), *room_id, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You are not the host of this room"
+        })),
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+
+    match room_teams::replace_teams(pool.get_ref(), *room_id, &body.teams).await {
+        Ok((teams, assignments)) => {
+            registry.publish(*room_id, &WsMessage::TeamsUpdated { teams: teams.clone(), assignments });
+            HttpResponse::Ok().json(teams)
+        },
+        Err(TeamError::Locked) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "As equipes só podem ser alteradas antes do início da partida",
+            "code": "teams_locked"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": e.message()
+        })),
+    }
+}
+
+// Gerar (ou trocar) o token de apresentador da sala.
+// Quem conecta no WebSocket com ?presenter_token=... recebe o papel de host.
+pub async fn create_presenter_token(
+    req: HttpRequest,
+    room_id: web::Path<i32>,
+    pool: web::Data<PgPool>,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Unauthorized"
+        })),
+    };
+
+    // Apenas o criador da sala
+    let owner = sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT created_by FROM game_rooms WHERE id = $1"
+    )
+    .bind(*room_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match owner {
+        Ok(Some(Some(created_by))) if created_by == user_id => {}
+        Ok(Some(_)) => return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You are not the host of this room"
+        })),
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Room not found"
+        })),
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
     let result = sqlx::query(
-        "UPDATE game_rooms SET is_active = false WHERE id = $1"
+        "UPDATE game_rooms SET presenter_token = $1 WHERE id = $2"
     )
+    .bind(&token)
     .bind(*room_id)
     .execute(pool.get_ref())
     .await;
 
     match result {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-            "message": "Room closed successfully"
+            "room_id": *room_id,
+            "presenter_token": token
         })),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("Failed to close room: {}", e)
+            "error": format!("Failed to create presenter token: {}", e)
         })),
     }
 }
 
+// Quiz da sala encerrado pelo servidor: partida finalizada/arquivada ou, nos modos
+// controlados pelo servidor, relógio já passou da última pergunta
+async fn quiz_room_ended(pool: &PgPool, room_id: i32) -> Result<bool, sqlx::Error> {
+    let ended = sqlx::query_scalar::<_, bool>(
+        "SELECT gr.status IN ('finished', 'archived')
+                OR (qc.pacing_mode IS NOT NULL AND qc.pacing_mode <> 'self_paced'
+                    AND gr.quiz_question_index >= (SELECT COUNT(*) FROM quiz_questions qq WHERE qq.quiz_config_id = qc.id))
+         FROM game_rooms gr
+         LEFT JOIN quiz_configs qc ON qc.game_id = gr.game_id
+         WHERE gr.id = $1
+         LIMIT 1"
+    )
+    .bind(room_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(ended.unwrap_or(false))
+}
+
+fn progress_finished(progress: Option<&serde_json::Value>) -> bool {
+    progress
+        .and_then(|p| p.get("finished"))
+        .map(|f| f.as_bool() == Some(true) || f.as_str() == Some("true"))
+        .unwrap_or(false)
+}
+
 // Salvar progresso do quiz (respostas intermediárias)
 pub async fn save_quiz_progress(
     req: HttpRequest,
@@ -1028,6 +1549,36 @@ pub async fn save_quiz_progress(
         }));
     };
 
+    // Partida encerrada (pelo host ou por tempo esgotado): as respostas ficam congeladas
+    match quiz_room_ended(pool.get_ref(), *room_id).await {
+        Ok(true) => return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "A partida já foi encerrada",
+            "code": "room_finished"
+        })),
+        Ok(false) => {}
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    }
+
+    // Quem já finalizou não altera mais as respostas (a revisão das respondidas foi liberada)
+    match sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT progress_data FROM quiz_progress WHERE room_id = $1 AND user_identifier = $2"
+    )
+    .bind(*room_id)
+    .bind(&identifier)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(progress) if progress_finished(progress.as_ref()) => return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Você já finalizou o quiz",
+            "code": "player_finished"
+        })),
+        Ok(_) => {}
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load progress: {}", e)
+        })),
+    }
+
     // Upsert: inserir ou atualizar progresso
     let result = sqlx::query(
         "INSERT INTO quiz_progress (room_id, user_identifier, progress_data, updated_at)
@@ -1108,6 +1659,99 @@ pub async fn get_quiz_progress(
     }
 }
 
+// Revisão pós-quiz: perguntas com resposta correta, justificativa e a resposta do jogador.
+// Depois que o participante finaliza, só as perguntas respondidas; com a sala encerrada, todas.
+pub async fn get_quiz_review(
+    req: HttpRequest,
+    room_id: web::Path<i32>,
+    pool: web::Data<PgPool>,
+) -> HttpResponse {
+    let user_id = extract_user_id(&req);
+    let session_id = req.headers()
+        .get("X-Session-Id")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    
+    let identifier = if let Some(uid) = user_id {
+        format!("user_{}", uid)
+    } else if let Some(sid) = session_id {
+        format!("session_{}", sid)
+    } else {
+        return HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "No authentication found"
+        }));
+    };
+
+    let room = match sqlx::query_as::<_, GameRoom>(
+        "SELECT * FROM game_rooms WHERE id = $1"
+    )
+    .bind(*room_id)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(Some(r)) => r,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Room not found"
+        })),
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    };
+
+    let progress: Option<serde_json::Value> = match sqlx::query_scalar(
+        "SELECT progress_data FROM quiz_progress WHERE room_id = $1 AND user_identifier = $2"
+    )
+    .bind(*room_id)
+    .bind(&identifier)
+    .fetch_optional(pool.get_ref())
+    .await {
+        Ok(p) => p,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load progress: {}", e)
+        })),
+    };
+
+    // O encerramento da sala é decidido no servidor; o "finished" do jogador só libera as
+    // perguntas que ele respondeu (depois disso o progresso não aceita mais respostas)
+    let room_ended = match quiz_room_ended(pool.get_ref(), room.id).await {
+        Ok(ended) => ended,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    };
+
+    if !room_ended && !progress_finished(progress.as_ref()) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Review is only available after you finish the quiz"
+        }));
+    }
+
+    let questions = match sqlx::query_as::<_, crate::models::QuizQuestion>(
+        "SELECT qq.* FROM quiz_questions qq
+         JOIN quiz_configs qc ON qc.id = qq.quiz_config_id
+         WHERE qc.game_id = $1
+         ORDER BY qq.id"
+    )
+    .bind(room.game_id)
+    .fetch_all(pool.get_ref())
+    .await {
+        Ok(q) => q,
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to fetch questions: {}", e)
+        })),
+    };
+
+    let answers = progress.as_ref()
+        .and_then(|p| p.get("answers"))
+        .and_then(|a| a.as_object());
+
+    let reveal = if room_ended { ReviewReveal::All } else { ReviewReveal::Answered };
+    let mut review = crate::handlers::quiz::build_quiz_review(&questions, answers, reveal);
+    review["room_id"] = serde_json::json!(room.id);
+    review["game_id"] = serde_json::json!(room.game_id);
+
+    HttpResponse::Ok().json(review)
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/rooms")
@@ -1119,21 +1763,41 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/{room_id}/answers", web::get().to(get_room_answers))
             .route("/{room_id}/quiz-progress", web::post().to(save_quiz_progress))
             .route("/{room_id}/quiz-progress", web::get().to(get_quiz_progress))
+            .route("/{room_id}/quiz-review", web::get().to(get_quiz_review))
             .route("/{room_id}/close", web::post().to(close_room))
     );
 }
 
 // Buscar palavras já encontradas na sala
 pub async fn get_room_found_words(
+    req: HttpRequest,
     room_id: web::Path<i32>,
     pool: web::Data<PgPool>,
 ) -> HttpResponse {
-    let words = sqlx::query_as::<_, RoomFoundWord>(
-        "SELECT * FROM room_found_words WHERE room_id = $1 ORDER BY found_at ASC"
-    )
-    .bind(*room_id)
-    .fetch_all(pool.get_ref())
-    .await;
+    let mode = match word_search_mode::load_mode(pool.get_ref(), *room_id).await {
+        Ok(mode) => mode,
+        Err(sqlx::Error::RowNotFound) => return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Room not found"
+        })),
+        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        })),
+    };
+
+    // Modos competitivos: só o tabuleiro de quem pede, identificado pelo JWT ou por uma
+    // sessão que entrou nesta sala (a mesma chave do WebSocket)
+    let viewer = if !mode.is_competitive() {
+        None
+    } else {
+        match found_words_viewer(&req, pool.get_ref(), *room_id).await {
+            Ok(viewer) => viewer,
+            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            })),
+        }
+    };
+
+    let words = word_search_mode::load_found_words(pool.get_ref(), *room_id, mode, viewer.as_deref()).await;
 
     match words {
         Ok(words) => HttpResponse::Ok().json(words),
@@ -1143,9 +1807,42 @@ pub async fn get_room_found_words(
     }
 }
 
-// Buscar pontuações dos jogadores na sala
+// Chave do tabuleiro de quem pede: "user_{id}" do participante logado ou o session_id anônimo
+async fn found_words_viewer(req: &HttpRequest, pool: &PgPool, room_id: i32) -> Result<Option<String>, sqlx::Error> {
+    if let Some(user_id) = middleware::bearer_user_id(req) {
+        let joined = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM room_participants WHERE room_id = $1 AND user_id = $2)"
+        )
+        .bind(room_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        return Ok(joined.then(|| format!("user_{}", user_id)));
+    }
+
+    let session_id = match req.headers().get("X-Session-Id").and_then(|h| h.to_str().ok()) {
+        Some(session_id) => session_id,
+        None => return Ok(None),
+    };
+    sqlx::query_scalar::<_, String>(
+        "SELECT session_id FROM room_participants WHERE room_id = $1 AND session_id = $2 AND user_id IS NULL"
+    )
+    .bind(room_id)
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoomScoresQuery {
+    // "team" = placar das equipes
+    pub by: Option<String>,
+}
+
+// Buscar pontuações dos jogadores na sala (?by=team para o placar das equipes)
 pub async fn get_room_scores(
     room_id: web::Path<i32>,
+    query: web::Query<RoomScoresQuery>,
     pool: web::Data<PgPool>,
 ) -> HttpResponse {
     // Primeiro, buscar a sala para saber o tipo de jogo
@@ -1166,22 +1863,30 @@ pub async fn get_room_scores(
         })),
     };
 
+    match room_scores(pool.get_ref(), &room).await {
+        Ok(scores) => scores_response(pool.get_ref(), room.id, scores, query.by.as_deref()).await,
+        Err(response) => response,
+    }
+}
+
+// Placar individual da sala, do quiz ou do caça-palavras (também usado pelas sessões legadas)
+pub async fn room_scores(pool: &PgPool, room: &GameRoom) -> Result<Vec<serde_json::Value>, HttpResponse> {
     // Buscar o tipo de jogo
     let game = sqlx::query_as::<_, Game>(
         "SELECT * FROM games WHERE id = $1"
     )
     .bind(room.game_id)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(pool)
     .await;
 
     let game = match game {
         Ok(Some(g)) => g,
-        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({
+        Ok(None) => return Err(HttpResponse::NotFound().json(serde_json::json!({
             "error": "Game not found"
-        })),
-        Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+        }))),
+        Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Database error: {}", e)
-        })),
+        }))),
     };
 
     // Se for quiz, buscar de quiz_progress e calcular scores
@@ -1191,17 +1896,17 @@ pub async fn get_room_scores(
             "SELECT id FROM quiz_configs WHERE game_id = $1 LIMIT 1"
         )
         .bind(game.id)
-        .fetch_optional(pool.get_ref())
+        .fetch_optional(pool)
         .await;
 
         let quiz_config_id = match quiz_config_id {
             Ok(Some((id,))) => id,
-            Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({
+            Ok(None) => return Err(HttpResponse::NotFound().json(serde_json::json!({
                 "error": "Quiz configuration not found"
-            })),
-            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            }))),
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Failed to fetch quiz config: {}", e)
-            })),
+            }))),
         };
 
         // Buscar as perguntas do quiz com respostas corretas e pontos
@@ -1209,102 +1914,128 @@ pub async fn get_room_scores(
             "SELECT id, correct_option, points FROM quiz_questions WHERE quiz_config_id = $1 ORDER BY id"
         )
         .bind(quiz_config_id)
-        .fetch_all(pool.get_ref())
+        .fetch_all(pool)
         .await;
 
         let questions = match questions {
             Ok(q) => q,
-            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Failed to fetch questions: {}", e)
-            })),
+            }))),
         };
 
         // Buscar progresso dos jogadores
         let quiz_progress: Result<Vec<(String, serde_json::Value)>, _> = sqlx::query_as(
             "SELECT user_identifier, progress_data FROM quiz_progress WHERE room_id = $1"
         )
-        .bind(*room_id)
-        .fetch_all(pool.get_ref())
+        .bind(room.id)
+        .fetch_all(pool)
         .await;
 
         let progress = match quiz_progress {
             Ok(p) => p,
-            Err(e) => return HttpResponse::InternalServerError().json(serde_json::json!({
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": format!("Failed to fetch progress: {}", e)
-            })),
+            }))),
         };
 
-        // Calcular scores para cada jogador
-        let formatted_scores: Vec<serde_json::Value> = progress.iter()
-            .filter_map(|(user_identifier, progress_data)| {
-                // Extrair respostas do JSON
-                let answers = progress_data.get("answers")?.as_object()?;
-                
-                // Calcular score
-                let mut total_score = 0i64;
-                let mut correct_answers = 0i32;
-                
-                for (idx, (_, correct_option, points)) in questions.iter().enumerate() {
-                    if let Some(player_answer) = answers.get(&idx.to_string()) {
-                        let player_answer_str = player_answer.as_str()?;
-                        if player_answer_str == correct_option.trim() {
-                            total_score += *points as i64;
-                            correct_answers += 1;
-                        }
-                    }
-                }
-                
-                // Extrair nome do jogador (do progress_data se disponível, senão do user_identifier)
-                let player_name = progress_data
-                    .get("player_name")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| {
-                        if user_identifier.starts_with("session_") {
-                            format!("Jogador #{}", &user_identifier[8..12])
-                        } else if user_identifier.starts_with("user_") {
-                            user_identifier.to_string()
-                        } else {
-                            user_identifier.to_string()
-                        }
-                    });
-                
-                Some(serde_json::json!({
-                    "player_name": player_name,
-                    "total_score": total_score,
-                    "words_found": correct_answers,
-                    "player_color": "#6366f1"
-                }))
-            })
-            .collect();
-
-        // Ordenar por pontuação
-        let mut sorted_scores = formatted_scores;
-        sorted_scores.sort_by(|a, b| {
-            let score_a = a.get("total_score").and_then(|v| v.as_i64()).unwrap_or(0);
-            let score_b = b.get("total_score").and_then(|v| v.as_i64()).unwrap_or(0);
-            score_b.cmp(&score_a)
-        });
-
-        return HttpResponse::Ok().json(sorted_scores);
-    }
-
-    // Se for caça-palavras, buscar de room_player_scores
-    let scores = sqlx::query_as::<_, RoomPlayerScore>(
-        "SELECT * FROM room_player_scores WHERE room_id = $1 ORDER BY total_score DESC"
-    )
-    .bind(*room_id)
-    .fetch_all(pool.get_ref())
-    .await;
+        let teams = match room_teams::quiz_teams(pool, room.id).await {
+            Ok(teams) => teams,
+            Err(e) => return Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch teams: {}", e)
+            }))),
+        };
 
-    match scores {
-        Ok(scores) => HttpResponse::Ok().json(scores),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+        return Ok(quiz_scores(&questions, &progress, &teams));
+    }
+
+    // Se for caça-palavras, buscar de room_player_scores (ordem conforme o modo da sala)
+    let mode = WordSearchMode::parse(&room.word_search_mode).unwrap_or_default();
+    match word_search_mode::load_scores(pool, room.id, mode).await {
+        Ok(scores) => Ok(scores.iter().map(|score| serde_json::json!(score)).collect()),
+        Err(e) => Err(HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Database error: {}", e)
+        }))),
+    }
+}
+
+// Placar individual ou, com by=team, o placar das equipes
+async fn scores_response(pool: &PgPool, room_id: i32, scores: Vec<serde_json::Value>, by: Option<&str>) -> HttpResponse {
+    if by != Some("team") {
+        return HttpResponse::Ok().json(scores);
+    }
+
+    match room_teams::load_teams(pool, room_id).await {
+        Ok(teams) => HttpResponse::Ok().json(room_teams::leaderboard(&teams, &scores)),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to fetch teams: {}", e)
         })),
     }
 }
 
+// Pontuação de cada jogador do quiz a partir do progresso salvo (maior pontuação primeiro).
+// questions: (id, correct_option, points) na ordem das perguntas.
+// teams: equipe de cada user_identifier (modo equipes).
+pub fn quiz_scores(
+    questions: &[(i32, String, i32)],
+    progress: &[(String, serde_json::Value)],
+    teams: &HashMap<String, i32>,
+) -> Vec<serde_json::Value> {
+    // Calcular scores para cada jogador
+    let formatted_scores: Vec<serde_json::Value> = progress.iter()
+        .filter_map(|(user_identifier, progress_data)| {
+            // Extrair respostas do JSON
+            let answers = progress_data.get("answers")?.as_object()?;
+            
+            // Calcular score
+            let mut total_score = 0i64;
+            let mut correct_answers = 0i32;
+            
+            for (idx, (_, correct_option, points)) in questions.iter().enumerate() {
+                if let Some(player_answer) = answers.get(&idx.to_string()) {
+                    let player_answer_str = player_answer.as_str()?;
+                    if player_answer_str == correct_option.trim() {
+                        total_score += *points as i64;
+                        correct_answers += 1;
+                    }
+                }
+            }
+            
+            // Extrair nome do jogador (do progress_data se disponível, senão do user_identifier)
+            let player_name = progress_data
+                .get("player_name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| {
+                    if user_identifier.starts_with("session_") {
+                        format!("Jogador #{}", &user_identifier[8..12])
+                    } else if user_identifier.starts_with("user_") {
+                        user_identifier.to_string()
+                    } else {
+                        user_identifier.to_string()
+                    }
+                });
+            
+            Some(serde_json::json!({
+                "player_name": player_name,
+                "total_score": total_score,
+                "words_found": correct_answers,
+                "player_color": "#6366f1",
+                "team_id": teams.get(user_identifier),
+            }))
+        })
+        .collect();
+
+    // Ordenar por pontuação
+    let mut sorted_scores = formatted_scores;
+    sorted_scores.sort_by(|a, b| {
+        let score_a = a.get("total_score").and_then(|v| v.as_i64()).unwrap_or(0);
+        let score_b = b.get("total_score").and_then(|v| v.as_i64()).unwrap_or(0);
+        score_b.cmp(&score_a)
+    });
+    sorted_scores
+}
+
 // Buscar detalhes de uma sala por ID (protegido)
 pub async fn get_room_by_id(
     req: HttpRequest,
@@ -1472,7 +2203,7 @@ pub async fn update_room(
             };
 
             sqlx::query(
-                "UPDATE game_rooms SET room_name = $1, password_hash = $2, max_players = $3, is_active = true, expires_at = $4 WHERE id = $5"
+                "UPDATE game_rooms SET room_name = $1, password_hash = $2, max_players = $3, is_active = true, expires_at = $4, status = CASE WHEN status = 'archived' THEN 'lobby' ELSE status END WHERE id = $5"
             )
             .bind(&body.room_name)
             .bind(&password_hash)
@@ -1484,7 +2215,7 @@ pub async fn update_room(
         } else {
             // Reativar mantendo senha atual
             sqlx::query(
-                "UPDATE game_rooms SET room_name = $1, max_players = $2, is_active = true, expires_at = $3 WHERE id = $4"
+                "UPDATE game_rooms SET room_name = $1, max_players = $2, is_active = true, expires_at = $3, status = CASE WHEN status = 'archived' THEN 'lobby' ELSE status END WHERE id = $4"
             )
             .bind(&body.room_name)
             .bind(body.max_players)
@@ -1573,7 +2304,8 @@ pub async fn reset_room(
     req: HttpRequest,
     room_id: web::Path<i32>,
     pool: web::Data<PgPool>,
-    room_manager: web::Data<crate::websocket::RoomManager>,
+    registry: web::Data<RoomRegistry>,
+    quiz_clocks: web::Data<crate::quiz_clock::QuizClocks>,
 ) -> HttpResponse {
     let user_id = match extract_user_id(&req) {
         Some(id) => id,
@@ -1771,7 +2503,7 @@ pub async fn reset_room(
 
     // Resetar o cronômetro (zerando started_at, paused_at, etc)
     let reset_timer = sqlx::query(
-        "UPDATE game_rooms SET started_at = NULL, paused_at = NULL, total_pause_duration = NULL, completed_at = NULL, completion_time = NULL WHERE id = $1"
+        "UPDATE game_rooms SET status = CASE WHEN status = 'archived' THEN status ELSE 'lobby' END, countdown_ends_at = NULL, started_at = NULL, paused_at = NULL, pause_reason = NULL, total_pause_duration = NULL, completed_at = NULL, completion_time = NULL, quiz_question_index = 0, quiz_question_started_at = NULL WHERE id = $1"
     )
     .bind(*room_id)
     .execute(pool.get_ref())
@@ -1781,27 +2513,15 @@ pub async fn reset_room(
         Ok(_) => {
             log::info!("Room {} reset successfully", room_id);
             
-            // Notificar todos os jogadores conectados via WebSocket
-            let manager = room_manager.lock().unwrap();
-            if let Some(connections) = manager.get(&(*room_id)) {
-                let message = crate::websocket::WsMessage::RoomReset {
-                    reset_by: format!("user_{}", user_id),
-                };
-                let text = serde_json::to_string(&message).unwrap();
-                
-                log::info!("🔊 Broadcasting RoomReset to room {}: {} connections", room_id, connections.len());
-                
-                for conn_info in connections.iter() {
-                    conn_info.addr.do_send(crate::websocket::SendMessage {
-                        text: text.clone(),
-                    });
-                }
-                
-                log::info!("✅ Room reset broadcast sent to {} connections", connections.len());
-            } else {
-                log::warn!("No active connections in room {}", room_id);
+            // Reiniciar o relógio do quiz na primeira pergunta
+            if let Some(clock) = quiz_clocks.lock().unwrap().get_mut(&(*room_id)) {
+                clock.reset(Utc::now());
             }
-            drop(manager);
+            
+            // Notificar todos os jogadores conectados via WebSocket
+            registry.publish(*room_id, &crate::websocket::WsMessage::RoomReset {
+                reset_by: format!("user_{}", user_id),
+            });
             
             HttpResponse::Ok().json(serde_json::json!({
                 "message": "Room reset successfully"
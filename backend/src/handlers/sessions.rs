@@ -1,229 +1,325 @@
 use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
 use sqlx::PgPool;
-use validator::Validate;
-use rand::Rng;
 
+use crate::handlers::rooms;
 use crate::models::{
-    GameSession, CreateSessionRequest, JoinSessionRequest, 
+    GameSession, CreateSessionRequest, JoinSessionRequest,
     GameResult, SubmitScoreRequest, ErrorResponse,
+    CreateRoomRequest, JoinRoomAnonymousRequest, GameRoom,
 };
+use crate::room_hub::RoomRegistry;
+use crate::room_clock::RoomTimer;
+use crate::room_status::{self, RoomStatus, StatusChange};
 
-fn generate_session_code() -> String {
-    let mut rng = rand::thread_rng();
-    let code: String = (0..6)
-        .map(|_| {
-            let idx = rng.gen_range(0..36);
-            if idx < 10 {
-                (b'0' + idx) as char
-            } else {
-                (b'A' + (idx - 10)) as char
-            }
-        })
-        .collect();
-    code
+// Camada de compatibilidade: as antigas sessões (/api/sessions/*) agora são salas
+// (game_rooms). O id da sessão é o id da sala e o código da sessão é o código da sala.
+
+// Limite de jogadores das sessões criadas sem max_players
+const DEFAULT_MAX_PLAYERS: i32 = 50;
+
+fn session_from_room(room: GameRoom) -> GameSession {
+    GameSession {
+        id: room.id,
+        game_id: room.game_id,
+        session_code: room.room_code,
+        // A sala só guarda o hash da senha
+        password: None,
+        is_active: room.is_active && room.completed_at.is_none(),
+        max_players: Some(room.max_players),
+        created_at: room.created_at,
+        started_at: room.started_at,
+        ended_at: room.completed_at,
+    }
+}
+
+fn database_error(e: sqlx::Error) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        error: format!("Database error: {}", e),
+    })
+}
+
+async fn room_by_id(pool: &PgPool, room_id: i32) -> Result<GameRoom, HttpResponse> {
+    let room = sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await;
+    session_room(room)
+}
+
+async fn room_by_code(pool: &PgPool, room_code: &str) -> Result<GameRoom, HttpResponse> {
+    let room = sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE room_code = $1")
+        .bind(room_code)
+        .fetch_optional(pool)
+        .await;
+    session_room(room)
+}
+
+fn session_room(room: Result<Option<GameRoom>, sqlx::Error>) -> Result<GameRoom, HttpResponse> {
+    room.map_err(database_error)?.ok_or_else(|| HttpResponse::NotFound().json(ErrorResponse {
+        error: "Session not found".to_string(),
+    }))
 }
 
+fn extract_user_id(req: &HttpRequest) -> Option<i32> {
+    req.extensions().get::<i32>().copied()
+}
+
+fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ErrorResponse {
+        error: "Unauthorized".to_string(),
+    })
+}
+
+// POST /api/protected/sessions - cria uma sala com nome gerado
 pub async fn create_session(
     pool: web::Data<PgPool>,
     body: web::Json<CreateSessionRequest>,
     req: HttpRequest,
 ) -> HttpResponse {
-    let _user_id = match extract_user_id(&req) {
+    let user_id = match extract_user_id(&req) {
         Some(id) => id,
-        None => return HttpResponse::Unauthorized().json(ErrorResponse {
-            error: "Unauthorized".to_string(),
-        }),
+        None => return unauthorized(),
     };
 
-    let session_code = generate_session_code();
-
-    let result = sqlx::query_as::<_, GameSession>(
-        "INSERT INTO game_sessions (game_id, session_code, password, max_players) 
-         VALUES ($1, $2, $3, $4) RETURNING *"
-    )
-    .bind(body.game_id)
-    .bind(&session_code)
-    .bind(&body.password)
-    .bind(body.max_players)
-    .fetch_one(pool.get_ref())
-    .await;
+    let body = body.into_inner();
+    let suffix = uuid::Uuid::new_v4().simple().to_string()[..6].to_uppercase();
+    let request = CreateRoomRequest {
+        game_id: body.game_id,
+        room_name: format!("Sessão {}", suffix),
+        password: body.password.filter(|password| !password.is_empty()),
+        max_players: Some(body.max_players.unwrap_or(DEFAULT_MAX_PLAYERS)),
+        duration_hours: None,
+        time_limit: None,
+        teams: None,
+        word_search_mode: None,
+    };
 
-    match result {
-        Ok(session) => HttpResponse::Created().json(session),
-        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
-            error: format!("Database error: {}", e),
-        }),
+    match rooms::insert_room(pool.get_ref(), user_id, &request).await {
+        Ok(room) => HttpResponse::Created().json(session_from_room(room)),
+        Err(response) => response,
     }
 }
 
+// GET /api/sessions/{code}
 pub async fn get_session(
     pool: web::Data<PgPool>,
     session_code: web::Path<String>,
 ) -> HttpResponse {
-    let session = match sqlx::query_as::<_, GameSession>(
-        "SELECT * FROM game_sessions WHERE session_code = $1"
-    )
-    .bind(session_code.into_inner())
-    .fetch_one(pool.get_ref())
-    .await {
-        Ok(s) => s,
-        Err(_) => return HttpResponse::NotFound().json(ErrorResponse {
-            error: "Session not found".to_string(),
-        }),
-    };
-
-    HttpResponse::Ok().json(session)
+    match room_by_code(pool.get_ref(), &session_code.trim().to_uppercase()).await {
+        Ok(room) => HttpResponse::Ok().json(session_from_room(room)),
+        Err(response) => response,
+    }
 }
 
+// POST /api/sessions/join - entra na sala como jogador anônimo. A resposta traz a sessão e,
+// em "player", a sessão do jogador (session_id para o header X-Session-Id e o WebSocket).
 pub async fn join_session(
     pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
     body: web::Json<JoinSessionRequest>,
 ) -> HttpResponse {
-    let session = match sqlx::query_as::<_, GameSession>(
-        "SELECT * FROM game_sessions WHERE session_code = $1 AND is_active = true"
-    )
-    .bind(&body.session_code)
-    .fetch_one(pool.get_ref())
-    .await {
-        Ok(s) => s,
-        Err(_) => return HttpResponse::NotFound().json(ErrorResponse {
-            error: "Session not found or inactive".to_string(),
-        }),
+    let body = body.into_inner();
+    let room_code = body.session_code.trim().to_uppercase();
+    let request = JoinRoomAnonymousRequest {
+        room_code: room_code.clone(),
+        password: body.password,
+        player_name: body.player_name,
+        existing_session_id: None,
+        team_id: None,
     };
 
-    // Check password if required
-    if let Some(ref session_password) = session.password {
-        if let Some(ref provided_password) = body.password {
-            if session_password != provided_password {
-                return HttpResponse::Unauthorized().json(ErrorResponse {
-                    error: "Invalid password".to_string(),
-                });
-            }
-        } else {
-            return HttpResponse::Unauthorized().json(ErrorResponse {
-                error: "Password required".to_string(),
-            });
-        }
-    }
+    let player = match rooms::join_anonymous(pool.get_ref(), registry.get_ref(), &request).await {
+        Ok(player) => player,
+        Err(response) => return response,
+    };
 
-    // Check max players if set
-    if let Some(max_players) = session.max_players {
-        let current_players = match sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM game_results WHERE session_id = $1"
-        )
-        .bind(session.id)
-        .fetch_one(pool.get_ref())
-        .await {
-            Ok(count) => count,
-            Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
-                error: format!("Database error: {}", e),
-            }),
-        };
-
-        if current_players >= max_players as i64 {
-            return HttpResponse::BadRequest().json(ErrorResponse {
-                error: "Session is full".to_string(),
-            });
+    match room_by_code(pool.get_ref(), &room_code).await {
+        Ok(room) => {
+            let mut session = serde_json::json!(session_from_room(room));
+            session["player"] = serde_json::json!(player);
+            HttpResponse::Ok().json(session)
         }
+        Err(response) => response,
     }
-
-    HttpResponse::Ok().json(session)
 }
 
+// POST /api/protected/sessions/{id}/start - só o anfitrião
 pub async fn start_session(
     pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
     session_id: web::Path<i32>,
     req: HttpRequest,
 ) -> HttpResponse {
-    let _user_id = match extract_user_id(&req) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().json(ErrorResponse {
-            error: "Unauthorized".to_string(),
-        }),
-    };
-
-    let result = sqlx::query_as::<_, GameSession>(
-        "UPDATE game_sessions SET started_at = NOW() WHERE id = $1 RETURNING *"
-    )
-    .bind(session_id.into_inner())
-    .fetch_one(pool.get_ref())
-    .await;
-
-    match result {
-        Ok(session) => HttpResponse::Ok().json(session),
-        Err(_) => HttpResponse::NotFound().json(ErrorResponse {
-            error: "Session not found".to_string(),
-        }),
-    }
+    session_status_change(&req, pool.get_ref(), registry.get_ref(), *session_id, StatusChange::Start).await
 }
 
+// POST /api/protected/sessions/{id}/end - só o anfitrião
 pub async fn end_session(
     pool: web::Data<PgPool>,
+    registry: web::Data<RoomRegistry>,
     session_id: web::Path<i32>,
     req: HttpRequest,
 ) -> HttpResponse {
-    let _user_id = match extract_user_id(&req) {
+    let change = StatusChange::End { reason: room_status::REASON_HOST };
+    session_status_change(&req, pool.get_ref(), registry.get_ref(), *session_id, change).await
+}
+
+async fn session_status_change(
+    req: &HttpRequest,
+    pool: &PgPool,
+    registry: &RoomRegistry,
+    room_id: i32,
+    change: StatusChange,
+) -> HttpResponse {
+    let user_id = match extract_user_id(req) {
         Some(id) => id,
-        None => return HttpResponse::Unauthorized().json(ErrorResponse {
-            error: "Unauthorized".to_string(),
-        }),
+        None => return unauthorized(),
     };
 
-    let result = sqlx::query_as::<_, GameSession>(
-        "UPDATE game_sessions SET ended_at = NOW(), is_active = false WHERE id = $1 RETURNING *"
-    )
-    .bind(session_id.into_inner())
-    .fetch_one(pool.get_ref())
-    .await;
-
-    match result {
-        Ok(session) => HttpResponse::Ok().json(session),
-        Err(_) => HttpResponse::NotFound().json(ErrorResponse {
-            error: "Session not found".to_string(),
-        }),
+    if let Err(response) = rooms::change_status_as_host(pool, registry, user_id, room_id, change).await {
+        return response;
+    }
+    match room_by_id(pool, room_id).await {
+        Ok(room) => HttpResponse::Ok().json(session_from_room(room)),
+        Err(response) => response,
     }
 }
 
+// POST /api/scores - pontuação final enviada por clientes antigos, guardada em game_results
+// ligada à sala. Não entra no placar da sala nem nos rankings, que são calculados pelo
+// servidor. O jogador é identificado pelo header X-Session-Id recebido no join, que precisa
+// ser de um participante da sala; só vale com a partida em andamento.
 pub async fn submit_score(
     pool: web::Data<PgPool>,
     body: web::Json<SubmitScoreRequest>,
+    req: HttpRequest,
 ) -> HttpResponse {
-    let result = sqlx::query_as::<_, GameResult>(
-        "INSERT INTO game_results (session_id, player_name, score) VALUES ($1, $2, $3) RETURNING *"
+    let player_name = body.player_name.trim();
+    if player_name.is_empty() || player_name.chars().count() > 50 {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "player_name must have between 1 and 50 characters".to_string(),
+        });
+    }
+    if body.score < 0 {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: "score must not be negative".to_string(),
+        });
+    }
+
+    let player_key = match req.headers().get("X-Session-Id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && value.len() <= 255)
+    {
+        Some(key) => key.to_string(),
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "X-Session-Id header required".to_string(),
+        }),
+    };
+
+    let room = match room_by_id(pool.get_ref(), body.session_id).await {
+        Ok(room) => room,
+        Err(response) => return response,
+    };
+    let accepts_plays = RoomStatus::parse(&room.status).is_some_and(|status| status.accepts_plays());
+    if !room.is_active || !accepts_plays {
+        return HttpResponse::Conflict().json(ErrorResponse {
+            error: "Session is not running".to_string(),
+        });
+    }
+
+    let participant = sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT user_id FROM room_participants WHERE room_id = $1 AND session_id = $2"
     )
-    .bind(body.session_id)
-    .bind(&body.player_name)
+    .bind(room.id)
+    .bind(&player_key)
+    .fetch_optional(pool.get_ref())
+    .await;
+    let user_id = match participant {
+        Ok(Some(user_id)) => user_id,
+        Ok(None) => return HttpResponse::Forbidden().json(ErrorResponse {
+            error: "Session does not belong to this room".to_string(),
+        }),
+        Err(e) => return database_error(e),
+    };
+
+    // Tempo pelo cronômetro da sala, não pelo cliente
+    let time_seconds = RoomTimer::from_room(&room).elapsed(chrono::Utc::now()).unwrap_or(0) as i32;
+
+    // Um resultado por jogador da sala: reenvios substituem o anterior
+    let updated = sqlx::query_as::<_, (i32, chrono::NaiveDateTime)>(
+        "UPDATE game_results SET score = $3, time_seconds = $4, user_id = $5, updated_at = NOW()
+         WHERE room_id = $1 AND device_session_id = $2
+         RETURNING id, updated_at"
+    )
+    .bind(room.id)
+    .bind(&player_key)
     .bind(body.score)
-    .fetch_one(pool.get_ref())
+    .bind(time_seconds)
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
     .await;
 
-    match result {
-        Ok(result) => HttpResponse::Created().json(result),
-        Err(e) => HttpResponse::InternalServerError().json(ErrorResponse {
-            error: format!("Database error: {}", e),
+    let saved = match updated {
+        Ok(Some(saved)) => Ok(saved),
+        Ok(None) => sqlx::query_as::<_, (i32, chrono::NaiveDateTime)>(
+            "INSERT INTO game_results (game_id, user_id, room_id, time_seconds, score, completed, device_session_id)
+             VALUES ($1, $2, $3, $4, $5, true, $6)
+             RETURNING id, updated_at"
+        )
+        .bind(room.game_id)
+        .bind(user_id)
+        .bind(room.id)
+        .bind(time_seconds)
+        .bind(body.score)
+        .bind(&player_key)
+        .fetch_one(pool.get_ref())
+        .await,
+        Err(e) => Err(e),
+    };
+
+    match saved {
+        Ok((id, completed_at)) => HttpResponse::Created().json(GameResult {
+            id,
+            session_id: room.id,
+            player_name: player_name.to_string(),
+            score: body.score,
+            completed_at: completed_at.and_utc(),
         }),
+        Err(e) => database_error(e),
     }
 }
 
+// GET /api/sessions/{id}/results - o placar da sala no formato antigo
 pub async fn get_session_results(
     pool: web::Data<PgPool>,
     session_id: web::Path<i32>,
 ) -> HttpResponse {
-    let results = match sqlx::query_as::<_, GameResult>(
-        "SELECT * FROM game_results WHERE session_id = $1 ORDER BY score DESC, completed_at ASC"
-    )
-    .bind(session_id.into_inner())
-    .fetch_all(pool.get_ref())
-    .await {
-        Ok(r) => r,
-        Err(e) => return HttpResponse::InternalServerError().json(ErrorResponse {
-            error: format!("Database error: {}", e),
-        }),
+    let room_id = *session_id;
+    let room = match room_by_id(pool.get_ref(), room_id).await {
+        Ok(room) => room,
+        Err(response) => return response,
     };
 
-    HttpResponse::Ok().json(results)
-}
+    let scores = match rooms::room_scores(pool.get_ref(), &room).await {
+        Ok(scores) => scores,
+        Err(response) => return response,
+    };
 
-fn extract_user_id(req: &HttpRequest) -> Option<i32> {
-    req.extensions().get::<i32>().copied()
+    let completed_at = room.completed_at.unwrap_or(room.created_at);
+    let results = scores.iter()
+        .enumerate()
+        .map(|(index, score)| GameResult {
+            // Jogadores do quiz não têm linha própria: usa a posição no placar
+            id: score.get("id").and_then(|v| v.as_i64()).map(|id| id as i32).unwrap_or(index as i32 + 1),
+            session_id: room_id,
+            player_name: score.get("player_name").and_then(|v| v.as_str()).unwrap_or("Jogador").to_string(),
+            score: score.get("total_score").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+            completed_at: score.get("last_updated")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or(completed_at),
+        })
+        .collect::<Vec<_>>();
+
+    HttpResponse::Ok().json(results)
 }
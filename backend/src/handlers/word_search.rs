@@ -1,10 +1,35 @@
 use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
+use serde::Deserialize;
 use sqlx::PgPool;
 use validator::Validate;
 
 use crate::models::{
     WordSearchConfig, CreateWordSearchRequest, ErrorResponse, Game,
 };
+use crate::word_search_clues;
+use crate::word_search_words;
+
+#[derive(Debug, Deserialize)]
+pub struct WordSearchConfigQuery {
+    // game_seed da sala (monta a mesma grade no modo pistas)
+    pub seed: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckWordRequest {
+    pub word: String,
+}
+
+// Curva de pontuação enviada (None = curva padrão)
+fn scoring_json(body: &CreateWordSearchRequest) -> Result<Option<serde_json::Value>, String> {
+    match &body.scoring {
+        Some(scoring) => {
+            scoring.validate()?;
+            serde_json::to_value(scoring).map(Some).map_err(|e| e.to_string())
+        }
+        None => Ok(None),
+    }
+}
 
 pub async fn create_word_search_config(
     pool: web::Data<PgPool>,
@@ -17,6 +42,28 @@ pub async fn create_word_search_config(
         });
     }
 
+    let scoring = match scoring_json(&body) {
+        Ok(scoring) => scoring,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
+    let strip_accents = body.strip_accents.unwrap_or(false);
+    let words = match word_search_words::validate_words(&body.words, body.grid_size, body.allowed_directions.as_ref(), strip_accents) {
+        Ok(words) => words,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
+    let concepts = match word_search_clues::validate_concepts(&words, body.concepts.as_ref(), body.hide_words.unwrap_or(false), strip_accents) {
+        Ok(concepts) => concepts,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
     let user_id = match extract_user_id(&req) {
         Some(id) => id,
         None => return HttpResponse::Unauthorized().json(ErrorResponse {
@@ -45,16 +92,18 @@ pub async fn create_word_search_config(
     }
 
     let result = sqlx::query_as::<_, WordSearchConfig>(
-        "INSERT INTO word_search_configs (game_id, grid_size, words, time_limit, allowed_directions, concepts, hide_words) 
-         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *"
+        "INSERT INTO word_search_configs (game_id, grid_size, words, time_limit, allowed_directions, concepts, hide_words, scoring, strip_accents) 
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *"
     )
     .bind(body.game_id)
     .bind(body.grid_size)
-    .bind(&body.words)
+    .bind(&words)
     .bind(body.time_limit)
     .bind(&body.allowed_directions)
-    .bind(&body.concepts)
+    .bind(&concepts)
     .bind(body.hide_words.unwrap_or(false))
+    .bind(&scoring)
+    .bind(strip_accents)
     .fetch_one(pool.get_ref())
     .await;
 
@@ -69,9 +118,11 @@ pub async fn create_word_search_config(
     }
 }
 
+// Configuração para jogar (no modo pistas, sem as palavras; ?seed= monta a grade da sala)
 pub async fn get_word_search_config(
     pool: web::Data<PgPool>,
     game_id: web::Path<i32>,
+    query: web::Query<WordSearchConfigQuery>,
 ) -> HttpResponse {
     let config = match sqlx::query_as::<_, WordSearchConfig>(
         "SELECT * FROM word_search_configs WHERE game_id = $1"
@@ -85,9 +136,69 @@ pub async fn get_word_search_config(
         }),
     };
 
+    HttpResponse::Ok().json(word_search_clues::public_config(config, query.seed.as_deref()))
+}
+
+// Configuração completa (palavras e conceitos) para o dono do jogo editar
+pub async fn get_own_word_search_config(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user_id = match extract_user_id(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Unauthorized".to_string(),
+        }),
+    };
+
+    let config = match sqlx::query_as::<_, WordSearchConfig>(
+        "SELECT wsc.* FROM word_search_configs wsc
+         JOIN games g ON g.id = wsc.game_id
+         WHERE wsc.game_id = $1 AND g.created_by = $2"
+    )
+    .bind(game_id.into_inner())
+    .bind(user_id)
+    .fetch_one(pool.get_ref())
+    .await {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Word search config not found or unauthorized".to_string(),
+        }),
+    };
+
     HttpResponse::Ok().json(config)
 }
 
+// Confere uma palavra no jogo individual do modo pistas e revela o conceito
+pub async fn check_word_search_word(
+    pool: web::Data<PgPool>,
+    game_id: web::Path<i32>,
+    body: web::Json<CheckWordRequest>,
+) -> HttpResponse {
+    let config = match sqlx::query_as::<_, WordSearchConfig>(
+        "SELECT * FROM word_search_configs WHERE game_id = $1"
+    )
+    .bind(game_id.into_inner())
+    .fetch_one(pool.get_ref())
+    .await {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::NotFound().json(ErrorResponse {
+            error: "Word search config not found".to_string(),
+        }),
+    };
+
+    match config.words.iter().find(|word| word_search_words::same_word(word, &body.word)) {
+        Some(word) => HttpResponse::Ok().json(serde_json::json!({
+            "word": word.to_uppercase(),
+            "concept": word_search_clues::concept_for(config.concepts.as_ref(), word),
+        })),
+        None => HttpResponse::NotFound().json(ErrorResponse {
+            error: "Word not found".to_string(),
+        }),
+    }
+}
+
 pub async fn update_word_search_config(
     pool: web::Data<PgPool>,
     game_id: web::Path<i32>,
@@ -100,6 +211,28 @@ pub async fn update_word_search_config(
         });
     }
 
+    let scoring = match scoring_json(&body) {
+        Ok(scoring) => scoring,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
+    let strip_accents = body.strip_accents.unwrap_or(false);
+    let words = match word_search_words::validate_words(&body.words, body.grid_size, body.allowed_directions.as_ref(), strip_accents) {
+        Ok(words) => words,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
+    let concepts = match word_search_clues::validate_concepts(&words, body.concepts.as_ref(), body.hide_words.unwrap_or(false), strip_accents) {
+        Ok(concepts) => concepts,
+        Err(e) => return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Validation error: {}", e),
+        }),
+    };
+
     let user_id = match extract_user_id(&req) {
         Some(id) => id,
         None => return HttpResponse::Unauthorized().json(ErrorResponse {
@@ -124,16 +257,18 @@ pub async fn update_word_search_config(
 
     let result = sqlx::query_as::<_, WordSearchConfig>(
         "UPDATE word_search_configs 
-         SET grid_size = $1, words = $2, time_limit = $3, allowed_directions = $4, concepts = $5, hide_words = $6 
-         WHERE game_id = $7 
+         SET grid_size = $1, words = $2, time_limit = $3, allowed_directions = $4, concepts = $5, hide_words = $6, scoring = $7, strip_accents = $8 
+         WHERE game_id = $9 
          RETURNING *"
     )
     .bind(body.grid_size)
-    .bind(&body.words)
+    .bind(&words)
     .bind(body.time_limit)
     .bind(&body.allowed_directions)
-    .bind(&body.concepts)
+    .bind(&concepts)
     .bind(body.hide_words.unwrap_or(false))
+    .bind(&scoring)
+    .bind(strip_accents)
     .bind(game_id_value)
     .fetch_one(pool.get_ref())
     .await;
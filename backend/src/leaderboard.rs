@@ -0,0 +1,230 @@
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::time::Duration;
+
+// Intervalo padrão entre atualizações da view leaderboard_entries (LEADERBOARD_REFRESH_SECS)
+const DEFAULT_REFRESH_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeaderboardPeriod {
+    #[default]
+    All,
+    // Desde segunda-feira 00:00 (UTC)
+    Week,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [&'static str; 2] = ["all", "week"];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "all" => Some(LeaderboardPeriod::All),
+            "week" => Some(LeaderboardPeriod::Week),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::All => "all",
+            LeaderboardPeriod::Week => "week",
+        }
+    }
+
+    fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            LeaderboardPeriod::All => None,
+            LeaderboardPeriod::Week => {
+                let monday = now.date_naive() - chrono::Duration::days(now.weekday().num_days_from_monday() as i64);
+                monday.and_hms_opt(0, 0, 0).map(|start| start.and_utc())
+            }
+        }
+    }
+}
+
+// Filtros comuns aos placares
+pub struct LeaderboardFilter {
+    pub period: LeaderboardPeriod,
+    // Só as salas informadas (ex.: as salas de uma turma)
+    pub room_ids: Option<Vec<i32>>,
+    pub include_anonymous: bool,
+}
+
+// Melhor partida de cada jogador em um jogo
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct GameLeaderboardEntry {
+    pub rank: i64,
+    pub player_name: String,
+    pub user_id: Option<i32>,
+    pub anonymous: bool,
+    pub score: i32,
+    pub time_seconds: Option<i32>,
+    pub room_id: Option<i32>,
+    pub played_at: Option<DateTime<Utc>>,
+}
+
+// Soma das melhores pontuações de cada jogador em cada jogo
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct GlobalLeaderboardEntry {
+    pub rank: i64,
+    pub player_name: String,
+    pub user_id: Option<i32>,
+    pub anonymous: bool,
+    pub total_score: i64,
+    pub games_played: i64,
+    pub total_time_seconds: Option<i64>,
+}
+
+// Melhor partida por jogador (e por jogo): maior pontuação, menor tempo, a mais antiga
+const BEST_ENTRIES: &str = "
+    SELECT DISTINCT ON (e.game_id, e.player_key)
+        e.game_id, e.player_key, e.player_name, e.user_id, e.anonymous_key,
+        e.score, e.time_seconds, e.room_id, e.played_at
+    FROM leaderboard_entries e
+    WHERE ($1::int IS NULL OR e.game_id = $1)
+      AND ($2::timestamptz IS NULL OR e.played_at >= $2)
+      AND ($3::int[] IS NULL OR e.room_id = ANY($3))
+      AND ($4 OR e.anonymous_key IS NULL)
+      AND NOT EXISTS (SELECT 1 FROM leaderboard_opt_outs o WHERE o.anonymous_key = e.anonymous_key)
+    ORDER BY e.game_id, e.player_key, e.score DESC, e.time_seconds ASC NULLS LAST, e.played_at ASC";
+
+pub async fn game_leaderboard(
+    pool: &sqlx::PgPool,
+    game_id: i32,
+    filter: &LeaderboardFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<GameLeaderboardEntry>, i64), sqlx::Error> {
+    let since = filter.period.since(Utc::now());
+
+    let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM ({}) best", BEST_ENTRIES))
+        .bind(Some(game_id))
+        .bind(since)
+        .bind(&filter.room_ids)
+        .bind(filter.include_anonymous)
+        .fetch_one(pool)
+        .await?;
+
+    let entries = sqlx::query_as::<_, GameLeaderboardEntry>(&format!(
+        "SELECT RANK() OVER (ORDER BY score DESC, time_seconds ASC NULLS LAST) AS rank,
+                player_name, user_id, anonymous_key IS NOT NULL AS anonymous,
+                score, time_seconds, room_id, played_at
+         FROM ({}) best
+         ORDER BY rank, played_at ASC, player_key
+         LIMIT $5 OFFSET $6",
+        BEST_ENTRIES
+    ))
+    .bind(Some(game_id))
+    .bind(since)
+    .bind(&filter.room_ids)
+    .bind(filter.include_anonymous)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((entries, total))
+}
+
+pub async fn global_leaderboard(
+    pool: &sqlx::PgPool,
+    filter: &LeaderboardFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<GlobalLeaderboardEntry>, i64), sqlx::Error> {
+    let since = filter.period.since(Utc::now());
+    let players = format!(
+        "SELECT player_key,
+                (ARRAY_AGG(player_name ORDER BY played_at DESC))[1] AS player_name,
+                MAX(user_id) AS user_id,
+                BOOL_OR(anonymous_key IS NOT NULL) AS anonymous,
+                SUM(score)::BIGINT AS total_score,
+                COUNT(*) AS games_played,
+                SUM(time_seconds)::BIGINT AS total_time_seconds
+         FROM ({}) best
+         GROUP BY player_key",
+        BEST_ENTRIES
+    );
+
+    let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM ({}) players", players))
+        .bind(None::<i32>)
+        .bind(since)
+        .bind(&filter.room_ids)
+        .bind(filter.include_anonymous)
+        .fetch_one(pool)
+        .await?;
+
+    let entries = sqlx::query_as::<_, GlobalLeaderboardEntry>(&format!(
+        "SELECT RANK() OVER (ORDER BY total_score DESC, total_time_seconds ASC NULLS LAST) AS rank,
+                player_name, user_id, anonymous, total_score, games_played, total_time_seconds
+         FROM ({}) players
+         ORDER BY rank, player_name, player_key
+         LIMIT $5 OFFSET $6",
+        players
+    ))
+    .bind(None::<i32>)
+    .bind(since)
+    .bind(&filter.room_ids)
+    .bind(filter.include_anonymous)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((entries, total))
+}
+
+// Chaves de jogadores anônimos conhecidos: sessão anônima de sala ou aparelho com partidas
+pub async fn known_anonymous_keys(pool: &sqlx::PgPool, keys: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT key FROM UNNEST($1::varchar[]) AS key
+         WHERE EXISTS (SELECT 1 FROM room_participants WHERE session_id = key AND user_id IS NULL)
+            OR EXISTS (SELECT 1 FROM game_results WHERE device_session_id = key AND user_id IS NULL)"
+    )
+    .bind(keys)
+    .fetch_all(pool)
+    .await
+}
+
+// Anônimo fora (ou de volta) dos placares. keys = device id e/ou session id do jogador.
+pub async fn set_opt_out(pool: &sqlx::PgPool, keys: &[String], opted_out: bool) -> Result<(), sqlx::Error> {
+    if opted_out {
+        sqlx::query(
+            "INSERT INTO leaderboard_opt_outs (anonymous_key)
+             SELECT UNNEST($1::varchar[])
+             ON CONFLICT (anonymous_key) DO NOTHING"
+        )
+        .bind(keys)
+        .execute(pool)
+        .await?;
+    } else {
+        sqlx::query("DELETE FROM leaderboard_opt_outs WHERE anonymous_key = ANY($1)")
+            .bind(keys)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+// Job de fundo: recalcula a view materializada dos placares
+pub async fn run_refresher(pool: sqlx::PgPool) {
+    let interval_secs = std::env::var("LEADERBOARD_REFRESH_SECS").ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_REFRESH_SECS);
+
+    log::info!("🏆 Leaderboard refresh every {}s", interval_secs);
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+
+        // CONCURRENTLY: as leituras continuam enquanto a view é recalculada
+        if let Err(e) = sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY leaderboard_entries")
+            .execute(&pool)
+            .await
+        {
+            log::error!("Leaderboard refresh failed: {}", e);
+        }
+    }
+}
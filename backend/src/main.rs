@@ -3,6 +3,21 @@ mod models;
 mod handlers;
 mod middleware;
 mod websocket;
+mod quiz_clock;
+mod fanout;
+mod room_hub;
+mod room_snapshot;
+mod room_clock;
+mod room_status;
+mod room_expiry;
+mod leaderboard;
+mod room_teams;
+mod word_search_mode;
+mod word_search_scoring;
+mod word_search_grid;
+mod word_search_hints;
+mod word_search_clues;
+mod word_search_words;
 
 use actix_web::{web, App, HttpServer, middleware::Logger};
 use actix_cors::Cors;
@@ -44,6 +59,20 @@ async fn main() -> std::io::Result<()> {
     
     let add_concepts = "ALTER TABLE word_search_configs ADD COLUMN IF NOT EXISTS concepts JSONB DEFAULT '{}'::jsonb";
     
+    let add_word_search_scoring = "ALTER TABLE word_search_configs ADD COLUMN IF NOT EXISTS scoring JSONB DEFAULT NULL";
+    
+    let add_strip_accents = "ALTER TABLE word_search_configs ADD COLUMN IF NOT EXISTS strip_accents BOOLEAN NOT NULL DEFAULT false";
+    
+    let add_question_time_limit = "ALTER TABLE quiz_questions ADD COLUMN IF NOT EXISTS time_limit INTEGER DEFAULT NULL";
+    
+    let add_pacing_mode = "ALTER TABLE quiz_configs ADD COLUMN IF NOT EXISTS pacing_mode VARCHAR(20) NOT NULL DEFAULT 'self_paced'";
+    
+    let add_room_quiz_clock = "ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS quiz_question_index INTEGER NOT NULL DEFAULT 0, ADD COLUMN IF NOT EXISTS quiz_question_started_at TIMESTAMPTZ";
+    
+    let add_presenter_token = "ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS presenter_token VARCHAR(64)";
+    
+    let add_room_time_limit = "ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS time_limit INTEGER DEFAULT NULL, ADD COLUMN IF NOT EXISTS pause_reason VARCHAR(20) DEFAULT NULL";
+    
     let create_game_results = "
         CREATE TABLE IF NOT EXISTS game_results (
             id SERIAL PRIMARY KEY,
@@ -57,10 +86,11 @@ async fn main() -> std::io::Result<()> {
             updated_at TIMESTAMP DEFAULT NOW()
         )";
     
-    let drop_game_results = "DROP TABLE IF EXISTS game_results CASCADE";
-    
     let create_results_index = "CREATE INDEX IF NOT EXISTS idx_game_results_game_user ON game_results(game_id, user_id)";
     let create_results_room_index = "CREATE INDEX IF NOT EXISTS idx_game_results_room ON game_results(room_id)";
+    let add_results_device_session = "ALTER TABLE game_results ADD COLUMN IF NOT EXISTS device_session_id VARCHAR(255)";
+    let create_results_device_index = "CREATE INDEX IF NOT EXISTS idx_game_results_device ON game_results(device_session_id, game_id) WHERE device_session_id IS NOT NULL";
+    let create_results_user_index = "CREATE INDEX IF NOT EXISTS idx_game_results_user_created ON game_results(user_id, created_at)";
     
     match sqlx::query(create_table).execute(&pool).await {
         Ok(_) => log::info!("Table quiz_progress created/verified"),
@@ -87,14 +117,49 @@ async fn main() -> std::io::Result<()> {
         Err(e) => log::warn!("Add concepts column error: {}", e),
     }
     
-    // Recriar tabela game_results para garantir estrutura correta
-    match sqlx::query(drop_game_results).execute(&pool).await {
-        Ok(_) => log::info!("Table game_results dropped (se existia)"),
-        Err(e) => log::warn!("Drop game_results error: {}", e),
+    match sqlx::query(add_word_search_scoring).execute(&pool).await {
+        Ok(_) => log::info!("Column word_search_configs.scoring added/verified"),
+        Err(e) => log::warn!("Add scoring column error: {}", e),
+    }
+    
+    match sqlx::query(add_strip_accents).execute(&pool).await {
+        Ok(_) => log::info!("Column word_search_configs.strip_accents added/verified"),
+        Err(e) => log::warn!("Add strip_accents column error: {}", e),
+    }
+    
+    match sqlx::query(add_question_time_limit).execute(&pool).await {
+        Ok(_) => log::info!("Column quiz_questions.time_limit added/verified"),
+        Err(e) => log::warn!("Add time_limit column error: {}", e),
+    }
+    
+    match sqlx::query(add_pacing_mode).execute(&pool).await {
+        Ok(_) => log::info!("Column pacing_mode added/verified"),
+        Err(e) => log::warn!("Add pacing_mode column error: {}", e),
+    }
+    
+    match sqlx::query(add_room_quiz_clock).execute(&pool).await {
+        Ok(_) => log::info!("Columns game_rooms.quiz_question_* added/verified"),
+        Err(e) => log::warn!("Add quiz clock columns error: {}", e),
+    }
+    
+    match sqlx::query(add_presenter_token).execute(&pool).await {
+        Ok(_) => log::info!("Column game_rooms.presenter_token added/verified"),
+        Err(e) => log::warn!("Add presenter_token column error: {}", e),
+    }
+    
+    match sqlx::query(add_room_time_limit).execute(&pool).await {
+        Ok(_) => log::info!("Columns game_rooms.time_limit/pause_reason added/verified"),
+        Err(e) => log::warn!("Add room time limit columns error: {}", e),
+    }
+    
+    // Status da sala com o preenchimento inicial (feito só quando a coluna é criada)
+    match sqlx::raw_sql(include_str!("../migrations/20261018000004_room_status.sql")).execute(&pool).await {
+        Ok(_) => log::info!("Columns game_rooms.status/countdown_ends_at added/verified"),
+        Err(e) => log::warn!("Add room status columns error: {}", e),
     }
     
     match sqlx::query(create_game_results).execute(&pool).await {
-        Ok(_) => log::info!("Table game_results created"),
+        Ok(_) => log::info!("Table game_results created/verified"),
         Err(e) => log::warn!("Create game_results table error: {}", e),
     }
     
@@ -108,6 +173,154 @@ async fn main() -> std::io::Result<()> {
         Err(e) => log::warn!("Create results room index error: {}", e),
     }
     
+    match sqlx::query(add_results_device_session).execute(&pool).await {
+        Ok(_) => log::info!("Column game_results.device_session_id added/verified"),
+        Err(e) => log::warn!("Add results device session column error: {}", e),
+    }
+    
+    match sqlx::query(create_results_device_index).execute(&pool).await {
+        Ok(_) => log::info!("Index idx_game_results_device created/verified"),
+        Err(e) => log::warn!("Create results device index error: {}", e),
+    }
+    
+    match sqlx::query(create_results_user_index).execute(&pool).await {
+        Ok(_) => log::info!("Index idx_game_results_user_created created/verified"),
+        Err(e) => log::warn!("Create results user index error: {}", e),
+    }
+    
+    // Resultados finais das salas encerradas (sobrevivem à limpeza dos dados anônimos)
+    let create_room_results = "
+        CREATE TABLE IF NOT EXISTS room_results (
+            room_id INTEGER PRIMARY KEY REFERENCES game_rooms(id) ON DELETE CASCADE,
+            game_id INTEGER NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+            room_name VARCHAR(100) NOT NULL,
+            results JSONB NOT NULL DEFAULT '[]',
+            total_score INTEGER NOT NULL DEFAULT 0,
+            completion_time INTEGER,
+            close_reason VARCHAR(20) NOT NULL,
+            closed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            anonymous_purged_at TIMESTAMPTZ
+        )";
+    
+    let create_room_expires_index = "CREATE INDEX IF NOT EXISTS idx_game_rooms_expires ON game_rooms(expires_at) WHERE is_active = true";
+    
+    match sqlx::query(create_room_results).execute(&pool).await {
+        Ok(_) => log::info!("Table room_results created/verified"),
+        Err(e) => log::warn!("Create room_results table error: {}", e),
+    }
+    
+    match sqlx::query(create_room_expires_index).execute(&pool).await {
+        Ok(_) => log::info!("Index idx_game_rooms_expires created/verified"),
+        Err(e) => log::warn!("Create game_rooms expires index error: {}", e),
+    }
+    
+    // Modo equipes: equipes da sala e equipe de cada participante, pontuação e palavra
+    let create_room_teams = "
+        CREATE TABLE IF NOT EXISTS room_teams (
+            id SERIAL PRIMARY KEY,
+            room_id INTEGER NOT NULL REFERENCES game_rooms(id) ON DELETE CASCADE,
+            name VARCHAR(50) NOT NULL,
+            color VARCHAR(7) NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at TIMESTAMPTZ DEFAULT NOW(),
+            CONSTRAINT room_teams_room_name_key UNIQUE (room_id, name)
+        )";
+    
+    let add_team_columns = [
+        "ALTER TABLE room_participants ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL",
+        "ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL",
+        "ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS team_id INTEGER REFERENCES room_teams(id) ON DELETE SET NULL",
+    ];
+    
+    match sqlx::query(create_room_teams).execute(&pool).await {
+        Ok(_) => log::info!("Table room_teams created/verified"),
+        Err(e) => log::warn!("Create room_teams table error: {}", e),
+    }
+    
+    for add_team_column in add_team_columns {
+        match sqlx::query(add_team_column).execute(&pool).await {
+            Ok(_) => log::info!("Column team_id added/verified"),
+            Err(e) => log::warn!("Add team_id column error: {}", e),
+        }
+    }
+
+    // Modos do caça-palavras: cada palavra pertence a um tabuleiro (board_key '' = compartilhado)
+    let word_search_mode_statements = [
+        "ALTER TABLE game_rooms ADD COLUMN IF NOT EXISTS word_search_mode VARCHAR(20) NOT NULL DEFAULT 'cooperative'",
+        "ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS board_key VARCHAR(255) NOT NULL DEFAULT ''",
+        "ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS points INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE room_found_words ADD COLUMN IF NOT EXISTS elapsed_seconds INTEGER",
+        "ALTER TABLE room_found_words DROP CONSTRAINT IF EXISTS room_found_words_room_id_word_key",
+        "CREATE UNIQUE INDEX IF NOT EXISTS room_found_words_room_word_board_key ON room_found_words(room_id, word, board_key)",
+        "ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS last_found_seconds INTEGER",
+    ];
+
+    for statement in word_search_mode_statements {
+        match sqlx::query(statement).execute(&pool).await {
+            Ok(_) => log::info!("Word search mode schema created/verified"),
+            Err(e) => log::warn!("Word search mode schema error: {}", e),
+        }
+    }
+
+    // Dicas do caça-palavras (uma cobrança por jogador, palavra e tipo de dica)
+    let create_room_hints = "
+        CREATE TABLE IF NOT EXISTS room_hints (
+            id SERIAL PRIMARY KEY,
+            room_id INTEGER NOT NULL REFERENCES game_rooms(id) ON DELETE CASCADE,
+            session_id VARCHAR(255) NOT NULL,
+            player_name VARCHAR(255) NOT NULL,
+            word VARCHAR(255) NOT NULL,
+            kind VARCHAR(20) NOT NULL,
+            penalty INTEGER NOT NULL DEFAULT 0,
+            created_at TIMESTAMPTZ DEFAULT NOW(),
+            CONSTRAINT room_hints_player_word_kind_key UNIQUE (room_id, session_id, word, kind)
+        )";
+    
+    let create_room_hints_index = "CREATE INDEX IF NOT EXISTS idx_room_hints_room ON room_hints(room_id)";
+    
+    let add_hints_used = "ALTER TABLE room_player_scores ADD COLUMN IF NOT EXISTS hints_used INTEGER NOT NULL DEFAULT 0";
+    
+    match sqlx::query(create_room_hints).execute(&pool).await {
+        Ok(_) => log::info!("Table room_hints created/verified"),
+        Err(e) => log::warn!("Create room_hints table error: {}", e),
+    }
+    
+    match sqlx::query(create_room_hints_index).execute(&pool).await {
+        Ok(_) => log::info!("Index idx_room_hints_room created/verified"),
+        Err(e) => log::warn!("Create room_hints index error: {}", e),
+    }
+    
+    match sqlx::query(add_hints_used).execute(&pool).await {
+        Ok(_) => log::info!("Column room_player_scores.hints_used added/verified"),
+        Err(e) => log::warn!("Add hints_used column error: {}", e),
+    }
+    
+    // Eventos de sala grandes demais para o NOTIFY (WS_FANOUT=postgres)
+    let create_room_event_payloads = "
+        CREATE TABLE IF NOT EXISTS room_event_payloads (
+            id BIGSERIAL PRIMARY KEY,
+            payload TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )";
+    
+    let create_room_event_payloads_index = "CREATE INDEX IF NOT EXISTS idx_room_event_payloads_created ON room_event_payloads(created_at)";
+    
+    match sqlx::query(create_room_event_payloads).execute(&pool).await {
+        Ok(_) => log::info!("Table room_event_payloads created/verified"),
+        Err(e) => log::warn!("Create room_event_payloads table error: {}", e),
+    }
+    
+    match sqlx::query(create_room_event_payloads_index).execute(&pool).await {
+        Ok(_) => log::info!("Index idx_room_event_payloads_created created/verified"),
+        Err(e) => log::warn!("Create room_event_payloads index error: {}", e),
+    }
+    
+    // Placar (view materializada + opt-out dos anônimos); a view é longa, então vem do arquivo da migração
+    match sqlx::raw_sql(include_str!("../migrations/20261018000012_leaderboards.sql")).execute(&pool).await {
+        Ok(_) => log::info!("Leaderboard view and opt-out table created/verified"),
+        Err(e) => log::warn!("Create leaderboard schema error: {}", e),
+    }
+    
     // Open Question tables - Create if not exists (removed DROP to preserve data)
     let create_open_question_games = "
         CREATE TABLE IF NOT EXISTS open_question_games (
@@ -190,15 +403,34 @@ async fn main() -> std::io::Result<()> {
     log::info!("Migrations completed!");
     log::info!("Starting server at {}:{}", host, port);
 
-    // Criar gerenciador de salas WebSocket
-    let room_manager = web::Data::new(Arc::new(Mutex::new(HashMap::<i32, Vec<websocket::ConnectionInfo>>::new())));
+    // Relógios autoritativos dos quizzes por sala
+    let quiz_clocks = Arc::new(Mutex::new(HashMap::<i32, quiz_clock::QuizClock>::new()));
+    
+    // Distribuição de eventos entre instâncias (WS_FANOUT=memory|postgres)
+    let (fanout, remote_events) = fanout::from_env(pool.clone());
+    
+    // Registro das salas WebSocket (um hub por sala)
+    let registry = room_hub::RoomRegistry::new(pool.clone(), fanout, quiz_clocks.clone());
+    if let Some(events) = remote_events {
+        actix::spawn(fanout::deliver_remote(events, registry.clone()));
+    }
+    
+    // Encerramento automático das salas expiradas
+    actix::spawn(room_expiry::run_sweeper(pool.clone(), registry.clone()));
+    
+    // Atualização periódica do placar
+    actix::spawn(leaderboard::run_refresher(pool.clone()));
+    
+    let registry = web::Data::new(registry);
+    let quiz_clocks = web::Data::new(quiz_clocks);
 
     HttpServer::new(move || {
         let cors = Cors::permissive();
 
         App::new()
             .app_data(web::Data::new(pool.clone()))
-            .app_data(room_manager.clone())
+            .app_data(registry.clone())
+            .app_data(quiz_clocks.clone())
             .wrap(cors)
             .wrap(Logger::default())
             // Public routes
@@ -211,22 +443,35 @@ async fn main() -> std::io::Result<()> {
             .route("/api/sessions/{id}/results", web::get().to(handlers::sessions::get_session_results))
             .route("/api/scores", web::post().to(handlers::sessions::submit_score))
             .route("/api/word-search/{game_id}", web::get().to(handlers::word_search::get_word_search_config))
+            .route("/api/word-search/{game_id}/check", web::post().to(handlers::word_search::check_word_search_word))
             .route("/api/quiz/{game_id}", web::get().to(handlers::quiz::get_quiz_config))
-            .route("/api/quiz/{game_id}/questions", web::get().to(handlers::quiz::get_quiz_questions))
+            .route("/api/quiz/{game_id}/questions", web::get().to(handlers::quiz::get_play_questions))
+            .route("/api/quiz/{game_id}/play", web::get().to(handlers::quiz::get_play_questions))
+            .route("/api/quiz/{game_id}/review", web::post().to(handlers::quiz::review_quiz_answers))
             // Game results public routes
             .route("/api/game-results", web::post().to(handlers::game_results::create_game_result))
+            .route("/api/game-results/history", web::get().to(handlers::game_results::get_results_history))
+            .route("/api/game-results/{game_id}/history", web::get().to(handlers::game_results::get_game_history))
             .route("/api/game-results/{game_id}", web::get().to(handlers::game_results::get_game_result))
             .route("/api/game-results/{game_id}/{room_id}", web::get().to(handlers::game_results::get_game_result))
+            // Leaderboard public routes
+            .route("/api/leaderboards", web::get().to(handlers::leaderboards::get_global_leaderboard))
+            .route("/api/leaderboards/opt-out", web::post().to(handlers::leaderboards::opt_out))
+            .route("/api/leaderboards/opt-out", web::delete().to(handlers::leaderboards::opt_in))
+            .route("/api/leaderboards/{game_id}", web::get().to(handlers::leaderboards::get_game_leaderboard))
             // Room public routes
             .route("/api/rooms/join-anonymous", web::post().to(handlers::rooms::join_room_anonymous))
+            .route("/api/rooms/spectate", web::post().to(handlers::rooms::spectate_room))
             .route("/api/rooms/info/{room_code}", web::get().to(handlers::rooms::get_room_info_public))
             .route("/api/rooms/info-by-id/{room_id}", web::get().to(handlers::rooms::get_room_info_by_id_public))
             .route("/api/rooms/by-game/{game_id}", web::get().to(handlers::rooms::list_rooms_by_game))
             .route("/api/rooms/active", web::get().to(handlers::rooms::list_active_rooms))
             .route("/api/rooms/{room_id}/found-words", web::get().to(handlers::rooms::get_room_found_words))
             .route("/api/rooms/{room_id}/scores", web::get().to(handlers::rooms::get_room_scores))
+            .route("/api/rooms/{room_id}/teams", web::get().to(handlers::rooms::get_room_teams))
             .route("/api/rooms/{room_id}/quiz-progress", web::post().to(handlers::rooms::save_quiz_progress))
             .route("/api/rooms/{room_id}/quiz-progress", web::get().to(handlers::rooms::get_quiz_progress))
+            .route("/api/rooms/{room_id}/quiz-review", web::get().to(handlers::rooms::get_quiz_review))
             .route("/api/rooms/{room_id}/answer", web::post().to(handlers::rooms::submit_room_answer))
             .route("/api/rooms/{room_id}/answers", web::get().to(handlers::rooms::get_room_answers))
             // Kahoot public routes
@@ -260,12 +505,17 @@ async fn main() -> std::io::Result<()> {
                             .route(web::put().to(handlers::games::update_game))
                             .route(web::delete().to(handlers::games::delete_game))
                     )
+                    .route("/games/{id}/export", web::get().to(handlers::packages::export_game))
+                    .route("/packages/import", web::post().to(handlers::packages::import_package))
                     .route("/word-search", web::post().to(handlers::word_search::create_word_search_config))
+                    .route("/word-search/{game_id}", web::get().to(handlers::word_search::get_own_word_search_config))
                     .route("/word-search/{game_id}", web::put().to(handlers::word_search::update_word_search_config))
                     .route("/quiz", web::post().to(handlers::quiz::create_quiz_config))
                     .route("/quiz/{game_id}", web::put().to(handlers::quiz::update_quiz_config))
+                    .route("/quiz/{game_id}/questions", web::get().to(handlers::quiz::get_quiz_questions))
                     .route("/quiz/{game_id}/questions", web::post().to(handlers::quiz::create_question))
                     .route("/quiz/{game_id}/questions", web::delete().to(handlers::quiz::delete_all_questions))
+                    .route("/quiz/{game_id}/import", web::post().to(handlers::quiz_import::import_questions))
                     .route("/sessions", web::post().to(handlers::sessions::create_session))
                     .route("/sessions/{id}/start", web::post().to(handlers::sessions::start_session))
                     .route("/sessions/{id}/end", web::post().to(handlers::sessions::end_session))
@@ -279,17 +529,23 @@ async fn main() -> std::io::Result<()> {
                     .route("/rooms/by-id/{room_id}/reset", web::post().to(handlers::rooms::reset_room))
                     .route("/rooms/{room_code}", web::get().to(handlers::rooms::get_room_details))
                     .route("/rooms/{room_id}/close", web::post().to(handlers::rooms::close_room))
+                    .route("/rooms/{room_id}/start", web::post().to(handlers::rooms::start_room))
+                    .route("/rooms/{room_id}/end", web::post().to(handlers::rooms::end_room))
+                    .route("/rooms/{room_id}/teams", web::put().to(handlers::rooms::update_room_teams))
+                    .route("/rooms/{room_id}/presenter-token", web::post().to(handlers::rooms::create_presenter_token))
                     // Kahoot protected routes (criação de jogos)
                     .route("/kahoot/games", web::post().to(handlers::kahoot::create_game))
                     .route("/kahoot/games/{id}", web::put().to(handlers::kahoot::update_game))
                     .route("/kahoot/games/{id}", web::delete().to(handlers::kahoot::delete_game))
                     .route("/kahoot/games/{id}/edit", web::get().to(handlers::kahoot::get_game_for_edit))
+                    .route("/kahoot/games/{id}/export", web::get().to(handlers::packages::export_kahoot))
                     // Open Question protected routes
                     .route("/open-question/games", web::post().to(handlers::open_question::create_game))
                     .route("/open-question/games/{id}/edit", web::get().to(handlers::open_question::get_game_for_edit))
                     .route("/open-question/games/{id}", web::put().to(handlers::open_question::update_game))
                     .route("/open-question/games/{id}", web::delete().to(handlers::open_question::delete_game))
                     .route("/open-question/games/{id}/presenter", web::get().to(handlers::open_question::get_game_presenter))
+                    .route("/open-question/games/{id}/export", web::get().to(handlers::packages::export_open_question))
                     .route("/open-question/questions/{id}/toggle", web::post().to(handlers::open_question::toggle_question))
                     .route("/open-question/questions/{id}/responses", web::get().to(handlers::open_question::get_question_responses))
             )
@@ -1,6 +1,6 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    Error, HttpMessage, HttpRequest,
 };
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
@@ -9,6 +9,30 @@ use sqlx::PgPool;
 
 use crate::models::{Claims, User};
 
+// Segredo dos tokens nas rotas sem o middleware (mesmo padrão usado fora dele)
+pub fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string())
+}
+
+// user_id de um token de login, nas rotas sem o middleware Auth
+pub fn user_id_from_token(token: &str) -> Option<i32> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret().as_bytes()),
+        &Validation::default(),
+    )
+    .ok()
+    .map(|data| data.claims.sub)
+}
+
+// user_id do header Authorization (Bearer), nas rotas sem o middleware Auth
+pub fn bearer_user_id(req: &HttpRequest) -> Option<i32> {
+    let token = req.headers().get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))?;
+    user_id_from_token(token)
+}
+
 pub struct Auth;
 
 impl<S, B> Transform<S, ServiceRequest> for Auth
@@ -3,6 +3,8 @@ use chrono::{DateTime, Utc};
 use validator::Validate;
 use serde_json::Value as JsonValue;
 
+use crate::word_search_scoring::ScoringConfig;
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
     pub id: i32,
@@ -98,6 +100,10 @@ pub struct WordSearchConfig {
     pub concepts: Option<serde_json::Value>,
     pub hide_words: bool,
     pub created_at: DateTime<Utc>,
+    // Curva de pontuação e bônus (NULL = curva padrão)
+    pub scoring: Option<serde_json::Value>,
+    // Palavras salvas sem acentos
+    pub strip_accents: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -111,6 +117,8 @@ pub struct CreateWordSearchRequest {
     pub allowed_directions: Option<serde_json::Value>,
     pub concepts: Option<serde_json::Value>,
     pub hide_words: Option<bool>,
+    pub scoring: Option<ScoringConfig>,
+    pub strip_accents: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -123,6 +131,7 @@ pub struct QuizConfig {
     pub end_screen_button_url: Option<String>,
     pub end_screen_button_new_tab: Option<bool>,
     pub min_players: Option<i32>,
+    pub pacing_mode: String,
     pub created_at: DateTime<Utc>,
 }
 
@@ -138,6 +147,7 @@ pub struct QuizQuestion {
     pub correct_option: String,
     pub justification: Option<String>,
     pub points: i32,
+    pub time_limit: Option<i32>,  // Segundos para responder (None = sem limite)
     pub created_at: DateTime<Utc>,
 }
 
@@ -164,7 +174,8 @@ pub struct CreateQuizQuestionRequest {
     pub points: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+// Formato legado de /api/sessions (a sessão é uma sala, ver handlers::sessions)
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GameSession {
     pub id: i32,
     pub game_id: i32,
@@ -191,6 +202,7 @@ pub struct JoinSessionRequest {
     pub player_name: String,
 }
 
+// Resultado no formato legado (session_id = id da sala)
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct GameResult {
     pub id: i32,
@@ -232,6 +244,11 @@ pub struct GameRoom {
     pub total_score: Option<i32>,
     pub completed_at: Option<DateTime<Utc>>,
     pub completion_time: Option<i32>,
+    pub time_limit: Option<i32>,
+    pub pause_reason: Option<String>,
+    pub status: String,  // lobby, countdown, running, paused, finished, archived
+    pub countdown_ends_at: Option<DateTime<Utc>>,
+    pub word_search_mode: String,  // cooperative, race, steal
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -243,12 +260,38 @@ pub struct CreateRoomRequest {
     #[validate(range(min = 2, max = 100))]
     pub max_players: Option<i32>,
     pub duration_hours: Option<i32>,
+    // Tempo limite da partida em segundos (None = sem limite)
+    #[validate(range(min = 30, max = 86400))]
+    pub time_limit: Option<i32>,
+    // Modo equipes (None = cada jogador por si)
+    #[validate(length(min = 2, max = 8), nested)]
+    pub teams: Option<Vec<TeamRequest>>,
+    // Modo do caça-palavras: cooperative (padrão), race ou steal
+    pub word_search_mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TeamRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    // Cor em hexadecimal (#RRGGBB)
+    #[validate(length(equal = 7))]
+    pub color: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateRoomTeamsRequest {
+    // Lista vazia desativa o modo equipes
+    #[validate(length(max = 8), nested)]
+    pub teams: Vec<TeamRequest>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JoinRoomRequest {
     pub room_code: String,
     pub password: Option<String>,
+    // Equipe escolhida (None = equilibrar automaticamente)
+    pub team_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -259,15 +302,44 @@ pub struct JoinRoomAnonymousRequest {
     #[validate(length(min = 2, max = 50))]
     pub player_name: String,
     pub existing_session_id: Option<String>,
+    // Equipe escolhida (None = equilibrar automaticamente)
+    pub team_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SpectateRoomRequest {
+    #[validate(length(min = 4, max = 10))]
+    pub room_code: String,
+    pub password: Option<String>,
+}
+
+// Token do modo espectador (projetor), válido apenas para uma sala
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpectatorClaims {
+    pub room_id: i32,
+    pub role: String,
+    pub exp: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpectatorSessionResponse {
+    pub spectator_token: String,
+    pub room_id: i32,
+    pub room_code: String,
+    pub game_id: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnonymousSessionResponse {
     pub session_id: String,
+    // room_participants.id: player_id usado no WebSocket da sala
+    pub player_id: i32,
     pub room_id: i32,
     pub room_code: String,
     pub player_name: String,
     pub player_color: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -280,6 +352,7 @@ pub struct RoomParticipant {
     pub joined_at: DateTime<Utc>,
     pub is_host: bool,
     pub player_color: Option<String>,
+    pub team_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -328,6 +401,11 @@ pub struct RoomFoundWord {
     pub player_color: String,
     pub cells: JsonValue,
     pub found_at: DateTime<Utc>,
+    pub team_id: Option<i32>,
+    // '' = tabuleiro compartilhado; senão o tabuleiro do jogador (modos competitivos)
+    pub board_key: String,
+    pub points: i32,
+    pub elapsed_seconds: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -340,4 +418,18 @@ pub struct RoomPlayerScore {
     pub words_found: i32,
     pub total_score: i32,
     pub last_updated: DateTime<Utc>,
+    pub team_id: Option<i32>,
+    // Tempo de jogo da última palavra (desempate da corrida)
+    pub last_found_seconds: Option<i32>,
+    // Dicas pedidas (a penalidade já está descontada de total_score)
+    pub hints_used: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RoomTeam {
+    pub id: i32,
+    pub room_id: i32,
+    pub name: String,
+    pub color: String,
+    pub position: i32,
 }
@@ -0,0 +1,399 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::room_hub::RoomRegistry;
+use crate::websocket::WsMessage;
+
+// Tempo padrão por pergunta no modo sincronizado quando a pergunta não define time_limit
+pub const DEFAULT_QUESTION_TIME_LIMIT: i32 = 30;
+
+// Intervalo entre QuizTimerSync enviados pelo servidor
+const SYNC_INTERVAL_SECS: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacingMode {
+    // Cada jogador avança no seu ritmo (comportamento original, mensagens repassadas)
+    SelfPaced,
+    // O host avança; o servidor controla o prazo de cada pergunta
+    HostPaced,
+    // O servidor avança todos quando o tempo da pergunta acaba
+    Synchronized,
+}
+
+impl PacingMode {
+    pub const ALL: [&'static str; 3] = ["self_paced", "host_paced", "synchronized"];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "self_paced" => Some(PacingMode::SelfPaced),
+            "host_paced" => Some(PacingMode::HostPaced),
+            "synchronized" => Some(PacingMode::Synchronized),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PacingMode::SelfPaced => "self_paced",
+            PacingMode::HostPaced => "host_paced",
+            PacingMode::Synchronized => "synchronized",
+        }
+    }
+}
+
+// Relógio autoritativo do quiz de uma sala
+#[derive(Debug, Clone)]
+pub struct QuizClock {
+    pub pacing_mode: PacingMode,
+    pub question_index: i32,
+    pub question_started_at: DateTime<Utc>,
+    // Limite de cada pergunta em segundos (None = sem prazo)
+    pub time_limits: Vec<Option<i32>>,
+    pub finished: bool,
+    // Prazo congelado enquanto a sala não está em andamento (lobby, contagem, pausa)
+    held_at: Option<DateTime<Utc>>,
+    last_sync: Option<DateTime<Utc>>,
+    expired_announced: bool,
+}
+
+impl QuizClock {
+    pub fn question_count(&self) -> i32 {
+        self.time_limits.len() as i32
+    }
+
+    pub fn current_time_limit(&self) -> Option<i32> {
+        self.time_limits.get(self.question_index as usize).copied().flatten()
+    }
+
+    pub fn elapsed(&self, now: DateTime<Utc>) -> i32 {
+        let now = self.held_at.unwrap_or(now);
+        (now - self.question_started_at).num_seconds().max(0) as i32
+    }
+
+    pub fn hold(&mut self, now: DateTime<Utc>) {
+        if self.held_at.is_none() {
+            self.held_at = Some(now);
+        }
+    }
+
+    // Volta a contar o prazo descontando o tempo parado. Retorna true se estava parado.
+    pub fn release(&mut self, now: DateTime<Utc>) -> bool {
+        match self.held_at.take() {
+            Some(held_at) => {
+                self.question_started_at += now - held_at;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<i32> {
+        self.current_time_limit().map(|limit| (limit - self.elapsed(now)).max(0))
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.remaining(now) == Some(0)
+    }
+
+    pub fn is_server_driven(&self) -> bool {
+        self.pacing_mode != PacingMode::SelfPaced
+    }
+
+    // Respostas só valem para a pergunta atual e dentro do prazo
+    pub fn accepts_answer(&self, question_index: i32, now: DateTime<Utc>) -> bool {
+        if !self.is_server_driven() {
+            return true;
+        }
+        !self.finished && question_index == self.question_index && !self.is_expired(now)
+    }
+
+    pub fn advance_to(&mut self, question_index: i32, now: DateTime<Utc>) {
+        self.question_index = question_index;
+        self.question_started_at = now;
+        if self.held_at.is_some() {
+            self.held_at = Some(now);
+        }
+        self.finished = question_index >= self.question_count();
+        self.last_sync = None;
+        self.expired_announced = false;
+    }
+
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        self.advance_to(0, now);
+    }
+
+    pub fn timer_sync(&self, now: DateTime<Utc>) -> WsMessage {
+        WsMessage::QuizTimerSync {
+            elapsed_time: self.elapsed(now),
+            player_id: 0,
+            question_index: Some(self.question_index),
+            remaining_time: self.remaining(now),
+            time_limit: self.current_time_limit(),
+        }
+    }
+}
+
+// Relógios ativos por sala (room_id -> relógio)
+pub type QuizClocks = Arc<Mutex<HashMap<i32, QuizClock>>>;
+
+// Carrega o relógio do quiz da sala. Retorna None se a sala não for de quiz.
+pub async fn load_clock(pool: &sqlx::PgPool, room_id: i32) -> Result<Option<QuizClock>, sqlx::Error> {
+    let config = sqlx::query_as::<_, (i32, String, i32, Option<DateTime<Utc>>)>(
+        "SELECT qc.id, qc.pacing_mode, gr.quiz_question_index, gr.quiz_question_started_at
+         FROM game_rooms gr
+         JOIN games g ON g.id = gr.game_id AND g.game_type = 'quiz'
+         JOIN quiz_configs qc ON qc.game_id = g.id
+         WHERE gr.id = $1"
+    )
+    .bind(room_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let (quiz_config_id, pacing_mode, question_index, started_at) = match config {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    let pacing_mode = PacingMode::parse(&pacing_mode).unwrap_or(PacingMode::SelfPaced);
+
+    let limits = sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT time_limit FROM quiz_questions WHERE quiz_config_id = $1 ORDER BY id"
+    )
+    .bind(quiz_config_id)
+    .fetch_all(pool)
+    .await?;
+
+    let time_limits = limits.into_iter().map(|limit| match (limit, pacing_mode) {
+        (Some(l), _) if l > 0 => Some(l),
+        (_, PacingMode::Synchronized) => Some(DEFAULT_QUESTION_TIME_LIMIT),
+        _ => None,
+    }).collect::<Vec<_>>();
+
+    let now = Utc::now();
+    let started_at = match started_at {
+        Some(s) => s,
+        None => {
+            if pacing_mode != PacingMode::SelfPaced {
+                persist_clock(pool, room_id, question_index, now).await?;
+            }
+            now
+        }
+    };
+
+    let question_count = time_limits.len() as i32;
+    Ok(Some(QuizClock {
+        pacing_mode,
+        question_index,
+        question_started_at: started_at,
+        time_limits,
+        finished: question_index >= question_count,
+        held_at: None,
+        last_sync: None,
+        expired_announced: false,
+    }))
+}
+
+pub async fn persist_clock(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    question_index: i32,
+    started_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE game_rooms SET quiz_question_index = $1, quiz_question_started_at = $2 WHERE id = $3"
+    )
+    .bind(question_index)
+    .bind(started_at)
+    .bind(room_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Avança a pergunta só se o banco ainda estiver em from_index (compare-and-set entre
+// instâncias). Retorna false se outra instância avançou antes.
+pub async fn advance_clock(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    from_index: i32,
+    to_index: i32,
+    started_at: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE game_rooms SET quiz_question_index = $1, quiz_question_started_at = $2
+         WHERE id = $3 AND quiz_question_index = $4"
+    )
+    .bind(to_index)
+    .bind(started_at)
+    .bind(room_id)
+    .bind(from_index)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Pergunta atual e início salvos no banco
+async fn load_position(pool: &sqlx::PgPool, room_id: i32) -> Result<Option<(i32, Option<DateTime<Utc>>)>, sqlx::Error> {
+    sqlx::query_as::<_, (i32, Option<DateTime<Utc>>)>(
+        "SELECT quiz_question_index, quiz_question_started_at FROM game_rooms WHERE id = $1"
+    )
+    .bind(room_id)
+    .fetch_optional(pool)
+    .await
+}
+
+// Garante que a sala tenha um relógio carregado e, nos modos controlados pelo servidor,
+// um ticker rodando. Chamado quando um socket de quiz conecta.
+pub async fn ensure_clock(
+    pool: sqlx::PgPool,
+    room_id: i32,
+    clocks: QuizClocks,
+    registry: RoomRegistry,
+) -> Option<QuizClock> {
+    if let Some(clock) = clocks.lock().unwrap().get(&room_id) {
+        return Some(clock.clone());
+    }
+
+    let clock = match load_clock(&pool, room_id).await {
+        Ok(Some(clock)) => clock,
+        Ok(None) => return None,
+        Err(e) => {
+            log::error!("Failed to load quiz clock for room {}: {}", room_id, e);
+            return None;
+        }
+    };
+
+    let inserted = match clocks.lock().unwrap().entry(room_id) {
+        std::collections::hash_map::Entry::Occupied(_) => false,
+        std::collections::hash_map::Entry::Vacant(entry) => {
+            entry.insert(clock.clone());
+            true
+        }
+    };
+
+    if inserted && clock.is_server_driven() {
+        log::info!("⏱️ Starting quiz clock for room {} ({})", room_id, clock.pacing_mode.as_str());
+        actix::spawn(run_clock(pool, room_id, clocks.clone(), registry));
+    }
+
+    clocks.lock().unwrap().get(&room_id).cloned()
+}
+
+// Loop do relógio: envia QuizTimerSync periódicos e avança perguntas no modo sincronizado.
+// Termina quando a sala fica sem conexões. Cada instância com jogadores da sala roda seu
+// próprio relógio a partir do estado salvo em game_rooms; o avanço de pergunta é decidido
+// por compare-and-set no banco e só a instância que vence publica QuizAdvance/QuizFinished
+// (as demais recebem pelo fan-out e adotam a posição salva).
+async fn run_clock(pool: sqlx::PgPool, room_id: i32, clocks: QuizClocks, registry: RoomRegistry) {
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        interval.tick().await;
+
+        let mut outgoing = Vec::new();
+        let mut released = None;
+        let mut advance = None;
+        let now = Utc::now();
+
+        {
+            let mut map = clocks.lock().unwrap();
+            if registry.connection_count(room_id) == 0 {
+                map.remove(&room_id);
+                log::info!("⏱️ Quiz clock for room {} stopped (no connections)", room_id);
+                return;
+            }
+
+            let clock = match map.get_mut(&room_id) {
+                Some(c) => c,
+                None => return,
+            };
+
+            if clock.finished {
+                continue;
+            }
+
+            // Fora de "running" o prazo da pergunta fica parado
+            if !registry.accepts_plays(room_id) {
+                clock.hold(now);
+                continue;
+            }
+            if clock.release(now) {
+                released = Some((clock.question_index, clock.question_started_at));
+            }
+
+            if clock.is_expired(now) {
+                match clock.pacing_mode {
+                    PacingMode::Synchronized => {
+                        advance = Some((clock.question_index, clock.question_index + 1));
+                    }
+                    PacingMode::HostPaced if !clock.expired_announced => {
+                        // Prazo encerrado: avisa uma vez com remaining_time = 0 e aguarda o host
+                        clock.expired_announced = true;
+                        clock.last_sync = Some(now);
+                        outgoing.push(clock.timer_sync(now));
+                    }
+                    _ => {}
+                }
+            }
+
+            let sync_due = clock.last_sync
+                .map(|last| (now - last).num_seconds() >= SYNC_INTERVAL_SECS)
+                .unwrap_or(true);
+
+            if advance.is_none() && sync_due {
+                clock.last_sync = Some(now);
+                outgoing.push(clock.timer_sync(now));
+            }
+        }
+
+        for message in &outgoing {
+            registry.broadcast_local(room_id, message);
+        }
+
+        if let Some((question_index, started_at)) = released {
+            if let Err(e) = advance_clock(&pool, room_id, question_index, question_index, started_at).await {
+                log::error!("Failed to persist quiz clock for room {}: {}", room_id, e);
+            }
+        }
+
+        if let Some((from, next)) = advance {
+            match advance_clock(&pool, room_id, from, next, now).await {
+                Ok(true) => {
+                    let message = {
+                        let mut map = clocks.lock().unwrap();
+                        match map.get_mut(&room_id) {
+                            Some(clock) if clock.question_index == from => {
+                                clock.advance_to(next, now);
+                                Some(if clock.finished {
+                                    WsMessage::QuizFinished { player_id: 0 }
+                                } else {
+                                    WsMessage::QuizAdvance { question_index: next }
+                                })
+                            }
+                            _ => None,
+                        }
+                    };
+                    if let Some(message) = message {
+                        registry.publish(room_id, &message);
+                    }
+                }
+                // Outra instância avançou: adota a posição salva sem publicar
+                Ok(false) => match load_position(&pool, room_id).await {
+                    Ok(Some((question_index, started_at))) => {
+                        if let Some(clock) = clocks.lock().unwrap().get_mut(&room_id) {
+                            if question_index != clock.question_index {
+                                clock.advance_to(question_index, now);
+                                clock.question_started_at = started_at.unwrap_or(now);
+                            }
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("Failed to reload quiz clock for room {}: {}", room_id, e),
+                },
+                Err(e) => log::error!("Failed to advance quiz clock for room {}: {}", room_id, e),
+            }
+        }
+    }
+}
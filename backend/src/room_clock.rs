@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::GameRoom;
+use crate::room_status::RoomStatus;
+use crate::websocket::WsMessage;
+
+// Intervalo entre TimerSync enviados pelo hub da sala
+pub const SYNC_INTERVAL_SECS: u64 = 5;
+
+// Cronômetro autoritativo da sala (cópia do que está salvo em game_rooms)
+#[derive(Debug, Clone, Default)]
+pub struct RoomTimer {
+    pub status: RoomStatus,
+    pub countdown_ends_at: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub total_pause_duration: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub completion_time: Option<i32>,
+    // Tempo limite em segundos (None = sem limite)
+    pub time_limit: Option<i32>,
+}
+
+impl RoomTimer {
+    pub fn from_room(room: &GameRoom) -> Self {
+        RoomTimer {
+            status: RoomStatus::parse(&room.status).unwrap_or_default(),
+            countdown_ends_at: room.countdown_ends_at,
+            started_at: room.started_at,
+            paused_at: room.paused_at,
+            total_pause_duration: room.total_pause_duration.unwrap_or(0),
+            completed_at: room.completed_at,
+            completion_time: room.completion_time,
+            time_limit: room.time_limit,
+        }
+    }
+
+    // Tempo de jogo descontando as pausas (mesma conta feita no frontend)
+    pub fn elapsed(&self, now: DateTime<Utc>) -> Option<i64> {
+        if let Some(completion_time) = self.completion_time {
+            return Some(completion_time as i64);
+        }
+        let started_at = self.started_at?;
+        let end = self.completed_at.or(self.paused_at).unwrap_or(now);
+        Some(((end - started_at).num_seconds() - self.total_pause_duration as i64).max(0))
+    }
+
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<i64> {
+        let limit = self.time_limit? as i64;
+        Some((limit - self.elapsed(now).unwrap_or(0)).max(0))
+    }
+
+    // Segundos até o fim da contagem regressiva
+    pub fn countdown_remaining(&self, now: DateTime<Utc>) -> Option<i64> {
+        match (self.status, self.countdown_ends_at) {
+            (RoomStatus::Countdown, Some(ends_at)) => Some((ends_at - now).num_seconds().max(0)),
+            _ => None,
+        }
+    }
+
+    pub fn countdown_finished(&self, now: DateTime<Utc>) -> bool {
+        self.countdown_remaining(now) == Some(0)
+    }
+
+    // Tempo limite atingido com a partida em andamento
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.status == RoomStatus::Running && self.remaining(now) == Some(0)
+    }
+
+    // Estados em que o cronômetro é sincronizado periodicamente
+    pub fn is_ticking(&self) -> bool {
+        matches!(self.status, RoomStatus::Countdown | RoomStatus::Running | RoomStatus::Paused)
+    }
+
+    pub fn timer_sync(&self, now: DateTime<Utc>) -> WsMessage {
+        WsMessage::TimerSync {
+            status: self.status,
+            elapsed_seconds: self.elapsed(now).unwrap_or(0),
+            remaining_seconds: self.remaining(now),
+            time_limit: self.time_limit,
+            countdown_seconds: self.countdown_remaining(now),
+            server_time: now,
+        }
+    }
+}
+
+pub async fn load_timer(pool: &sqlx::PgPool, room_id: i32) -> Result<RoomTimer, sqlx::Error> {
+    let room = sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(RoomTimer::from_room(&room))
+}
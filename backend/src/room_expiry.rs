@@ -0,0 +1,213 @@
+use serde_json::json;
+use std::time::Duration;
+
+use crate::room_hub::RoomRegistry;
+use crate::room_snapshot;
+use crate::room_status::{self, RoomStatus, StatusChange, StatusError};
+
+// Intervalo padrão entre varreduras (ROOM_SWEEP_INTERVAL_SECS)
+const DEFAULT_SWEEP_INTERVAL_SECS: u64 = 60;
+// Salas processadas por varredura
+const SWEEP_BATCH_SIZE: i64 = 100;
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|value| value.trim().parse().ok())
+}
+
+// Job de fundo: fecha as salas com expires_at vencido e, se ANONYMOUS_DATA_RETENTION_DAYS
+// estiver definida, apaga os dados dos participantes anônimos das salas fechadas há mais
+// tempo que isso. Várias instâncias podem rodar juntas: cada transição só é aplicada uma vez.
+pub async fn run_sweeper(pool: sqlx::PgPool, registry: RoomRegistry) {
+    let interval_secs = env_number::<u64>("ROOM_SWEEP_INTERVAL_SECS")
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_SWEEP_INTERVAL_SECS);
+    let retention_days = env_number::<i32>("ANONYMOUS_DATA_RETENTION_DAYS").filter(|days| *days > 0);
+
+    log::info!("⌛ Room expiry sweeper every {}s (anonymous data retention: {})",
+        interval_secs,
+        retention_days.map(|days| format!("{} days", days)).unwrap_or_else(|| "disabled".to_string()));
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = sweep_expired_rooms(&pool, &registry).await {
+            log::error!("Room expiry sweep failed: {}", e);
+        }
+
+        if let Some(days) = retention_days {
+            if let Err(e) = purge_anonymous_data(&pool, days).await {
+                log::error!("Anonymous data purge failed: {}", e);
+            }
+        }
+    }
+}
+
+async fn sweep_expired_rooms(pool: &sqlx::PgPool, registry: &RoomRegistry) -> Result<(), sqlx::Error> {
+    let expired = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM game_rooms
+         WHERE is_active = true AND expires_at IS NOT NULL AND expires_at <= NOW()
+         ORDER BY expires_at
+         LIMIT $1"
+    )
+    .bind(SWEEP_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for room_id in expired {
+        match close_room(pool, registry, room_id, room_status::REASON_EXPIRED, "server").await {
+            Ok(()) => log::info!("⌛ Room {} expired and was closed", room_id),
+            // Outra instância fechou antes
+            Err(StatusError::InvalidTransition { .. }) | Err(StatusError::NotFound) => {}
+            Err(e) => log::error!("Failed to close expired room {}: {}", room_id, e.message()),
+        }
+    }
+
+    Ok(())
+}
+
+// Fecha a sala: finaliza a partida em andamento, guarda os resultados finais e arquiva.
+// O RoomStatusChanged (archived) desconecta os sockets da sala em todas as instâncias.
+pub async fn close_room(
+    pool: &sqlx::PgPool,
+    registry: &RoomRegistry,
+    room_id: i32,
+    reason: &'static str,
+    closed_by: &str,
+) -> Result<(), StatusError> {
+    if let Some(RoomStatus::Running | RoomStatus::Paused) = room_status::load_status(pool, room_id).await? {
+        // Pode perder para outra instância; quem decide é o arquivamento abaixo
+        let _ = room_status::change(pool, registry, room_id, StatusChange::End { reason }, closed_by).await;
+    }
+
+    save_results(pool, room_id, reason).await?;
+    room_status::change(pool, registry, room_id, StatusChange::Archive { reason }, closed_by).await?;
+    Ok(())
+}
+
+// Classificação final sem identificadores de sessão (apenas o que aparece no placar)
+fn final_results(scores: &serde_json::Value) -> Vec<serde_json::Value> {
+    scores.as_array()
+        .map(|scores| scores.iter().enumerate().map(|(index, score)| json!({
+            "rank": index + 1,
+            "player_name": score["player_name"],
+            "player_color": score["player_color"],
+            "total_score": score["total_score"],
+            "words_found": score["words_found"],
+            "team_id": score["team_id"],
+        })).collect())
+        .unwrap_or_default()
+}
+
+async fn save_results(pool: &sqlx::PgPool, room_id: i32, reason: &str) -> Result<(), sqlx::Error> {
+    let state = room_snapshot::load_snapshot(pool, room_id, Vec::new(), None, None).await?;
+    let results = final_results(&state["scores"]);
+    let total_score = results.iter()
+        .filter_map(|result| result["total_score"].as_i64())
+        .sum::<i64>();
+
+    sqlx::query(
+        "INSERT INTO room_results (room_id, game_id, room_name, results, total_score, completion_time, close_reason)
+         SELECT id, game_id, room_name, $2, $3, completion_time, $4 FROM game_rooms WHERE id = $1
+         ON CONFLICT (room_id) DO UPDATE SET
+             results = EXCLUDED.results, total_score = EXCLUDED.total_score,
+             completion_time = EXCLUDED.completion_time, close_reason = EXCLUDED.close_reason,
+             closed_at = NOW(), anonymous_purged_at = NULL"
+    )
+    .bind(room_id)
+    .bind(json!(results))
+    .bind(total_score as i32)
+    .bind(reason)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// Apaga sessões, progresso, dicas e pontuações individuais dos participantes anônimos.
+// Os resultados finais (room_results) continuam disponíveis.
+async fn purge_anonymous_data(pool: &sqlx::PgPool, retention_days: i32) -> Result<(), sqlx::Error> {
+    let rooms = sqlx::query_scalar::<_, i32>(
+        "SELECT room_id FROM room_results
+         WHERE anonymous_purged_at IS NULL AND closed_at < NOW() - $1 * INTERVAL '1 day'
+         LIMIT $2"
+    )
+    .bind(retention_days)
+    .bind(SWEEP_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for room_id in rooms {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM room_player_scores WHERE room_id = $1 AND session_id IN (
+                SELECT session_id FROM room_participants WHERE room_id = $1 AND user_id IS NULL
+             )"
+        )
+        .bind(room_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // As palavras continuam no tabuleiro, sem o vínculo com a sessão (tabuleiros
+        // individuais passam a ser identificados pela própria linha)
+        sqlx::query(
+            "UPDATE room_found_words SET found_by_session_id = 'anonymous',
+                board_key = CASE WHEN board_key = '' THEN '' ELSE 'anonymous_' || id END
+             WHERE room_id = $1 AND found_by_session_id IN (
+                SELECT session_id FROM room_participants WHERE room_id = $1 AND user_id IS NULL
+             )"
+        )
+        .bind(room_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM quiz_progress WHERE room_id = $1 AND user_identifier LIKE 'session_%'")
+            .bind(room_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // As penalidades já estão no placar; o registro das dicas guarda sessão e nome
+        sqlx::query(
+            "DELETE FROM room_hints WHERE room_id = $1 AND session_id IN (
+                SELECT session_id FROM room_participants WHERE room_id = $1 AND user_id IS NULL
+             )"
+        )
+        .bind(room_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE game_results SET device_session_id = NULL WHERE room_id = $1 AND user_id IS NULL")
+            .bind(room_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let participants = sqlx::query("DELETE FROM room_participants WHERE room_id = $1 AND user_id IS NULL")
+            .bind(room_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE room_results SET anonymous_purged_at = NOW() WHERE room_id = $1")
+            .bind(room_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        log::info!("🧹 Room {}: anonymous data purged ({} participants)", room_id, participants.rows_affected());
+    }
+
+    // Partidas individuais anônimas: o resultado fica, sem o id do aparelho
+    let solo = sqlx::query(
+        "UPDATE game_results SET device_session_id = NULL
+         WHERE room_id IS NULL AND user_id IS NULL AND device_session_id IS NOT NULL
+           AND created_at < NOW() - $1 * INTERVAL '1 day'"
+    )
+    .bind(retention_days)
+    .execute(pool)
+    .await?;
+    if solo.rows_affected() > 0 {
+        log::info!("🧹 Device id removed from {} anonymous solo results", solo.rows_affected());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,658 @@
+use actix::{Actor, ActorContext, ActorFutureExt, Addr, AsyncContext, Context, Handler, Message as ActixMessage, WrapFuture};
+use actix::dev::SendError;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::fanout::Fanout;
+use crate::quiz_clock::QuizClocks;
+use crate::room_clock::{self, RoomTimer};
+use crate::room_snapshot;
+use crate::room_status::{self, RoomStatus, StatusChange};
+use crate::websocket::{ConnectionInfo, Disconnect, GameWebSocket, PlayerInfo, SendMessage, SetTeam, WsMessage};
+
+// Tempo de espera antes de anunciar PlayerLeft (troca de rede em celulares)
+const LEAVE_GRACE_PERIOD: Duration = Duration::from_secs(10);
+// Quantidade de eventos mantidos por sala para retomada
+const HISTORY_SIZE: usize = 256;
+// Frequência com que o hub confere o cronômetro da sala
+const TIMER_TICK: Duration = Duration::from_secs(1);
+
+// Hub de uma sala: dono das conexões, da sequência e do histórico de eventos.
+// Cada sala processa suas mensagens no próprio actor, sem lock global durante o envio.
+pub struct RoomHub {
+    room_id: i32,
+    connections: Vec<ConnectionInfo>,
+    next_seq: u64,
+    history: VecDeque<(u64, String)>,
+    // session_id -> instante da desconexão (aguardando o período de graça)
+    pending_leave: HashMap<String, Instant>,
+    // Cronômetro da sala (None até a primeira leitura do banco)
+    timer: Option<RoomTimer>,
+    // Cada releitura recebe um número; só a mais recente é aplicada
+    timer_generation: u64,
+    // Transição automática (fim da contagem ou do tempo) em andamento
+    transitioning: bool,
+    last_sync: Instant,
+    registry: RoomRegistry,
+}
+
+impl RoomHub {
+    fn new(room_id: i32, registry: RoomRegistry) -> Self {
+        // A sequência parte do relógio para continuar crescendo se a sala for recriada
+        // (clientes antigos recebem ResumeFailed em vez de descartar eventos novos)
+        RoomHub {
+            room_id,
+            connections: Vec::new(),
+            next_seq: chrono::Utc::now().timestamp_millis() as u64,
+            history: VecDeque::new(),
+            pending_leave: HashMap::new(),
+            timer: None,
+            timer_generation: 0,
+            transitioning: false,
+            last_sync: Instant::now(),
+            registry,
+        }
+    }
+
+    // Numera a mensagem, guarda no histórico e retorna o texto a ser enviado
+    fn record(&mut self, message: &WsMessage) -> String {
+        self.next_seq += 1;
+        let seq = self.next_seq;
+
+        let mut value = serde_json::to_value(message).unwrap();
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("seq".to_string(), serde_json::json!(seq));
+        }
+        let text = value.to_string();
+
+        self.history.push_back((seq, text.clone()));
+        if self.history.len() > HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        text
+    }
+
+    // Eventos após last_seq, ou None se o histórico já não os contém.
+    // A sequência e o histórico são desta instância: a retomada só funciona reconectando
+    // na mesma instância (sticky session); em outra o cliente recebe ResumeFailed.
+    fn replay_since(&self, last_seq: u64) -> Option<Vec<String>> {
+        if last_seq == self.next_seq {
+            return Some(Vec::new());
+        }
+        // seq que este hub nunca emitiu (outra instância ou hub anterior)
+        if last_seq > self.next_seq {
+            return None;
+        }
+        match self.history.front() {
+            Some((first, _)) if *first <= last_seq + 1 => Some(
+                self.history.iter()
+                    .filter(|(seq, _)| *seq > last_seq)
+                    .map(|(_, text)| text.clone())
+                    .collect()
+            ),
+            _ => None,
+        }
+    }
+
+    // Serializa uma vez e entrega a todas as conexões locais. Conexões com a caixa de
+    // mensagens cheia (cliente lento) são desconectadas e podem retomar pelo seq.
+    fn deliver(&mut self, message: &WsMessage) {
+        let text = self.record(message);
+
+        let mut lagging = Vec::new();
+        for conn in &self.connections {
+            match conn.addr.try_send(SendMessage { text: text.clone() }) {
+                Ok(()) => {}
+                Err(SendError::Full(_)) => lagging.push(conn.addr.clone()),
+                // Conexão encerrando: o Leave dela já está a caminho
+                Err(SendError::Closed(_)) => {}
+            }
+        }
+
+        if !lagging.is_empty() {
+            log::warn!("🐢 Sala {}: {} conexão(ões) lentas desconectadas", self.room_id, lagging.len());
+            self.connections.retain(|conn| !lagging.contains(&conn.addr));
+            for addr in lagging {
+                addr.do_send(Disconnect { reason: "lagging".to_string(), reconnect: true });
+            }
+        }
+
+        log::debug!("Room {} broadcast to {} connections", self.room_id, self.connections.len());
+    }
+
+    // Entrega local e repasse para as demais instâncias
+    fn publish(&mut self, message: WsMessage) {
+        self.deliver(&message);
+        self.registry.fanout.publish(self.room_id, &message);
+    }
+
+    fn players(&self) -> Vec<PlayerInfo> {
+        self.connections.iter()
+            .filter(|c| !c.is_spectator())
+            .map(|c| PlayerInfo {
+                player_id: c.player_id,
+                username: c.username.clone(),
+                player_color: c.player_color.clone(),
+                team_id: c.team_id,
+            })
+            .collect()
+    }
+
+    fn send_players_list(&self, addr: &Addr<GameWebSocket>) {
+        let players = self.players();
+        if !players.is_empty() {
+            addr.do_send(SendMessage {
+                text: serde_json::to_string(&WsMessage::PlayersList { players }).unwrap(),
+            });
+        }
+    }
+
+    // Estado completo da sala para a conexão nova. O seq do snapshot é o último evento já
+    // numerado: eventos com seq maior chegam depois (ou antes, e o cliente fica com o maior).
+    fn send_snapshot(&self, conn: &ConnectionInfo, resume_timer: bool) {
+        let seq = self.next_seq;
+        let room_id = self.room_id;
+        let addr = conn.addr.clone();
+        let mut players = self.players();
+        if !conn.is_spectator() {
+            players.retain(|p| p.player_id != conn.player_id);
+            players.push(PlayerInfo {
+                player_id: conn.player_id,
+                username: conn.username.clone(),
+                player_color: conn.player_color.clone(),
+                team_id: conn.team_id,
+            });
+        }
+        // Identidade da própria conexão (o cliente não precisa calcular o player_id)
+        let you = serde_json::json!({
+            "player_id": conn.player_id,
+            "username": conn.username,
+            "player_color": conn.player_color,
+            "team_id": conn.team_id,
+            "role": conn.role.as_str(),
+        });
+        let clock = self.registry.quiz_clocks.lock().unwrap().get(&room_id).cloned();
+        // Espectadores não têm tabuleiro próprio
+        let viewer = (!conn.is_spectator()).then(|| conn.session_id.clone());
+        let pool = self.registry.pool.clone();
+        let registry = self.registry.clone();
+
+        actix::spawn(async move {
+            if resume_timer {
+                resume_room_timer(&pool, &registry, room_id).await;
+            }
+
+            match room_snapshot::load_snapshot(&pool, room_id, players, clock, viewer.as_deref()).await {
+                Ok(mut state) => {
+                    state["you"] = you;
+                    addr.do_send(SendMessage {
+                        text: serde_json::to_string(&WsMessage::GameStateSnapshot {
+                            version: room_snapshot::SNAPSHOT_VERSION,
+                            seq,
+                            state,
+                        }).unwrap(),
+                    });
+                }
+                Err(e) => log::error!("Failed to load snapshot for room {}: {}", room_id, e),
+            }
+        });
+    }
+
+    fn has_players(&self) -> bool {
+        self.connections.iter().any(|conn| !conn.is_spectator())
+    }
+
+    // Encerra o hub quando não sobra nenhuma conexão (nem espectadores) nem saída pendente
+    fn stop_if_idle(&mut self, ctx: &mut Context<Self>) {
+        if self.connections.is_empty() && self.pending_leave.is_empty()
+            && self.registry.remove_if_idle(self.room_id, &ctx.address())
+        {
+            ctx.stop();
+        }
+    }
+
+    fn finish_leave(&mut self, session_id: String, player_id: i32, username: String, ctx: &mut Context<Self>) {
+        // Reconectou (ou outra desconexão mais recente assumiu a espera)
+        match self.pending_leave.get(&session_id) {
+            Some(since) if since.elapsed() >= LEAVE_GRACE_PERIOD => {
+                self.pending_leave.remove(&session_id);
+            }
+            _ => return,
+        }
+
+        // Notificar todos que um jogador saiu
+        self.publish(WsMessage::PlayerLeft { player_id, username });
+
+        // Espectadores não mantêm o timer rodando
+        if self.has_players() || !self.pending_leave.is_empty() {
+            return;
+        }
+
+        // Relógios sem ticker (self_paced) são descartados aqui; os demais, pelo próprio ticker
+        {
+            let mut clocks = self.registry.quiz_clocks.lock().unwrap();
+            if clocks.get(&self.room_id).is_some_and(|clock| !clock.is_server_driven()) {
+                clocks.remove(&self.room_id);
+            }
+        }
+
+        // Se não há mais jogadores, pausar a partida em andamento
+        if self.timer.as_ref().is_some_and(|timer| timer.status == RoomStatus::Running) {
+            let pool = self.registry.pool.clone();
+            let registry = self.registry.clone();
+            let room_id = self.room_id;
+            actix::spawn(async move {
+                let pause = StatusChange::Pause { reason: room_status::REASON_EMPTY };
+                if room_status::change(&pool, &registry, room_id, pause, "server").await.is_ok() {
+                    log::info!("Room {} timer paused (all players left)", room_id);
+                }
+            });
+        }
+
+        self.stop_if_idle(ctx);
+    }
+
+    // Relê o cronômetro do banco (início, pausa, retomada, reset ou fim da sala)
+    fn reload_timer(&mut self, ctx: &mut Context<Self>) {
+        self.timer_generation += 1;
+        let generation = self.timer_generation;
+        let pool = self.registry.pool.clone();
+        let room_id = self.room_id;
+
+        ctx.spawn(
+            async move { room_clock::load_timer(&pool, room_id).await }
+                .into_actor(self)
+                .map(move |result, hub, _ctx| {
+                    if generation != hub.timer_generation {
+                        return;
+                    }
+                    match result {
+                        Ok(timer) => hub.set_timer(timer),
+                        Err(e) => log::error!("Failed to load timer for room {}: {}", room_id, e),
+                    }
+                }),
+        );
+    }
+
+    fn set_timer(&mut self, timer: RoomTimer) {
+        self.registry.set_accepts_plays(self.room_id, timer.status.accepts_plays());
+        self.transitioning = false;
+
+        // Sincronizar logo após cada mudança
+        if timer.is_ticking() && !self.connections.is_empty() {
+            self.deliver(&timer.timer_sync(chrono::Utc::now()));
+            self.last_sync = Instant::now();
+        }
+        self.timer = Some(timer);
+    }
+
+    fn tick_timer(&mut self, ctx: &mut Context<Self>) {
+        let now = chrono::Utc::now();
+        let (change, ticking) = match &self.timer {
+            Some(timer) if timer.countdown_finished(now) => (Some(StatusChange::CountdownFinished), true),
+            Some(timer) if timer.is_expired(now) => (Some(StatusChange::TimeUp), true),
+            Some(timer) => (None, timer.is_ticking()),
+            None => return,
+        };
+
+        // Fim da contagem ou do tempo limite. Todas as instâncias tentam; a transição
+        // no banco só é aplicada uma vez e o RoomStatusChanged chega a todas.
+        if let Some(change) = change {
+            if !self.transitioning {
+                self.transitioning = true;
+                let pool = self.registry.pool.clone();
+                let registry = self.registry.clone();
+                let room_id = self.room_id;
+
+                ctx.spawn(
+                    async move { room_status::change(&pool, &registry, room_id, change, "server").await }
+                        .into_actor(self)
+                        .map(|result, hub, ctx| {
+                            // Outra instância aplicou antes ou o estado mudou: reler o banco
+                            if result.is_err() {
+                                hub.reload_timer(ctx);
+                            }
+                        }),
+                );
+            }
+            return;
+        }
+
+        if ticking && !self.connections.is_empty()
+            && self.last_sync.elapsed() >= Duration::from_secs(room_clock::SYNC_INTERVAL_SECS)
+        {
+            if let Some(timer) = &self.timer {
+                let sync = timer.timer_sync(now);
+                self.deliver(&sync);
+            }
+            self.last_sync = Instant::now();
+        }
+    }
+}
+
+impl Actor for RoomHub {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.reload_timer(ctx);
+        ctx.run_interval(TIMER_TICK, |hub, ctx| hub.tick_timer(ctx));
+    }
+}
+
+// Nova conexão na sala
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct Join {
+    pub connection: ConnectionInfo,
+}
+
+impl Handler<Join> for RoomHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Join, _ctx: &mut Self::Context) {
+        let conn = msg.connection;
+
+        // Espectador: recebe os eventos, mas não aparece para os jogadores nem inicia o timer
+        if conn.is_spectator() {
+            self.send_players_list(&conn.addr);
+            self.send_snapshot(&conn, false);
+            self.connections.push(conn);
+            return;
+        }
+
+        let was_empty = !self.has_players();
+        // Reconexão dentro do período de graça: os outros nunca viram o PlayerLeft
+        let reconnected = self.pending_leave.remove(&conn.session_id).is_some();
+
+        // Remover conexões antigas do mesmo session_id (reconexão)
+        self.connections.retain(|c| c.session_id != conn.session_id);
+
+        // Enviar lista de jogadores já conectados APENAS para o novo jogador
+        self.send_players_list(&conn.addr);
+
+        let joined = WsMessage::PlayerJoined {
+            player_id: conn.player_id,
+            username: conn.username.clone(),
+            player_color: conn.player_color.clone(),
+            team_id: conn.team_id,
+        };
+        // Se é a primeira conexão, iniciar/retomar timer (antes de montar o snapshot)
+        self.send_snapshot(&conn, was_empty);
+        self.connections.push(conn);
+
+        // Notificar TODOS que um novo jogador entrou
+        if !reconnected {
+            self.publish(joined);
+        }
+    }
+}
+
+// Conexão encerrada
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct Leave {
+    pub addr: Addr<GameWebSocket>,
+    pub session_id: String,
+    pub player_id: i32,
+    pub username: String,
+    pub spectator: bool,
+}
+
+impl Handler<Leave> for RoomHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Leave, ctx: &mut Self::Context) {
+        self.registry.release(self.room_id, msg.spectator);
+        self.connections.retain(|conn| conn.addr != msg.addr);
+
+        if msg.spectator {
+            self.stop_if_idle(ctx);
+            return;
+        }
+
+        if self.connections.iter().any(|conn| conn.session_id == msg.session_id) {
+            return;
+        }
+
+        // Aguardar o período de graça antes de anunciar a saída e pausar o timer
+        self.pending_leave.insert(msg.session_id.clone(), Instant::now());
+        let Leave { session_id, player_id, username, .. } = msg;
+        ctx.run_later(LEAVE_GRACE_PERIOD, move |hub, ctx| {
+            hub.finish_leave(session_id, player_id, username, ctx);
+        });
+    }
+}
+
+// Evento para todas as conexões locais da sala
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct Broadcast {
+    pub message: WsMessage,
+}
+
+impl Handler<Broadcast> for RoomHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Broadcast, ctx: &mut Self::Context) {
+        self.deliver(&msg.message);
+
+        // Eventos que mudam o cronômetro (desta ou de outra instância)
+        if matches!(msg.message, WsMessage::RoomStatusChanged { .. } | WsMessage::RoomReset { .. }) {
+            self.reload_timer(ctx);
+        }
+
+        // Pergunta avançada (por esta ou outra instância): o relógio local acompanha
+        let advanced_to = match &msg.message {
+            WsMessage::QuizAdvance { question_index } => Some(*question_index),
+            WsMessage::QuizFinished { player_id: 0 } => Some(i32::MAX),
+            _ => None,
+        };
+        if let Some(question_index) = advanced_to {
+            if let Some(clock) = self.registry.quiz_clocks.lock().unwrap().get_mut(&self.room_id) {
+                let question_index = question_index.min(clock.question_count());
+                if clock.is_server_driven() && question_index > clock.question_index {
+                    clock.advance_to(question_index, chrono::Utc::now());
+                }
+            }
+        }
+
+        // Nova equipe/cor dos jogadores conectados nesta instância
+        if let WsMessage::TeamsUpdated { assignments, .. } = &msg.message {
+            for assignment in assignments {
+                for conn in self.connections.iter_mut().filter(|conn| conn.player_id == assignment.player_id) {
+                    conn.team_id = assignment.team_id;
+                    conn.player_color = assignment.player_color.clone();
+                    conn.addr.do_send(SetTeam {
+                        team_id: assignment.team_id,
+                        player_color: assignment.player_color.clone(),
+                    });
+                }
+            }
+        }
+
+        // Sala fechada: desconecta todos depois do aviso
+        if let WsMessage::RoomStatusChanged { status: RoomStatus::Archived, reason, .. } = &msg.message {
+            let reason = match reason.as_deref() {
+                Some(room_status::REASON_EXPIRED) => "room_expired",
+                _ => "room_closed",
+            };
+            for conn in self.connections.drain(..) {
+                conn.addr.do_send(Disconnect { reason: reason.to_string(), reconnect: false });
+            }
+        }
+    }
+}
+
+// Cliente reconectado pedindo os eventos com seq > last_seq
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct Replay {
+    pub last_seq: u64,
+    pub addr: Addr<GameWebSocket>,
+}
+
+impl Handler<Replay> for RoomHub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Replay, _ctx: &mut Self::Context) {
+        let Replay { last_seq, addr } = msg;
+        match self.replay_since(last_seq) {
+            Some(messages) => {
+                log::info!("🔁 Resume in room {} from seq {}: {} messages", self.room_id, last_seq, messages.len());
+                let replayed = messages.len();
+                for text in messages {
+                    addr.do_send(SendMessage { text });
+                }
+                addr.do_send(SendMessage {
+                    text: serde_json::to_string(&WsMessage::Resumed { last_seq, replayed }).unwrap(),
+                });
+            }
+            None => {
+                addr.do_send(SendMessage {
+                    text: serde_json::to_string(&WsMessage::ResumeFailed { last_seq, current_seq: self.next_seq }).unwrap(),
+                });
+            }
+        }
+    }
+}
+
+// Primeira conexão de jogador: retoma a partida pausada porque todos saíram.
+// O início da partida fica com o host (RoomStart).
+async fn resume_room_timer(pool: &sqlx::PgPool, registry: &RoomRegistry, room_id: i32) {
+    let resume = StatusChange::Resume { only_reason: Some(room_status::REASON_EMPTY) };
+    if room_status::change(pool, registry, room_id, resume, "server").await.is_ok() {
+        log::info!("Room {} resumed via WebSocket", room_id);
+    }
+}
+
+struct RoomHandle {
+    addr: Addr<RoomHub>,
+    // Conexões registradas e ainda não encerradas (lido sem passar pelo actor)
+    connections: Arc<AtomicUsize>,
+    // Dessas, as que são jogadores (sem espectadores)
+    players: Arc<AtomicUsize>,
+    // Partida em andamento: jogadas fora dela são recusadas sem consultar o hub
+    accepts_plays: Arc<AtomicBool>,
+}
+
+// Registro das salas ativas nesta instância (room_id -> hub).
+// O lock só cobre a busca do endereço; serialização e envio ficam no hub de cada sala.
+#[derive(Clone)]
+pub struct RoomRegistry {
+    rooms: Arc<Mutex<HashMap<i32, RoomHandle>>>,
+    fanout: Fanout,
+    quiz_clocks: QuizClocks,
+    pool: sqlx::PgPool,
+}
+
+impl RoomRegistry {
+    pub fn new(pool: sqlx::PgPool, fanout: Fanout, quiz_clocks: QuizClocks) -> Self {
+        RoomRegistry {
+            rooms: Arc::new(Mutex::new(HashMap::new())),
+            fanout,
+            quiz_clocks,
+            pool,
+        }
+    }
+
+    fn hub(&self, room_id: i32) -> Option<Addr<RoomHub>> {
+        self.rooms.lock().unwrap().get(&room_id).map(|room| room.addr.clone())
+    }
+
+    // Registra a conexão, criando o hub da sala se necessário
+    pub fn join(&self, room_id: i32, connection: ConnectionInfo) {
+        let addr = {
+            let mut rooms = self.rooms.lock().unwrap();
+            let room = rooms.entry(room_id).or_insert_with(|| RoomHandle {
+                addr: RoomHub::new(room_id, self.clone()).start(),
+                connections: Arc::new(AtomicUsize::new(0)),
+                players: Arc::new(AtomicUsize::new(0)),
+                accepts_plays: Arc::new(AtomicBool::new(false)),
+            });
+            room.connections.fetch_add(1, Ordering::SeqCst);
+            if !connection.is_spectator() {
+                room.players.fetch_add(1, Ordering::SeqCst);
+            }
+            room.addr.clone()
+        };
+        addr.do_send(Join { connection });
+    }
+
+    pub fn leave(&self, room_id: i32, leave: Leave) {
+        if let Some(addr) = self.hub(room_id) {
+            addr.do_send(leave);
+        }
+    }
+
+    // Envia para os sockets desta instância e repassa às demais pelo backend de fan-out
+    pub fn publish(&self, room_id: i32, message: &WsMessage) {
+        self.broadcast_local(room_id, message);
+        self.fanout.publish(room_id, message);
+    }
+
+    // Envia para as conexões desta instância (com número de sequência por instância)
+    pub fn broadcast_local(&self, room_id: i32, message: &WsMessage) {
+        if let Some(addr) = self.hub(room_id) {
+            addr.do_send(Broadcast { message: message.clone() });
+        }
+    }
+
+    pub fn replay(&self, room_id: i32, last_seq: u64, addr: Addr<GameWebSocket>) {
+        match self.hub(room_id) {
+            Some(hub) => hub.do_send(Replay { last_seq, addr }),
+            None => addr.do_send(SendMessage {
+                text: serde_json::to_string(&WsMessage::ResumeFailed { last_seq, current_seq: 0 }).unwrap(),
+            }),
+        }
+    }
+
+    pub fn connection_count(&self, room_id: i32) -> usize {
+        self.rooms.lock().unwrap()
+            .get(&room_id)
+            .map(|room| room.connections.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    // Jogadores conectados (espectadores não contam para max_players)
+    pub fn player_count(&self, room_id: i32) -> usize {
+        self.rooms.lock().unwrap()
+            .get(&room_id)
+            .map(|room| room.players.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    pub fn accepts_plays(&self, room_id: i32) -> bool {
+        self.rooms.lock().unwrap()
+            .get(&room_id)
+            .is_some_and(|room| room.accepts_plays.load(Ordering::SeqCst))
+    }
+
+    fn set_accepts_plays(&self, room_id: i32, accepts_plays: bool) {
+        if let Some(room) = self.rooms.lock().unwrap().get(&room_id) {
+            room.accepts_plays.store(accepts_plays, Ordering::SeqCst);
+        }
+    }
+
+    fn release(&self, room_id: i32, spectator: bool) {
+        let decrement = |n: usize| Some(n.saturating_sub(1));
+        if let Some(room) = self.rooms.lock().unwrap().get(&room_id) {
+            let _ = room.connections.fetch_update(Ordering::SeqCst, Ordering::SeqCst, decrement);
+            if !spectator {
+                let _ = room.players.fetch_update(Ordering::SeqCst, Ordering::SeqCst, decrement);
+            }
+        }
+    }
+
+    // Remove o hub se nenhuma conexão foi registrada desde que ele ficou vazio
+    fn remove_if_idle(&self, room_id: i32, addr: &Addr<RoomHub>) -> bool {
+        let mut rooms = self.rooms.lock().unwrap();
+        let idle = rooms.get(&room_id)
+            .is_some_and(|room| room.addr == *addr && room.connections.load(Ordering::SeqCst) == 0);
+        if idle {
+            rooms.remove(&room_id);
+        }
+        idle
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::handlers::rooms::quiz_scores;
+use crate::models::GameRoom;
+use crate::quiz_clock::QuizClock;
+use crate::room_clock::RoomTimer;
+use crate::room_teams;
+use crate::websocket::PlayerInfo;
+use crate::word_search_clues;
+use crate::word_search_mode::{self, WordSearchMode};
+
+// Versão do formato do snapshot (incrementar ao mudar a estrutura de `state`)
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+// Votos de cada pergunta no formato do QuizVoteState:
+// { "0": { "A": [{"player_name": "João", "user_identifier": "session_..."}] } }
+fn quiz_votes(progress: &[(String, serde_json::Value)]) -> serde_json::Value {
+    let mut votes = serde_json::Map::new();
+
+    for (user_identifier, progress_data) in progress {
+        let answers = match progress_data.get("answers").and_then(|a| a.as_object()) {
+            Some(answers) => answers,
+            None => continue,
+        };
+        let player_name = progress_data.get("player_name").cloned().unwrap_or(serde_json::Value::Null);
+
+        for (question_index, answer) in answers {
+            let answer = match answer.as_str() {
+                Some(a) => a,
+                None => continue,
+            };
+            let question = votes.entry(question_index.clone())
+                .or_insert_with(|| json!({}));
+            if let Some(question) = question.as_object_mut() {
+                let voters = question.entry(answer.to_string()).or_insert_with(|| json!([]));
+                if let Some(voters) = voters.as_array_mut() {
+                    voters.push(json!({
+                        "player_name": player_name,
+                        "user_identifier": user_identifier,
+                    }));
+                }
+            }
+        }
+    }
+
+    serde_json::Value::Object(votes)
+}
+
+// Estado completo da sala enviado a cada conexão nova.
+// viewer: chave do jogador (modos competitivos mostram só o tabuleiro dele).
+pub async fn load_snapshot(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    players: Vec<PlayerInfo>,
+    clock: Option<QuizClock>,
+    viewer: Option<&str>,
+) -> Result<serde_json::Value, sqlx::Error> {
+    let now = Utc::now();
+
+    let room = sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_one(pool)
+        .await?;
+
+    let game_type = sqlx::query_scalar::<_, String>("SELECT game_type FROM games WHERE id = $1")
+        .bind(room.game_id)
+        .fetch_one(pool)
+        .await?;
+
+    let room_timer = RoomTimer::from_room(&room);
+    let timer = json!({
+        "started_at": room.started_at,
+        "paused_at": room.paused_at,
+        "pause_reason": room.pause_reason,
+        "total_pause_duration": room.total_pause_duration.unwrap_or(0),
+        "completed_at": room.completed_at,
+        "completion_time": room.completion_time,
+        "time_limit": room.time_limit,
+        "countdown_ends_at": room.countdown_ends_at,
+        "elapsed_seconds": room_timer.elapsed(now),
+        "remaining_seconds": room_timer.remaining(now),
+        "server_time": now,
+    });
+
+    let mut state = json!({
+        "room_id": room.id,
+        "game_id": room.game_id,
+        "game_type": game_type,
+        "is_active": room.is_active,
+        "status": room.status,
+        "timer": timer,
+        "players": players,
+    });
+
+    if game_type == "quiz" {
+        let questions = sqlx::query_as::<_, (i32, String, i32)>(
+            "SELECT qq.id, qq.correct_option, qq.points
+             FROM quiz_questions qq
+             JOIN quiz_configs qc ON qc.id = qq.quiz_config_id
+             WHERE qc.game_id = $1
+             ORDER BY qq.id"
+        )
+        .bind(room.game_id)
+        .fetch_all(pool)
+        .await?;
+
+        let progress = sqlx::query_as::<_, (String, serde_json::Value)>(
+            "SELECT user_identifier, progress_data FROM quiz_progress WHERE room_id = $1"
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await?;
+
+        // Pergunta atual: relógio do servidor quando existe, senão o valor salvo na sala
+        let current_question = match &clock {
+            Some(clock) => json!({
+                "question_index": clock.question_index,
+                "question_started_at": clock.question_started_at,
+                "pacing_mode": clock.pacing_mode.as_str(),
+                "time_limit": clock.current_time_limit(),
+                "remaining_time": clock.remaining(now),
+                "finished": clock.finished,
+            }),
+            None => {
+                let (question_index, started_at) = sqlx::query_as::<_, (i32, Option<DateTime<Utc>>)>(
+                    "SELECT quiz_question_index, quiz_question_started_at FROM game_rooms WHERE id = $1"
+                )
+                .bind(room_id)
+                .fetch_one(pool)
+                .await?;
+                json!({
+                    "question_index": question_index,
+                    "question_started_at": started_at,
+                })
+            }
+        };
+
+        let teams = room_teams::quiz_teams(pool, room_id).await?;
+        state["scores"] = json!(quiz_scores(&questions, &progress, &teams));
+        state["quiz"] = json!({
+            "question_count": questions.len(),
+            "current_question": current_question,
+            "votes": quiz_votes(&progress),
+        });
+    } else {
+        let mode = WordSearchMode::parse(&room.word_search_mode).unwrap_or_default();
+        let found_words = word_search_mode::load_found_words(pool, room_id, mode, viewer).await?;
+        let scores = word_search_mode::load_scores(pool, room_id, mode).await?;
+
+        // Modo pistas: conceitos das palavras já encontradas
+        let concepts = word_search_clues::load_concepts(pool, room.game_id).await?;
+        let found = found_words.iter().map(|found| found.word.clone()).collect::<Vec<_>>();
+
+        state["word_search_mode"] = json!(mode.as_str());
+        state["revealed_concepts"] = json!(word_search_clues::revealed_concepts(concepts.as_ref(), &found));
+        state["found_words"] = json!(found_words);
+        state["scores"] = json!(scores);
+    }
+
+    // Modo equipes: equipes e placar das equipes
+    let scores = state["scores"].as_array().cloned().unwrap_or_default();
+    if let Some((teams, team_scores)) = room_teams::room_team_state(pool, room_id, &scores).await? {
+        state["teams"] = json!(teams);
+        state["team_scores"] = json!(team_scores);
+    }
+
+    Ok(state)
+}
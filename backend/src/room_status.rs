@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::room_hub::RoomRegistry;
+use crate::websocket::WsMessage;
+
+// Contagem regressiva máxima antes de iniciar a partida
+pub const MAX_COUNTDOWN_SECS: i32 = 60;
+
+// Motivos registrados nas transições (pause_reason e RoomStatusChanged.reason)
+pub const REASON_HOST: &str = "host";  // Ação do host (pausa só é desfeita pelo host)
+pub const REASON_EMPTY: &str = "empty";  // Todos os jogadores saíram
+pub const REASON_TIME_UP: &str = "time_up";  // Tempo limite atingido
+pub const REASON_COUNTDOWN: &str = "countdown";  // Fim da contagem regressiva
+pub const REASON_EXPIRED: &str = "expired";  // Prazo da sala (expires_at) encerrado
+
+// Estado da sala salvo em game_rooms.status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomStatus {
+    // Aguardando o host iniciar (jogadores podem entrar)
+    #[default]
+    Lobby,
+    // Contagem regressiva para o início
+    Countdown,
+    Running,
+    Paused,
+    // Partida encerrada (pelo host ou por tempo esgotado); pontuação congelada
+    Finished,
+    // Sala fechada (is_active = false)
+    Archived,
+}
+
+impl RoomStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "lobby" => Some(RoomStatus::Lobby),
+            "countdown" => Some(RoomStatus::Countdown),
+            "running" => Some(RoomStatus::Running),
+            "paused" => Some(RoomStatus::Paused),
+            "finished" => Some(RoomStatus::Finished),
+            "archived" => Some(RoomStatus::Archived),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoomStatus::Lobby => "lobby",
+            RoomStatus::Countdown => "countdown",
+            RoomStatus::Running => "running",
+            RoomStatus::Paused => "paused",
+            RoomStatus::Finished => "finished",
+            RoomStatus::Archived => "archived",
+        }
+    }
+
+    // Estados a partir dos quais se chega a este
+    fn allowed_from(&self) -> &'static [RoomStatus] {
+        use RoomStatus::*;
+        match self {
+            // Cancelar contagem, reset da sala ou reativação
+            Lobby => &[Countdown, Running, Paused, Finished, Archived],
+            Countdown => &[Lobby],
+            Running => &[Lobby, Countdown, Paused],
+            Paused => &[Running],
+            Finished => &[Running, Paused],
+            Archived => &[Lobby, Countdown, Running, Paused, Finished],
+        }
+    }
+
+    // Jogadas (palavras, respostas) só contam com a partida em andamento
+    pub fn accepts_plays(&self) -> bool {
+        *self == RoomStatus::Running
+    }
+}
+
+// Início da partida: o cronômetro recomeça do zero
+const START_COLUMNS: &str = "started_at = NOW(), paused_at = NULL, pause_reason = NULL, total_pause_duration = 0,
+     completed_at = NULL, completion_time = NULL, countdown_ends_at = NULL";
+
+// Ações que mudam o estado da sala
+#[derive(Debug, Clone, Copy)]
+pub enum StatusChange {
+    // Host inicia com contagem regressiva
+    Countdown { seconds: i32 },
+    // Host inicia imediatamente (também pula uma contagem em andamento)
+    Start,
+    // Servidor inicia quando a contagem termina
+    CountdownFinished,
+    Pause { reason: &'static str },
+    // only_reason: retoma apenas pausas com esse motivo (retomada automática)
+    Resume { only_reason: Option<&'static str> },
+    // Host (ou o encerramento da sala) finaliza a partida
+    End { reason: &'static str },
+    // Servidor encerra quando o tempo limite é atingido
+    TimeUp,
+    Archive { reason: &'static str },
+}
+
+impl StatusChange {
+    pub fn target(&self) -> RoomStatus {
+        match self {
+            StatusChange::Countdown { .. } => RoomStatus::Countdown,
+            StatusChange::Start
+            | StatusChange::CountdownFinished
+            | StatusChange::Resume { .. } => RoomStatus::Running,
+            StatusChange::Pause { .. } => RoomStatus::Paused,
+            StatusChange::End { .. } | StatusChange::TimeUp => RoomStatus::Finished,
+            StatusChange::Archive { .. } => RoomStatus::Archived,
+        }
+    }
+
+    fn reason(&self) -> Option<&'static str> {
+        match self {
+            StatusChange::Pause { reason }
+            | StatusChange::End { reason }
+            | StatusChange::Archive { reason } => Some(reason),
+            StatusChange::Resume { only_reason } => *only_reason,
+            StatusChange::CountdownFinished => Some(REASON_COUNTDOWN),
+            StatusChange::TimeUp => Some(REASON_TIME_UP),
+            StatusChange::Countdown { .. } | StatusChange::Start => Some(REASON_HOST),
+        }
+    }
+
+    // Estados de origem aceitos (mais restritos que o grafo em alguns casos)
+    fn source_states(&self) -> Vec<RoomStatus> {
+        match self {
+            StatusChange::Start => vec![RoomStatus::Lobby, RoomStatus::Countdown],
+            StatusChange::CountdownFinished => vec![RoomStatus::Countdown],
+            StatusChange::Resume { .. } => vec![RoomStatus::Paused],
+            StatusChange::TimeUp => vec![RoomStatus::Running],
+            _ => self.target().allowed_from().to_vec(),
+        }
+    }
+
+    // Colunas alteradas e condição extra de cada ação ($3 = parâmetro da ação)
+    fn sql(&self) -> (&'static str, &'static str) {
+        match self {
+            StatusChange::Countdown { .. } => (
+                "countdown_ends_at = NOW() + $3 * INTERVAL '1 second'",
+                "",
+            ),
+            StatusChange::Start => (START_COLUMNS, ""),
+            StatusChange::CountdownFinished => (START_COLUMNS, "AND gr.countdown_ends_at <= NOW()"),
+            StatusChange::Pause { .. } => (
+                "paused_at = NOW(), pause_reason = $3",
+                "",
+            ),
+            StatusChange::Resume { .. } => (
+                "total_pause_duration = COALESCE(gr.total_pause_duration, 0)
+                     + GREATEST(EXTRACT(EPOCH FROM (NOW() - COALESCE(gr.paused_at, NOW()))), 0)::INTEGER,
+                 paused_at = NULL, pause_reason = NULL",
+                "AND ($3::VARCHAR IS NULL OR gr.pause_reason = $3)",
+            ),
+            StatusChange::End { .. } => (
+                "completed_at = NOW(), paused_at = NULL, pause_reason = NULL,
+                 completion_time = LEAST(
+                     GREATEST(EXTRACT(EPOCH FROM (COALESCE(gr.paused_at, NOW()) - gr.started_at))::INTEGER
+                         - COALESCE(gr.total_pause_duration, 0), 0),
+                     COALESCE(gr.time_limit, 2147483647))",
+                "",
+            ),
+            // O prazo é conferido no banco (o estado em memória do hub pode estar atrasado)
+            StatusChange::TimeUp => (
+                "completed_at = NOW(), completion_time = gr.time_limit",
+                "AND gr.time_limit IS NOT NULL
+                 AND EXTRACT(EPOCH FROM (NOW() - gr.started_at)) - COALESCE(gr.total_pause_duration, 0) >= gr.time_limit",
+            ),
+            StatusChange::Archive { .. } => (
+                "is_active = false, countdown_ends_at = NULL",
+                "",
+            ),
+        }
+    }
+}
+
+// Resultado de uma transição recusada
+#[derive(Debug)]
+pub enum StatusError {
+    NotFound,
+    InvalidTransition { from: RoomStatus, to: RoomStatus },
+    Database(sqlx::Error),
+}
+
+impl StatusError {
+    pub fn message(&self) -> String {
+        match self {
+            StatusError::NotFound => "Room not found".to_string(),
+            StatusError::InvalidTransition { from, to } => {
+                format!("Invalid room transition: {} -> {}", from.as_str(), to.as_str())
+            }
+            StatusError::Database(e) => format!("Database error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for StatusError {
+    fn from(e: sqlx::Error) -> Self {
+        StatusError::Database(e)
+    }
+}
+
+pub async fn load_status(pool: &sqlx::PgPool, room_id: i32) -> Result<Option<RoomStatus>, sqlx::Error> {
+    let status = sqlx::query_scalar::<_, String>("SELECT status FROM game_rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(status.map(|s| RoomStatus::parse(&s).unwrap_or_default()))
+}
+
+// Aplica a transição de forma atômica (só vence quem encontrar a sala no estado de origem)
+// e avisa todos os participantes. Retorna o estado anterior.
+pub async fn change(
+    pool: &sqlx::PgPool,
+    registry: &RoomRegistry,
+    room_id: i32,
+    change: StatusChange,
+    changed_by: &str,
+) -> Result<RoomStatus, StatusError> {
+    let to = change.target();
+    let (set, condition) = change.sql();
+    let from_states = change.source_states().iter().map(|s| s.as_str().to_string()).collect::<Vec<_>>();
+
+    let query = format!(
+        "UPDATE game_rooms gr
+         SET status = '{}', {}
+         FROM (SELECT id, status AS previous_status FROM game_rooms WHERE id = $1) prev
+         WHERE gr.id = prev.id AND gr.status = ANY($2) {}
+         RETURNING prev.previous_status, gr.countdown_ends_at",
+        to.as_str(), set, condition
+    );
+
+    let mut update = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(&query)
+        .bind(room_id)
+        .bind(&from_states);
+    update = match change {
+        StatusChange::Countdown { seconds } => update.bind(seconds),
+        StatusChange::Pause { reason } => update.bind(reason),
+        StatusChange::Resume { only_reason } => update.bind(only_reason),
+        _ => update,
+    };
+
+    let (previous, countdown_ends_at) = match update.fetch_optional(pool).await? {
+        Some(row) => row,
+        None => {
+            return Err(match load_status(pool, room_id).await? {
+                Some(from) => StatusError::InvalidTransition { from, to },
+                None => StatusError::NotFound,
+            });
+        }
+    };
+    let previous = RoomStatus::parse(&previous).unwrap_or_default();
+
+    log::info!("🚦 Room {}: {} -> {} ({})", room_id, previous.as_str(), to.as_str(), changed_by);
+
+    registry.publish(room_id, &WsMessage::RoomStatusChanged {
+        status: to,
+        previous_status: previous,
+        changed_by: changed_by.to_string(),
+        reason: change.reason().map(|r| r.to_string()),
+        countdown_ends_at,
+    });
+
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use RoomStatus::*;
+
+    const ALL: [RoomStatus; 6] = [Lobby, Countdown, Running, Paused, Finished, Archived];
+
+    #[test]
+    fn status_round_trips() {
+        for status in ALL {
+            assert_eq!(RoomStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(RoomStatus::parse("ended"), None);
+    }
+
+    #[test]
+    fn transitions_follow_the_graph() {
+        // lobby → countdown → running ⇄ paused → finished → archived
+        let expected: [(StatusChange, &[RoomStatus]); 8] = [
+            (StatusChange::Countdown { seconds: 5 }, &[Lobby]),
+            (StatusChange::Start, &[Lobby, Countdown]),
+            (StatusChange::CountdownFinished, &[Countdown]),
+            (StatusChange::Pause { reason: REASON_HOST }, &[Running]),
+            (StatusChange::Resume { only_reason: None }, &[Paused]),
+            (StatusChange::End { reason: REASON_HOST }, &[Running, Paused]),
+            (StatusChange::TimeUp, &[Running]),
+            (StatusChange::Archive { reason: REASON_HOST }, &[Lobby, Countdown, Running, Paused, Finished]),
+        ];
+
+        for (change, sources) in expected {
+            let accepted = change.source_states();
+            for status in ALL {
+                assert_eq!(
+                    accepted.contains(&status),
+                    sources.contains(&status),
+                    "{:?} a partir de {:?}", change, status
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::handlers::rooms::{assign_player_color, assign_player_color_anon};
+use crate::models::{RoomTeam, TeamRequest};
+use crate::room_status::RoomStatus;
+
+// Equipe de um participante (player_id = room_participants.id). A cor do jogador passa a
+// ser a da equipe; sem equipe, volta à cor individual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamAssignment {
+    pub player_id: i32,
+    pub team_id: Option<i32>,
+    pub player_color: String,
+}
+
+#[derive(Debug)]
+pub enum TeamError {
+    // Equipe não pertence à sala
+    InvalidTeam,
+    // Troca de equipe só é permitida antes do início da partida
+    Locked,
+    Database(sqlx::Error),
+}
+
+impl TeamError {
+    pub fn message(&self) -> String {
+        match self {
+            TeamError::InvalidTeam => "Equipe inválida para esta sala".to_string(),
+            TeamError::Locked => "A troca de equipe só é permitida antes do início da partida".to_string(),
+            TeamError::Database(e) => format!("Database error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for TeamError {
+    fn from(e: sqlx::Error) -> Self {
+        TeamError::Database(e)
+    }
+}
+
+// Chave do participante em room_player_scores (mesma usada pelo WebSocket)
+const SCORE_KEY: &str = "COALESCE(rp.session_id, 'user_' || rp.user_id)";
+
+// Nomes únicos e cores no formato #RRGGBB (os tamanhos já passaram pelo validator)
+pub fn check_teams(teams: &[TeamRequest]) -> Result<(), String> {
+    let mut names = Vec::new();
+    for team in teams {
+        let name = team.name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err("Nome de equipe vazio".to_string());
+        }
+        if names.contains(&name) {
+            return Err(format!("Equipe repetida: {}", team.name.trim()));
+        }
+        names.push(name);
+
+        let hex = team.color.strip_prefix('#').unwrap_or("");
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Cor inválida para a equipe {}: {}", team.name.trim(), team.color));
+        }
+    }
+    Ok(())
+}
+
+pub async fn load_teams(pool: &sqlx::PgPool, room_id: i32) -> Result<Vec<RoomTeam>, sqlx::Error> {
+    sqlx::query_as::<_, RoomTeam>(
+        "SELECT id, room_id, name, color, position FROM room_teams WHERE room_id = $1 ORDER BY position, id"
+    )
+    .bind(room_id)
+    .fetch_all(pool)
+    .await
+}
+
+// Cor individual do participante (modo sem equipes)
+fn individual_color(user_id: Option<i32>, session_id: Option<&str>) -> String {
+    match (user_id, session_id) {
+        (Some(user_id), _) => assign_player_color(user_id),
+        (None, Some(session_id)) => assign_player_color_anon(session_id),
+        (None, None) => assign_player_color(0),
+    }
+}
+
+// Aplica a equipe ao participante e à sua linha de pontuação
+async fn apply_assignment(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    participant_id: i32,
+    team: Option<&RoomTeam>,
+) -> Result<TeamAssignment, sqlx::Error> {
+    let (user_id, session_id) = sqlx::query_as::<_, (Option<i32>, Option<String>)>(
+        "SELECT user_id, session_id FROM room_participants WHERE id = $1"
+    )
+    .bind(participant_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let player_color = match team {
+        Some(team) => team.color.clone(),
+        None => individual_color(user_id, session_id.as_deref()),
+    };
+    let team_id = team.map(|team| team.id);
+
+    sqlx::query("UPDATE room_participants SET team_id = $2, player_color = $3 WHERE id = $1")
+        .bind(participant_id)
+        .bind(team_id)
+        .bind(&player_color)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(&format!(
+        "UPDATE room_player_scores rps SET team_id = $2, player_color = $3
+         FROM room_participants rp
+         WHERE rp.id = $1 AND rps.room_id = rp.room_id AND rps.session_id = {}",
+        SCORE_KEY
+    ))
+    .bind(participant_id)
+    .bind(team_id)
+    .bind(&player_color)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(TeamAssignment { player_id: participant_id, team_id, player_color })
+}
+
+// Substitui as equipes da sala e redistribui os jogadores (hosts ficam de fora).
+// Lista vazia desativa o modo equipes. Só antes do início da partida.
+pub async fn replace_teams(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    teams: &[TeamRequest],
+) -> Result<(Vec<RoomTeam>, Vec<TeamAssignment>), TeamError> {
+    let mut tx = pool.begin().await?;
+    // Serializa as atribuições de equipe da sala
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(room_id as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    // FOR SHARE: a partida não começa enquanto as equipes são trocadas
+    let status = sqlx::query_scalar::<_, String>("SELECT status FROM game_rooms WHERE id = $1 FOR SHARE")
+        .bind(room_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if RoomStatus::parse(&status) != Some(RoomStatus::Lobby) {
+        return Err(TeamError::Locked);
+    }
+
+    sqlx::query("DELETE FROM room_teams WHERE room_id = $1")
+        .bind(room_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut created = Vec::with_capacity(teams.len());
+    for (position, team) in teams.iter().enumerate() {
+        let team = sqlx::query_as::<_, RoomTeam>(
+            "INSERT INTO room_teams (room_id, name, color, position)
+             VALUES ($1, $2, $3, $4)
+             RETURNING id, room_id, name, color, position"
+        )
+        .bind(room_id)
+        .bind(team.name.trim())
+        .bind(team.color.to_uppercase())
+        .bind(position as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+        created.push(team);
+    }
+
+    let participants = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM room_participants WHERE room_id = $1 AND is_host = false ORDER BY joined_at, id"
+    )
+    .bind(room_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut assignments = Vec::with_capacity(participants.len());
+    for (index, participant_id) in participants.into_iter().enumerate() {
+        let team = (!created.is_empty()).then(|| &created[index % created.len()]);
+        assignments.push(apply_assignment(&mut tx, participant_id, team).await?);
+    }
+
+    tx.commit().await?;
+    Ok((created, assignments))
+}
+
+// Coloca o participante em uma equipe: a escolhida ou a com menos jogadores.
+// Retorna None se a sala não usa equipes; o bool indica se a equipe mudou.
+pub async fn join_team(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    participant_id: i32,
+    requested: Option<i32>,
+) -> Result<Option<(TeamAssignment, bool)>, TeamError> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(room_id as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    let teams = sqlx::query_as::<_, RoomTeam>(
+        "SELECT id, room_id, name, color, position FROM room_teams WHERE room_id = $1 ORDER BY position, id"
+    )
+    .bind(room_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if teams.is_empty() {
+        return Ok(None);
+    }
+
+    let current = sqlx::query_scalar::<_, Option<i32>>(
+        "SELECT team_id FROM room_participants WHERE id = $1 AND room_id = $2"
+    )
+    .bind(participant_id)
+    .bind(room_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(requested) = requested {
+        if !teams.iter().any(|team| team.id == requested) {
+            return Err(TeamError::InvalidTeam);
+        }
+    }
+
+    // Já está em uma equipe e não pediu outra (reaplica a cor, que o join pode ter trocado)
+    if let Some(current) = current.filter(|current| requested.is_none_or(|requested| requested == *current)) {
+        let team = teams.iter().find(|team| team.id == current);
+        let assignment = apply_assignment(&mut tx, participant_id, team).await?;
+        tx.commit().await?;
+        return Ok(Some((assignment, false)));
+    }
+
+    // Trocar de equipe com a partida em andamento mudaria o placar das equipes
+    if current.is_some() {
+        let status = sqlx::query_scalar::<_, String>("SELECT status FROM game_rooms WHERE id = $1")
+            .bind(room_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if RoomStatus::parse(&status) != Some(RoomStatus::Lobby) {
+            return Err(TeamError::Locked);
+        }
+    }
+
+    let team_id = match requested {
+        Some(requested) => requested,
+        None => sqlx::query_scalar::<_, i32>(
+            "SELECT t.id FROM room_teams t
+             LEFT JOIN room_participants rp ON rp.team_id = t.id AND rp.id <> $2
+             WHERE t.room_id = $1
+             GROUP BY t.id, t.position
+             ORDER BY COUNT(rp.id), t.position, t.id
+             LIMIT 1"
+        )
+        .bind(room_id)
+        .bind(participant_id)
+        .fetch_one(&mut *tx)
+        .await?,
+    };
+    let team = teams.iter().find(|team| team.id == team_id);
+
+    let assignment = apply_assignment(&mut tx, participant_id, team).await?;
+    tx.commit().await?;
+    Ok(Some((assignment, true)))
+}
+
+// Equipe de cada jogador do quiz, pelo user_identifier de quiz_progress
+pub async fn quiz_teams(pool: &sqlx::PgPool, room_id: i32) -> Result<HashMap<String, i32>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, i32)>(
+        "SELECT CASE WHEN user_id IS NOT NULL THEN 'user_' || user_id ELSE 'session_' || session_id END, team_id
+         FROM room_participants
+         WHERE room_id = $1 AND team_id IS NOT NULL"
+    )
+    .bind(room_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+// Placar das equipes a partir do placar individual (entradas com team_id, total_score, words_found)
+pub fn leaderboard(teams: &[RoomTeam], scores: &[serde_json::Value]) -> Vec<serde_json::Value> {
+    let mut totals = teams.iter()
+        .map(|team| {
+            let members = scores.iter().filter(|score| score["team_id"].as_i64() == Some(team.id as i64));
+            let (players, total_score, words_found) = members.fold((0, 0i64, 0i64), |(players, total, words), score| (
+                players + 1,
+                total + score["total_score"].as_i64().unwrap_or(0),
+                words + score["words_found"].as_i64().unwrap_or(0),
+            ));
+            (team, players, total_score, words_found)
+        })
+        .collect::<Vec<_>>();
+
+    // Empate: ordem definida pelo host
+    totals.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.position.cmp(&b.0.position)));
+
+    totals.into_iter().enumerate()
+        .map(|(index, (team, players, total_score, words_found))| json!({
+            "rank": index + 1,
+            "team_id": team.id,
+            "name": team.name,
+            "color": team.color,
+            "players": players,
+            "total_score": total_score,
+            "words_found": words_found,
+        }))
+        .collect()
+}
+
+// Equipes e placar das equipes para o snapshot / get_room_scores
+pub async fn room_team_state(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    scores: &[serde_json::Value],
+) -> Result<Option<(Vec<RoomTeam>, Vec<serde_json::Value>)>, sqlx::Error> {
+    let teams = load_teams(pool, room_id).await?;
+    if teams.is_empty() {
+        return Ok(None);
+    }
+    let board = leaderboard(&teams, scores);
+    Ok(Some((teams, board)))
+}
@@ -4,6 +4,20 @@ use actix_web_actors::ws;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::room_hub::{Leave, RoomRegistry};
+use crate::quiz_clock::{self, PacingMode, QuizClocks};
+use crate::models::{GameRoom, RoomTeam};
+use crate::room_clock::RoomTimer;
+use crate::room_status::{self, RoomStatus, StatusChange, StatusError};
+use crate::room_teams::{self, TeamAssignment, TeamError};
+use crate::word_search_clues;
+use crate::word_search_grid;
+use crate::word_search_mode::{self, Find, FindOutcome, WordSearchMode};
+use crate::word_search_hints::{self, HintError, HintKind, HintPlayer};
+use crate::word_search_scoring;
+use crate::word_search_words;
 
 // Mensagens do WebSocket
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,12 +35,20 @@ pub enum WsMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "foundAt")]
         found_at: Option<i32>,  // Tempo em segundos quando a palavra foi encontrada
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        team_id: Option<i32>,  // Equipe creditada (modo equipes)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        points: Option<i32>,  // Pontos ganhos (preenchido pelo servidor)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        concept: Option<String>,  // Conceito revelado (modo pistas, preenchido pelo servidor)
     },
     QuizAnswer {
         question_index: i32,
         answer: String,
         player_id: i32,
         player_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        team_id: Option<i32>,  // Preenchido pelo servidor
     },
     QuizConsensus {
         question_index: i32,
@@ -39,7 +61,13 @@ pub enum WsMessage {
     },
     QuizTimerSync {
         elapsed_time: i32,  // Tempo decorrido em segundos
-        player_id: i32,
+        player_id: i32,  // 0 = enviado pelo servidor
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        question_index: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        remaining_time: Option<i32>,  // Segundos restantes na pergunta atual
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        time_limit: Option<i32>,
     },
     QuizCurrentQuestion {
         question_index: i32,
@@ -52,6 +80,8 @@ pub enum WsMessage {
         username: String,
         player_id: i32,
         player_color: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        team_id: Option<i32>,
     },
     PlayerLeft {
         username: String,
@@ -82,6 +112,174 @@ pub enum WsMessage {
         question_id: i32,
         is_open: bool,
     },
+    // Cliente -> servidor após reconectar: reenviar eventos com seq > last_seq
+    Resume {
+        last_seq: u64,
+    },
+    Resumed {
+        last_seq: u64,
+        replayed: usize,
+    },
+    // Histórico não cobre last_seq: o cliente deve recarregar o estado da sala
+    ResumeFailed {
+        last_seq: u64,
+        current_seq: u64,
+    },
+    // Estado completo da sala, enviado ao conectar. Eventos com seq > seq já estão
+    // refletidos ou chegam em seguida.
+    GameStateSnapshot {
+        version: u32,
+        seq: u64,
+        state: serde_json::Value,
+    },
+    // Host -> servidor: controle do ciclo de vida da sala
+    RoomStart {
+        #[serde(default)]
+        countdown_seconds: Option<i32>,  // None/0 = iniciar imediatamente
+    },
+    RoomEnd {},
+    TimerPause {},
+    TimerResume {},
+    // Cada transição de estado da sala (lobby, countdown, running, paused, finished, archived)
+    RoomStatusChanged {
+        status: RoomStatus,
+        previous_status: RoomStatus,
+        changed_by: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,  // host, empty, countdown, time_up
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        countdown_ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    // Cronômetro autoritativo da sala (enviado periodicamente pelo servidor)
+    TimerSync {
+        status: RoomStatus,
+        elapsed_seconds: i64,
+        remaining_seconds: Option<i64>,  // None = sala sem tempo limite
+        time_limit: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        countdown_seconds: Option<i64>,
+        server_time: chrono::DateTime<chrono::Utc>,
+    },
+    // Jogador -> servidor: escolher equipe (antes do início da partida)
+    TeamJoin {
+        team_id: i32,
+    },
+    // Jogador -> servidor: pedir dica do caça-palavras (sem word, a primeira palavra que falta)
+    HintRequest {
+        #[serde(default)]
+        word: Option<String>,
+        kind: HintKind,
+    },
+    // Resposta ao remetente: dica calculada pelo servidor e pontos descontados
+    Hint {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+        word: String,
+        kind: HintKind,
+        cells: Vec<CellPosition>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        direction: Option<String>,
+        penalty: i32,
+    },
+    // Equipes da sala e jogadores que mudaram de equipe (cor = cor da equipe)
+    TeamsUpdated {
+        teams: Vec<RoomTeam>,
+        assignments: Vec<TeamAssignment>,
+    },
+    // Resposta ao remetente: mensagem aceita (request_id enviado pelo cliente)
+    Ack {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    // Resposta ao remetente: mensagem rejeitada
+    Error {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+        code: String,  // invalid_json, invalid_message, invalid_word, already_found, hint_unavailable, rejected, forbidden, invalid_state, internal_error
+        message: String,
+    },
+}
+
+// Papel da conexão na sala
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomRole {
+    Spectator,
+    Player,
+    Host,
+}
+
+impl RoomRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoomRole::Spectator => "spectator",
+            RoomRole::Player => "player",
+            RoomRole::Host => "host",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            RoomRole::Spectator => "participantes da sala",
+            RoomRole::Player => "jogadores",
+            RoomRole::Host => "o host da sala",
+        }
+    }
+}
+
+impl WsMessage {
+    // Papel mínimo para um cliente enviar a mensagem (None = só o servidor envia).
+    // Sem curinga de propósito: novas variantes precisam decidir quem pode enviá-las.
+    pub fn required_role(&self) -> Option<RoomRole> {
+        match self {
+            // Controle do fluxo do jogo
+            WsMessage::RoomReset { .. }
+            | WsMessage::QuizAdvance { .. }
+            | WsMessage::GameState { .. }
+            | WsMessage::OpenQuestionToggle { .. }
+            | WsMessage::RoomStart { .. }
+            | WsMessage::RoomEnd { .. }
+            | WsMessage::TimerPause { .. }
+            | WsMessage::TimerResume { .. }
+            | WsMessage::QuizConsensus { .. }
+            | WsMessage::QuizVoteState { .. } => Some(RoomRole::Host),
+            // Jogadas
+            WsMessage::WordFound { .. }
+            | WsMessage::QuizAnswer { .. }
+            | WsMessage::QuizTimerSync { .. }
+            | WsMessage::QuizCurrentQuestion { .. }
+            | WsMessage::QuizFinished { .. }
+            | WsMessage::OpenQuestionResponse { .. }
+            | WsMessage::TeamJoin { .. }
+            | WsMessage::HintRequest { .. } => Some(RoomRole::Player),
+            WsMessage::Resume { .. } => Some(RoomRole::Spectator),
+            // Eventos gerados pelo servidor
+            WsMessage::PlayerJoined { .. }
+            | WsMessage::PlayerLeft { .. }
+            | WsMessage::PlayersList { .. }
+            | WsMessage::Resumed { .. }
+            | WsMessage::ResumeFailed { .. }
+            | WsMessage::GameStateSnapshot { .. }
+            | WsMessage::RoomStatusChanged { .. }
+            | WsMessage::TimerSync { .. }
+            | WsMessage::TeamsUpdated { .. }
+            | WsMessage::Hint { .. }
+            | WsMessage::Ack { .. }
+            | WsMessage::Error { .. } => None,
+        }
+    }
+
+    pub fn permits(&self, role: RoomRole) -> bool {
+        self.required_role().is_some_and(|required| role >= required)
+    }
+
+    pub fn error(request_id: Option<String>, code: &str, message: impl Into<String>) -> Self {
+        WsMessage::Error {
+            request_id,
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +287,8 @@ pub struct PlayerInfo {
     pub player_id: i32,
     pub username: String,
     pub player_color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,11 +304,23 @@ pub struct ConnectionInfo {
     pub username: String,
     pub session_id: String,
     pub player_color: String,
+    pub team_id: Option<i32>,
+    pub role: RoomRole,
 }
 
-// Gerenciador de salas
-pub type RoomManager = Arc<Mutex<HashMap<i32, Vec<ConnectionInfo>>>>;
+impl ConnectionInfo {
+    // Espectadores recebem os eventos, mas não contam como jogadores
+    pub fn is_spectator(&self) -> bool {
+        self.role == RoomRole::Spectator
+    }
+}
 
+// Intervalo do heartbeat (ping) enviado pelo servidor
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+// Sem resposta do cliente por esse tempo, a conexão é encerrada
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(20);
+// Mensagens pendentes por conexão antes de ser considerada lenta
+const CONNECTION_MAILBOX_CAPACITY: usize = 256;
 // Gerenciador de votos (room_id -> votos)
 pub type VotesManager = Arc<Mutex<HashMap<i32, serde_json::Value>>>;
 
@@ -127,9 +339,14 @@ pub struct GameWebSocket {
     pub user_id: i32,
     pub username: String,
     pub player_color: String,
+    pub team_id: Option<i32>,
     pub session_id: String,
-    pub room_manager: RoomManager,
+    pub registry: RoomRegistry,
+    pub quiz_clocks: QuizClocks,
+    pub role: RoomRole,
     pub pool: sqlx::PgPool,
+    // Última atividade do cliente (mensagem, ping ou pong)
+    pub hb: Instant,
 }
 
 impl Actor for GameWebSocket {
@@ -138,93 +355,36 @@ impl Actor for GameWebSocket {
     fn started(&mut self, ctx: &mut Self::Context) {
         log::info!("WebSocket started for user {} in room {}", self.user_id, self.room_id);
         
-        // Adicionar à lista de conexões da sala e coletar lista de jogadores existentes
-        let (was_empty, existing_players) = {
-            let mut manager = self.room_manager.lock().unwrap();
-            let connections = manager.entry(self.room_id).or_insert_with(Vec::new);
-            let was_empty = connections.is_empty();
-            
-            // Remover conexões antigas do mesmo session_id (reconexão)
-            connections.retain(|conn| conn.session_id != self.session_id);
-            
-            // Coletar lista de jogadores já conectados (após remoção de duplicatas)
-            let existing_players: Vec<PlayerInfo> = connections.iter()
-                .map(|conn| PlayerInfo {
-                    player_id: conn.player_id,
-                    username: conn.username.clone(),
-                    player_color: conn.player_color.clone(),
-                })
-                .collect();
-            
-            // Adicionar nova conexão
-            connections.push(ConnectionInfo {
-                addr: ctx.address(),
-                player_id: self.user_id,
-                username: self.username.clone(),
-                session_id: self.session_id.clone(),
-                player_color: self.player_color.clone(),
-            });
-            
-            (was_empty, existing_players)
-        };
-        
-        // Enviar lista de jogadores já conectados APENAS para o novo jogador
-        if !existing_players.is_empty() {
-            ctx.text(serde_json::to_string(&WsMessage::PlayersList {
-                players: existing_players,
-            }).unwrap());
-        }
+        // Caixa de mensagens limitada: o hub desconecta clientes que não acompanham
+        ctx.set_mailbox_capacity(CONNECTION_MAILBOX_CAPACITY);
+        self.start_heartbeat(ctx);
         
-        // Notificar TODOS que um novo jogador entrou
-        self.broadcast(WsMessage::PlayerJoined {
+        // Registrar no hub da sala (lista de jogadores, PlayerJoined e timer ficam a cargo dele)
+        self.registry.join(self.room_id, ConnectionInfo {
+            addr: ctx.address(),
             player_id: self.user_id,
             username: self.username.clone(),
+            session_id: self.session_id.clone(),
             player_color: self.player_color.clone(),
-        }, None);
+            team_id: self.team_id,
+            role: self.role,
+        });
         
-        // Se é a primeira conexão, iniciar/retomar timer
-        if was_empty {
+        // Carregar relógio do quiz (se for quiz) e enviar o estado atual para o novo jogador
+        {
             let room_id = self.room_id;
             let pool = self.pool.clone();
-            
-            actix::spawn(async move {
-                // Verificar se estava pausado
-                let pause_info = sqlx::query_as::<_, (Option<chrono::DateTime<chrono::Utc>>, Option<i32>)>(
-                    "SELECT paused_at, total_pause_duration FROM game_rooms WHERE id = $1"
-                )
-                .bind(room_id)
-                .fetch_one(&pool)
-                .await;
+            let clocks = self.quiz_clocks.clone();
+            let registry = self.registry.clone();
+            let addr = ctx.address();
 
-                if let Ok((paused_at, total_pause)) = pause_info {
-                    if let Some(pause_time) = paused_at {
-                        // Calcular quanto tempo ficou pausado
-                        let pause_duration = (chrono::Utc::now() - pause_time).num_seconds() as i32;
-                        let total_pause_duration = total_pause.unwrap_or(0) + pause_duration;
-                        
-                        // Atualizar total de pausa e limpar paused_at
-                        let _ = sqlx::query(
-                            "UPDATE game_rooms 
-                             SET paused_at = NULL, total_pause_duration = $1 
-                             WHERE id = $2"
-                        )
-                        .bind(total_pause_duration)
-                        .bind(room_id)
-                        .execute(&pool)
-                        .await;
-                        
-                        log::info!("Room {} resumed via WebSocket. Pause duration: {}s, Total pause: {}s", 
-                            room_id, pause_duration, total_pause_duration);
-                    } else {
-                        // Primeira vez entrando, iniciar timer
-                        let _ = sqlx::query(
-                            "UPDATE game_rooms SET started_at = NOW() WHERE id = $1 AND started_at IS NULL"
-                        )
-                        .bind(room_id)
-                        .execute(&pool)
-                        .await;
-                        
-                        log::info!("Room {} timer started via WebSocket", room_id);
+            actix::spawn(async move {
+                if let Some(clock) = quiz_clock::ensure_clock(pool, room_id, clocks, registry).await {
+                    if clock.is_server_driven() && !clock.finished {
+                        let sync = clock.timer_sync(chrono::Utc::now());
+                        addr.do_send(SendMessage {
+                            text: serde_json::to_string(&sync).unwrap(),
+                        });
                     }
                 }
             });
@@ -234,213 +394,149 @@ impl Actor for GameWebSocket {
     fn stopped(&mut self, ctx: &mut Self::Context) {
         log::info!("WebSocket stopped for user {} in room {}", self.user_id, self.room_id);
         
-        // Notificar todos que um jogador saiu
-        self.broadcast(WsMessage::PlayerLeft {
+        self.registry.leave(self.room_id, Leave {
+            addr: ctx.address(),
+            session_id: self.session_id.clone(),
             player_id: self.user_id,
             username: self.username.clone(),
-        }, None);
-        
-        // Remover da lista de conexões
-        let remaining_connections = {
-            let mut manager = self.room_manager.lock().unwrap();
-            if let Some(connections) = manager.get_mut(&self.room_id) {
-                let before = connections.len();
-                connections.retain(|conn| conn.addr != ctx.address());
-                let after = connections.len();
-                log::info!("🔌 Removendo conexão da sala {}. Antes: {}, Depois: {}", self.room_id, before, after);
-                if after == 0 {
-                    manager.remove(&self.room_id);
-                }
-                after
-            } else {
-                0
-            }
-        };
-        
-        // Se não há mais conexões, pausar o timer
-        if remaining_connections == 0 {
-            let room_id = self.room_id;
-            let pool = self.pool.clone();
-            
-            actix::spawn(async move {
-                let _ = sqlx::query(
-                    "UPDATE game_rooms SET paused_at = NOW() WHERE id = $1"
-                )
-                .bind(room_id)
-                .execute(&pool)
-                .await;
-                
-                log::info!("Room {} timer paused (all players left)", room_id);
-            });
-        }
+            spectator: self.role == RoomRole::Spectator,
+        });
     }
 }
 
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GameWebSocket {
     fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        // Qualquer frame do cliente conta como sinal de vida
+        self.hb = Instant::now();
+        
         match msg {
             Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
+            Ok(ws::Message::Pong(_)) => {},
             Ok(ws::Message::Text(text)) => {
-                log::info!("Received WebSocket text message: {}", text);
-                // Parse mensagem recebida
-                match serde_json::from_str::<WsMessage>(&text) {
-                    Ok(mut ws_msg) => {
-                        log::info!("Parsed WS message: {:?}", ws_msg);
-                    
-                    // Adicionar player_id e player_color nas mensagens WordFound
-                    ws_msg = match ws_msg {
-                        WsMessage::WordFound { word, cells, found_at, .. } => {
-                            let word_msg = WsMessage::WordFound {
-                                word: word.clone(),
-                                cells: cells.clone(),
-                                player_id: Some(self.user_id),
-                                player_color: Some(self.player_color.clone()),
-                                player_name: Some(self.username.clone()),
-                                found_at,
-                            };
-                            
-                            // Salvar palavra encontrada no banco de dados
-                            let room_id = self.room_id;
-                            let session_id = self.session_id.clone();
-                            let player_name = self.username.clone();
-                            let player_color = self.player_color.clone();
-                            let pool = self.pool.clone();
-                            
-                            actix::spawn(async move {
-                                let cells_json = serde_json::to_value(&cells).unwrap();
-                                let word_upper = word.to_uppercase();
-                                
-                                // Salvar palavra encontrada
-                                let result = sqlx::query(
-                                    "INSERT INTO room_found_words 
-                                     (room_id, word, found_by_session_id, found_by_name, player_color, cells)
-                                     VALUES ($1, $2, $3, $4, $5, $6)
-                                     ON CONFLICT (room_id, word) DO NOTHING"
-                                )
-                                .bind(room_id)
-                                .bind(&word_upper)
-                                .bind(&session_id)
-                                .bind(&player_name)
-                                .bind(&player_color)
-                                .bind(&cells_json)
-                                .execute(&pool)
-                                .await;
-                                
-                                if let Err(e) = result {
-                                    log::error!("Error saving found word: {}", e);
-                                    return;
-                                }
-                                
-                                // Calcular pontuação da palavra baseada no tempo
-                                // Pontuação diminui gradualmente:
-                                // 0-60s: 100 pontos (100%)
-                                // 61-300s (5min): diminui de 100 para 50 (50%)
-                                // 301-600s (10min): diminui de 50 para 20 (20%)
-                                // Acima de 600s: 10 pontos (mínimo)
-                                log::info!("💰 Calculando pontuação - found_at: {:?}", found_at);
-                                let total_score = if let Some(found_at) = found_at {
-                                    if found_at <= 60 {
-                                        100  // Primeiro minuto: 100 pontos
-                                    } else if found_at <= 300 {
-                                        // De 1 a 5 minutos: diminui de 100 para 50
-                                        let elapsed = found_at - 60;  // 0 a 240 segundos
-                                        let reduction = (elapsed as f32 / 240.0) * 50.0;  // 0 a 50 pontos de redução
-                                        (100.0 - reduction).floor() as i32
-                                    } else if found_at <= 600 {
-                                        // De 5 a 10 minutos: diminui de 50 para 20
-                                        let elapsed = found_at - 300;  // 0 a 300 segundos
-                                        let reduction = (elapsed as f32 / 300.0) * 30.0;  // 0 a 30 pontos de redução
-                                        (50.0 - reduction).floor() as i32
-                                    } else {
-                                        10  // Acima de 10 minutos: pontuação mínima
-                                    }
-                                } else {
-                                    log::warn!("⚠️ found_at é None, usando pontuação máxima");
-                                    100  // Se não tiver tempo, pontuação máxima
-                                };
-                                log::info!("💰 Pontuação calculada: {} pontos (tempo: {:?}s)", total_score, found_at);
-                                
-                                // Atualizar pontuação do jogador
-                                let _ = sqlx::query(
-                                    "INSERT INTO room_player_scores 
-                                     (room_id, session_id, player_name, player_color, words_found, total_score)
-                                     VALUES ($1, $2, $3, $4, 1, $5)
-                                     ON CONFLICT (room_id, session_id) 
-                                     DO UPDATE SET 
-                                        words_found = room_player_scores.words_found + 1,
-                                        total_score = room_player_scores.total_score + $5,
-                                        last_updated = NOW()"
-                                )
-                                .bind(room_id)
-                                .bind(&session_id)
-                                .bind(&player_name)
-                                .bind(&player_color)
-                                .bind(total_score)
-                                .execute(&pool)
-                                .await;
-                                
-                                // Atualizar pontuação total da sala
-                                let _ = sqlx::query(
-                                    "UPDATE game_rooms 
-                                     SET total_score = COALESCE(total_score, 0) + $1
-                                     WHERE id = $2"
-                                )
-                                .bind(total_score)
-                                .bind(room_id)
-                                .execute(&pool)
-                                .await;
-                            });
-                            
-                            word_msg
-                        },
-                        WsMessage::QuizAnswer { question_index, answer, player_id, player_name } => {
-                            let quiz_msg = WsMessage::QuizAnswer {
-                                question_index,
-                                answer: answer.clone(),
-                                player_id,
-                                player_name: player_name.clone(),
-                            };
-                            
-                            // Processar votação do quiz
-                            let room_id = self.room_id;
-                            let room_manager = self.room_manager.clone();
-                            
-                            actix::spawn(async move {
-                                // Contar votos atuais
-                                let manager = room_manager.lock().unwrap();
-                                let total_players = if let Some(connections) = manager.get(&room_id) {
-                                    connections.len() as i32
-                                } else {
-                                    return;
-                                };
-                                drop(manager);
-                                
-                                // Aqui você pode salvar o voto em memória ou banco
-                                // Por simplicidade, vamos apenas broadcast e deixar o frontend gerenciar
-                                
-                                log::info!("Quiz vote - Room {}, Question {}, Answer {}, Total players: {}", 
-                                    room_id, question_index, answer, total_players);
-                            });
-                            
-                            quiz_msg
-                        },
-                        WsMessage::QuizAdvance { question_index } => {
-                            // Repassar sinal de avanço para todos
-                            WsMessage::QuizAdvance { question_index }
-                        },
-                        WsMessage::QuizTimerSync { elapsed_time, player_id } => {
-                            // Repassar sincronização de timer para todos
-                            WsMessage::QuizTimerSync { elapsed_time, player_id }
-                        },
-                        other => other,
-                    };
-                    
-                    // Broadcast para todos na sala
-                    self.broadcast(ws_msg, Some(self.user_id));
-                    },
+                log::debug!("Received WebSocket text message: {}", text);
+                // Parse em duas etapas para devolver o request_id mesmo se a mensagem for inválida
+                let value = match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        log::warn!("Invalid WebSocket JSON: {}", e);
+                        self.reply(ctx, WsMessage::error(None, "invalid_json", format!("JSON inválido: {}", e)));
+                        return;
+                    }
+                };
+                let request_id = value.get("request_id").and_then(|id| match id {
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    serde_json::Value::Null => None,
+                    other => Some(other.to_string()),
+                });
+                
+                let mut ws_msg = match serde_json::from_value::<WsMessage>(value) {
+                    Ok(ws_msg) => ws_msg,
                     Err(e) => {
-                        log::error!("Failed to parse WebSocket message: {}. Raw text: {}", e, text);
+                        log::warn!("Failed to parse WebSocket message: {}. Raw text: {}", e, text);
+                        self.reply(ctx, WsMessage::error(request_id, "invalid_message", format!("Mensagem inválida: {}", e)));
+                        return;
                     }
+                };
+                
+                // Cada tipo de mensagem exige um papel mínimo na sala
+                if !ws_msg.permits(self.role) {
+                    log::info!("🚫 {} ({}) sem permissão para {:?} na sala {}",
+                        self.username, self.role.as_str(), ws_msg.required_role(), self.room_id);
+                    let reason = match ws_msg.required_role() {
+                        Some(role) => format!("Apenas {} pode enviar esta mensagem", role.description()),
+                        None => "Tipo de mensagem reservado ao servidor".to_string(),
+                    };
+                    self.reply(ctx, WsMessage::error(request_id, "forbidden", reason));
+                    return;
+                }
+                
+                // Retomada após reconexão: reenviar apenas para este cliente
+                if let WsMessage::Resume { last_seq } = ws_msg {
+                    self.resume(last_seq, ctx);
+                    return;
+                }
+                
+                // Nos modos controlados pelo servidor, o relógio decide o que é repassado
+                if !self.apply_quiz_pacing(&ws_msg) {
+                    self.reply(ctx, WsMessage::error(request_id, "rejected", "Mensagem fora do tempo ou não permitida no modo do quiz"));
+                    return;
+                }
+                
+                // Jogadas só contam com a partida em andamento (não no lobby, pausa ou após o fim)
+                if matches!(ws_msg, WsMessage::WordFound { .. } | WsMessage::QuizAnswer { .. } | WsMessage::HintRequest { .. })
+                    && !self.registry.accepts_plays(self.room_id)
+                {
+                    self.reply(ctx, WsMessage::error(request_id, "invalid_state", "A partida não está em andamento"));
+                    return;
+                }
+                
+                ws_msg = match ws_msg {
+                    WsMessage::RoomStart { countdown_seconds } => {
+                        let change = match countdown_seconds {
+                            Some(seconds) if seconds > 0 => StatusChange::Countdown {
+                                seconds: seconds.min(room_status::MAX_COUNTDOWN_SECS),
+                            },
+                            _ => StatusChange::Start,
+                        };
+                        self.change_status(change, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::RoomEnd {} => {
+                        self.change_status(StatusChange::End { reason: room_status::REASON_HOST }, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::TimerPause {} => {
+                        self.change_status(StatusChange::Pause { reason: room_status::REASON_HOST }, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::TimerResume {} => {
+                        self.change_status(StatusChange::Resume { only_reason: None }, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::WordFound { word, cells, .. } => {
+                        // Validada e salva antes do broadcast; o resultado volta como Ack ou Error.
+                        // O found_at do cliente é ignorado: vale o tempo do servidor.
+                        self.submit_word(word, cells, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::TeamJoin { team_id } => {
+                        self.join_team(team_id, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::HintRequest { word, kind } => {
+                        self.request_hint(word, kind, request_id, ctx);
+                        return;
+                    },
+                    WsMessage::QuizAnswer { question_index, answer, .. } => {
+                        // Aqui você pode salvar o voto em memória ou banco
+                        // Por simplicidade, vamos apenas broadcast e deixar o frontend gerenciar
+                        log::info!("Quiz vote - Room {}, Question {}, Answer {}, Total players: {}", 
+                            self.room_id, question_index, answer, self.registry.player_count(self.room_id));
+                        
+                        // O voto é sempre de quem está conectado (ignora o jogador informado pelo cliente)
+                        WsMessage::QuizAnswer {
+                            question_index,
+                            answer,
+                            player_id: self.user_id,
+                            player_name: self.username.clone(),
+                            team_id: self.team_id,
+                        }
+                    },
+                    // O player_id repassado é sempre o da conexão (0 é reservado ao servidor)
+                    WsMessage::QuizTimerSync { elapsed_time, question_index, remaining_time, time_limit, .. } => {
+                        WsMessage::QuizTimerSync { elapsed_time, player_id: self.user_id, question_index, remaining_time, time_limit }
+                    },
+                    WsMessage::QuizCurrentQuestion { question_index, .. } => {
+                        WsMessage::QuizCurrentQuestion { question_index, player_id: self.user_id }
+                    },
+                    WsMessage::QuizFinished { .. } => WsMessage::QuizFinished { player_id: self.user_id },
+                    other => other,
+                };
+                
+                // Broadcast para todos na sala
+                self.broadcast(ws_msg, Some(self.user_id));
+                if request_id.is_some() {
+                    self.reply(ctx, WsMessage::Ack { request_id });
                 }
             }
             Ok(ws::Message::Binary(_)) => {},
@@ -454,25 +550,399 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GameWebSocket {
 }
 
 impl GameWebSocket {
-    fn broadcast(&self, message: WsMessage, exclude_user: Option<i32>) {
-        let manager = self.room_manager.lock().unwrap();
-        if let Some(connections) = manager.get(&self.room_id) {
-            let text = serde_json::to_string(&message).unwrap();
+    fn broadcast(&self, message: WsMessage, _exclude_user: Option<i32>) {
+        self.registry.publish(self.room_id, &message);
+    }
+
+    // Envia um quadro apenas para este cliente (sem número de sequência)
+    fn reply(&self, ctx: &mut ws::WebsocketContext<Self>, message: WsMessage) {
+        ctx.text(serde_json::to_string(&message).unwrap());
+    }
+
+    // Ações do host sobre o estado da sala. O RoomStatusChanged vai para todos e cada
+    // hub recarrega o cronômetro do banco.
+    fn change_status(&self, change: StatusChange, request_id: Option<String>, ctx: &mut ws::WebsocketContext<Self>) {
+        let room_id = self.room_id;
+        let username = self.username.clone();
+        let pool = self.pool.clone();
+        let registry = self.registry.clone();
+        let addr = ctx.address();
+        
+        actix::spawn(async move {
+            let reply = |message: WsMessage| addr.do_send(SendMessage {
+                text: serde_json::to_string(&message).unwrap(),
+            });
             
-            log::info!("🔊 Broadcasting to room {}: {} connections. Message type: {:?}", 
-                self.room_id, connections.len(), message);
+            match room_status::change(&pool, &registry, room_id, change, &username).await {
+                Ok(_) => reply(WsMessage::Ack { request_id }),
+                Err(StatusError::Database(e)) => {
+                    log::error!("Error changing room {} status: {}", room_id, e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao atualizar a sala"));
+                }
+                Err(e) => reply(WsMessage::error(request_id, "invalid_state", e.message())),
+            }
+        });
+    }
+
+    // Troca de equipe pelo próprio jogador. O TeamsUpdated atualiza a cor em todas as conexões.
+    fn join_team(&self, team_id: i32, request_id: Option<String>, ctx: &mut ws::WebsocketContext<Self>) {
+        let room_id = self.room_id;
+        let player_id = self.user_id;
+        let pool = self.pool.clone();
+        let registry = self.registry.clone();
+        let addr = ctx.address();
+        
+        actix::spawn(async move {
+            let reply = |message: WsMessage| addr.do_send(SendMessage {
+                text: serde_json::to_string(&message).unwrap(),
+            });
             
-            // Enviar para todos os jogadores na sala
-            for (index, conn_info) in connections.iter().enumerate() {
-                log::info!("📤 Enviando para conexão {} da sala {}", index, self.room_id);
-                conn_info.addr.do_send(SendMessage {
-                    text: text.clone(),
-                });
+            match room_teams::join_team(&pool, room_id, player_id, Some(team_id)).await {
+                Ok(Some((assignment, changed))) => {
+                    if changed {
+                        match room_teams::load_teams(&pool, room_id).await {
+                            Ok(teams) => registry.publish(room_id, &WsMessage::TeamsUpdated {
+                                teams,
+                                assignments: vec![assignment],
+                            }),
+                            Err(e) => log::error!("Failed to load teams for room {}: {}", room_id, e),
+                        }
+                    }
+                    reply(WsMessage::Ack { request_id });
+                }
+                Ok(None) => reply(WsMessage::error(request_id, "invalid_state", "Esta sala não usa equipes")),
+                Err(TeamError::Database(e)) => {
+                    log::error!("Error joining team in room {}: {}", room_id, e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao trocar de equipe"));
+                }
+                Err(e) => reply(WsMessage::error(request_id, "invalid_state", e.message())),
+            }
+        });
+    }
+
+    // Dica só para o jogador que pediu; a penalidade é descontada da pontuação dele
+    fn request_hint(&self, word: Option<String>, kind: HintKind, request_id: Option<String>, ctx: &mut ws::WebsocketContext<Self>) {
+        let room_id = self.room_id;
+        let participant_id = self.user_id;
+        let session_id = self.session_id.clone();
+        let player_name = self.username.clone();
+        let player_color = self.player_color.clone();
+        let pool = self.pool.clone();
+        let addr = ctx.address();
+        
+        actix::spawn(async move {
+            let reply = |message: WsMessage| addr.do_send(SendMessage {
+                text: serde_json::to_string(&message).unwrap(),
+            });
+            
+            // Conferência no banco (a mudança de estado pode não ter chegado a esta instância)
+            let room = match sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE id = $1")
+                .bind(room_id)
+                .fetch_one(&pool)
+                .await
+            {
+                Ok(room) => room,
+                Err(e) => {
+                    log::error!("Error checking room state: {}", e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao calcular dica"));
+                    return;
+                }
+            };
+            if !RoomTimer::from_room(&room).status.accepts_plays() {
+                reply(WsMessage::error(request_id, "invalid_state", "A partida não está em andamento"));
+                return;
+            }
+            
+            let player = HintPlayer {
+                participant_id,
+                session_id: &session_id,
+                player_name: &player_name,
+                player_color: &player_color,
+            };
+            match word_search_hints::request_hint(&pool, &room, &player, word.as_deref(), kind).await {
+                Ok(hint) => {
+                    log::info!("💡 Dica {} para {} na sala {}: {} (-{} pontos)",
+                        kind.as_str(), player_name, room_id, hint.word, hint.penalty);
+                    reply(WsMessage::Hint {
+                        request_id,
+                        word: hint.word,
+                        kind: hint.kind,
+                        cells: hint.cells,
+                        direction: hint.direction,
+                        penalty: hint.penalty,
+                    });
+                }
+                Err(HintError::Database(e)) => {
+                    log::error!("Error requesting hint in room {}: {}", room_id, e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao calcular dica"));
+                }
+                Err(e) => reply(WsMessage::error(request_id, "hint_unavailable", e.message())),
+            }
+        });
+    }
+
+    // Valida a palavra contra o caça-palavras da sala, registra no tabuleiro definido pelo
+    // modo da sala e só então faz o broadcast. Duplicatas e palavras inválidas voltam como Error.
+    fn submit_word(
+        &self,
+        word: String,
+        cells: Vec<CellPosition>,
+        request_id: Option<String>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let room_id = self.room_id;
+        let player_id = self.user_id;
+        let session_id = self.session_id.clone();
+        let player_name = self.username.clone();
+        let player_color = self.player_color.clone();
+        let pool = self.pool.clone();
+        let registry = self.registry.clone();
+        let addr = ctx.address();
+        
+        actix::spawn(async move {
+            let reply = |message: WsMessage| addr.do_send(SendMessage {
+                text: serde_json::to_string(&message).unwrap(),
+            });
+            // Conferência no banco (a mudança de estado pode não ter chegado a esta instância)
+            let room = match sqlx::query_as::<_, GameRoom>("SELECT * FROM game_rooms WHERE id = $1")
+                .bind(room_id)
+                .fetch_one(&pool)
+                .await
+            {
+                Ok(room) => room,
+                Err(e) => {
+                    log::error!("Error checking room state: {}", e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao validar palavra"));
+                    return;
+                }
+            };
+            let timer = RoomTimer::from_room(&room);
+            if !timer.status.accepts_plays() {
+                reply(WsMessage::error(request_id, "invalid_state", "A partida não está em andamento"));
+                return;
+            }
+            let mode = WordSearchMode::parse(&room.word_search_mode).unwrap_or_default();
+            let elapsed_seconds = timer.elapsed(chrono::Utc::now()).map(|elapsed| elapsed as i32);
+            
+            let (words, hidden, scoring) = match word_search_scoring::load_game_scoring(&pool, room.game_id).await {
+                Ok(Some(config)) => config,
+                Ok(None) => {
+                    reply(WsMessage::error(request_id, "invalid_word", "Este jogo não é um caça-palavras"));
+                    return;
+                }
+                Err(e) => {
+                    log::error!("Error validating found word: {}", e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao validar palavra"));
+                    return;
+                }
+            };
+            
+            // Salva a palavra como está na configuração do jogo
+            let word_upper = match words.iter().find(|w| word_search_words::same_word(w, &word)) {
+                Some(w) => w.to_uppercase(),
+                None => {
+                    reply(WsMessage::error(request_id, "invalid_word", format!("\"{}\" não é uma palavra deste jogo", word_search_words::key(&word))));
+                    return;
+                }
+            };
+            
+            // As células precisam ser as da palavra na grade da sala (em qualquer sentido)
+            let layout = sqlx::query_as::<_, (i32, Option<serde_json::Value>)>(
+                "SELECT grid_size, allowed_directions FROM word_search_configs WHERE game_id = $1"
+            )
+            .bind(room.game_id)
+            .fetch_one(&pool)
+            .await;
+            let (grid_size, allowed_directions) = match layout {
+                Ok(layout) => layout,
+                Err(e) => {
+                    log::error!("Error validating found word: {}", e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao validar palavra"));
+                    return;
+                }
+            };
+            let placed = word_search_grid::place_words(word_search_grid::room_seed(&room), grid_size, &words, allowed_directions.as_ref());
+            let in_grid = placed.iter()
+                .find(|placed| word_search_words::same_word(&placed.word, &word_upper))
+                .is_some_and(|placed| placed.matches(&cells));
+            if !in_grid {
+                reply(WsMessage::error(request_id, "invalid_word", "As letras marcadas não formam esta palavra na grade"));
+                return;
             }
             
-            log::info!("✅ Broadcast concluído para {} conexões", connections.len());
-        } else {
-            log::warn!("⚠️ Sala {} não encontrada no manager", self.room_id);
+            // Modo pistas: o conceito da palavra vai junto no broadcast
+            let concept = match word_search_clues::load_concepts(&pool, room.game_id).await {
+                Ok(concepts) => word_search_clues::concept_for(concepts.as_ref(), &word_upper),
+                Err(_) => None,
+            };
+            
+            // Pontuação pela curva do caça-palavras, sempre pelo tempo do servidor
+            let word_score = scoring.word_points(&word_upper, elapsed_seconds, hidden);
+            
+            let cells_json = serde_json::to_value(&cells).unwrap();
+            
+            // Salvar palavra encontrada no tabuleiro que o modo da sala define. A equipe é
+            // lida do participante no momento da inserção.
+            let outcome = word_search_mode::record_find(&pool, mode, &Find {
+                room_id,
+                participant_id: player_id,
+                session_id: &session_id,
+                player_name: &player_name,
+                player_color: &player_color,
+                word: &word_upper,
+                cells: &cells_json,
+                elapsed_seconds,
+                points: word_score,
+            }).await;
+            
+            let (team_id, total_score) = match outcome {
+                Ok(FindOutcome::Found { team_id, points }) => (team_id, points),
+                Ok(FindOutcome::AlreadyFound { by: Some(found_by) }) => {
+                    reply(WsMessage::error(request_id, "already_found", format!("Já encontrada por {}", found_by)));
+                    return;
+                }
+                Ok(FindOutcome::AlreadyFound { by: None }) => {
+                    reply(WsMessage::error(request_id, "already_found", "Você já encontrou esta palavra"));
+                    return;
+                }
+                Err(e) => {
+                    log::error!("Error saving found word: {}", e);
+                    reply(WsMessage::error(request_id, "internal_error", "Erro ao salvar palavra"));
+                    return;
+                }
+            };
+            
+            // Bônus de tabuleiro completo para quem encontrou a última palavra. No modo
+            // cooperativo o tabuleiro é compartilhado, então o bônus vai inteiro para quem
+            // fechou o tabuleiro da sala (não é dividido entre os jogadores).
+            let total_score = if scoring.bonuses.completion > 0
+                && word_search_mode::board_complete(&pool, mode, room_id, &session_id, words.len()).await.unwrap_or(false)
+            {
+                total_score + scoring.bonuses.completion
+            } else {
+                total_score
+            };
+            log::info!("💰 Pontuação calculada: {} pontos (tempo: {:?}s, modo {})", total_score, elapsed_seconds, mode.as_str());
+            
+            // Nos modos competitivos os outros jogadores só ficam sabendo quem pontuou: a
+            // palavra, as células e o conceito (modo pistas) revelariam o próprio tabuleiro
+            let competitive = mode.is_competitive();
+            registry.publish(room_id, &WsMessage::WordFound {
+                word: if competitive { String::new() } else { word_upper },
+                cells: if competitive { Vec::new() } else { cells },
+                player_id: Some(player_id),
+                player_color: Some(player_color.clone()),
+                player_name: Some(player_name.clone()),
+                found_at: elapsed_seconds,
+                team_id,
+                points: Some(total_score),
+                concept: if competitive { None } else { concept },
+            });
+            reply(WsMessage::Ack { request_id });
+            
+            // Atualizar pontuação do jogador
+            let _ = sqlx::query(
+                "INSERT INTO room_player_scores 
+                 (room_id, session_id, player_name, player_color, words_found, total_score, team_id, last_found_seconds)
+                 VALUES ($1, $2, $3, $4, 1, $5, $6, $7)
+                 ON CONFLICT (room_id, session_id) 
+                 DO UPDATE SET 
+                    words_found = room_player_scores.words_found + 1,
+                    total_score = room_player_scores.total_score + $5,
+                    team_id = EXCLUDED.team_id,
+                    last_found_seconds = EXCLUDED.last_found_seconds,
+                    last_updated = NOW()"
+            )
+            .bind(room_id)
+            .bind(&session_id)
+            .bind(&player_name)
+            .bind(&player_color)
+            .bind(total_score)
+            .bind(team_id)
+            .bind(elapsed_seconds)
+            .execute(&pool)
+            .await;
+            
+            // Atualizar pontuação total da sala
+            let _ = sqlx::query(
+                "UPDATE game_rooms 
+                 SET total_score = COALESCE(total_score, 0) + $1
+                 WHERE id = $2"
+            )
+            .bind(total_score)
+            .bind(room_id)
+            .execute(&pool)
+            .await;
+        });
+    }
+
+    // Envia ping periodicamente e encerra conexões que pararam de responder
+    fn start_heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |act, ctx| {
+            if Instant::now().duration_since(act.hb) > CLIENT_TIMEOUT {
+                log::info!("💔 Heartbeat timeout for user {} in room {}", act.user_id, act.room_id);
+                ctx.close(Some(ws::CloseReason {
+                    code: ws::CloseCode::Away,
+                    description: Some("heartbeat timeout".to_string()),
+                }));
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+
+    fn resume(&self, last_seq: u64, ctx: &mut ws::WebsocketContext<Self>) {
+        self.registry.replay(self.room_id, last_seq, ctx.address());
+    }
+
+    // Aplica o modo de ritmo do quiz. Retorna false se a mensagem deve ser descartada.
+    fn apply_quiz_pacing(&self, message: &WsMessage) -> bool {
+        let now = chrono::Utc::now();
+        let mut clocks = self.quiz_clocks.lock().unwrap();
+        let clock = match clocks.get_mut(&self.room_id) {
+            Some(clock) if clock.is_server_driven() => clock,
+            _ => return true,
+        };
+
+        match message {
+            WsMessage::QuizTimerSync { .. } => {
+                // O servidor é a fonte do tempo
+                false
+            },
+            // Pergunta atual e fim do quiz também vêm só do relógio do servidor
+            WsMessage::QuizCurrentQuestion { .. } | WsMessage::QuizFinished { .. } => false,
+            WsMessage::QuizAnswer { question_index, .. } => {
+                let accepted = clock.accepts_answer(*question_index, now);
+                if !accepted {
+                    log::info!("⏱️ Resposta descartada na sala {}: pergunta {} (atual {})",
+                        self.room_id, question_index, clock.question_index);
+                }
+                accepted
+            },
+            WsMessage::QuizAdvance { question_index } => {
+                // Só hosts chegam aqui (ver WsMessage::required_role)
+                if clock.pacing_mode != PacingMode::HostPaced {
+                    return false;
+                }
+                if *question_index <= clock.question_index {
+                    return false;
+                }
+
+                let from_index = clock.question_index;
+                clock.advance_to(*question_index, now);
+
+                let pool = self.pool.clone();
+                let room_id = self.room_id;
+                let question_index = *question_index;
+                actix::spawn(async move {
+                    if let Err(e) = quiz_clock::advance_clock(&pool, room_id, from_index, question_index, now).await {
+                        log::error!("Failed to persist quiz clock for room {}: {}", room_id, e);
+                    }
+                });
+                true
+            },
+            _ => true,
         }
     }
 }
@@ -492,12 +962,51 @@ impl Handler<SendMessage> for GameWebSocket {
     }
 }
 
+// Equipe (e cor) da conexão alterada pelo host ou pelo próprio jogador
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct SetTeam {
+    pub team_id: Option<i32>,
+    pub player_color: String,
+}
+
+impl Handler<SetTeam> for GameWebSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetTeam, _ctx: &mut Self::Context) {
+        self.team_id = msg.team_id;
+        self.player_color = msg.player_color;
+    }
+}
+
+// Encerra a conexão com um motivo (cliente lento, sala encerrada...)
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct Disconnect {
+    pub reason: String,
+    // false quando a sala foi fechada (o cliente não deve tentar reconectar)
+    pub reconnect: bool,
+}
+
+impl Handler<Disconnect> for GameWebSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: Disconnect, ctx: &mut Self::Context) {
+        ctx.close(Some(ws::CloseReason {
+            code: if msg.reconnect { ws::CloseCode::Again } else { ws::CloseCode::Normal },
+            description: Some(msg.reason),
+        }));
+        ctx.stop();
+    }
+}
+
 // Endpoint WebSocket
 pub async fn room_websocket(
     req: HttpRequest,
     stream: web::Payload,
     room_id: web::Path<i32>,
-    room_manager: web::Data<RoomManager>,
+    registry: web::Data<RoomRegistry>,
+    quiz_clocks: web::Data<QuizClocks>,
     pool: web::Data<sqlx::PgPool>,
 ) -> Result<HttpResponse, Error> {
     // Tentar extrair user_id do token (usuário autenticado)
@@ -510,46 +1019,138 @@ pub async fn room_websocket(
                 .find(|(key, _)| key == "token")
                 .map(|(_, value)| value.to_string())
         }) {
-            // Validar token
-            use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
-            
-            let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "secret".to_string());
-            let mut validation = Validation::new(Algorithm::HS256);
-            validation.validate_exp = true;
-            
-            if let Ok(token_data) = decode::<serde_json::Value>(
-                &token_param,
-                &DecodingKey::from_secret(jwt_secret.as_bytes()),
-                &validation,
-            ) {
-                // Tentar pegar "sub" ou "user_id" do token
-                if let Some(user_id) = token_data.claims.get("sub")
-                    .and_then(|v| v.as_i64())
-                    .or_else(|| token_data.claims.get("user_id").and_then(|v| v.as_i64())) 
-                {
-                    user_id_from_token = Some(user_id as i32);
-                }
-            }
+            // Validar token (mesmo token do login)
+            user_id_from_token = crate::middleware::user_id_from_token(&token_param);
         }
     }
     
     // Tentar extrair session_id do query parameter (jogador anônimo)
     // WebSocket do navegador não suporta headers customizados
-    let session_id = req
-        .uri()
-        .query()
-        .and_then(|q| {
-            url::form_urlencoded::parse(q.as_bytes())
-                .find(|(key, _)| key == "session_id")
-                .map(|(_, value)| value.to_string())
-        });
+    let query_param = |name: &str| req.uri().query().and_then(|q| {
+        url::form_urlencoded::parse(q.as_bytes())
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+    });
+    let session_id = query_param("session_id");
+    
+    // Espectador (projetor): token de espectador da sala ou host/criador logado com ?mode=spectator.
+    // Não entra em room_participants, não ganha cor e não aparece no PlayersList.
+    let spectator_room = query_param("spectator_token").and_then(|token| {
+        use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+        
+        let jwt_secret = crate::middleware::jwt_secret();
+        decode::<crate::models::SpectatorClaims>(
+            &token,
+            &DecodingKey::from_secret(jwt_secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .ok()
+        .filter(|data| data.claims.role == "spectator")
+        .map(|data| data.claims.room_id)
+    });
     
-    // Buscar participante (autenticado ou anônimo)
-    let (username, player_color) = if let Some(user_id) = user_id_from_token {
+    if spectator_room.is_some_and(|id| id != *room_id) {
+        return Err(actix_web::error::ErrorForbidden("Spectator token is for another room"));
+    }
+    
+    // Sala fechada (pelo host ou pelo prazo de expiração) não aceita conexões
+    let is_active = sqlx::query_scalar::<_, bool>("SELECT is_active FROM game_rooms WHERE id = $1")
+        .bind(*room_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load room: {}", e);
+            actix_web::error::ErrorInternalServerError("Database error")
+        })?;
+    match is_active {
+        Some(true) => {}
+        Some(false) => return Err(actix_web::error::ErrorGone("Room is closed")),
+        None => return Err(actix_web::error::ErrorNotFound("Room not found")),
+    }
+    
+    // Token de apresentador: conexão de host mesmo sem linha em room_participants
+    if let Some(token) = query_param("presenter_token") {
+        let valid = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM game_rooms WHERE id = $1 AND presenter_token IS NOT NULL AND presenter_token = $2)"
+        )
+        .bind(*room_id)
+        .bind(&token)
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            log::error!("Failed to check presenter token: {}", e);
+            actix_web::error::ErrorInternalServerError("Database error")
+        })?;
+        if !valid {
+            return Err(actix_web::error::ErrorForbidden("Invalid presenter token"));
+        }
+
+        let ws = GameWebSocket {
+            room_id: *room_id,
+            user_id: 0,
+            username: "Apresentador".to_string(),
+            player_color: String::new(),
+            team_id: None,
+            // Fixo por sala: a reconexão do apresentador substitui a anterior
+            session_id: format!("presenter_{}", *room_id),
+            registry: registry.get_ref().clone(),
+            quiz_clocks: quiz_clocks.get_ref().clone(),
+            role: RoomRole::Host,
+            pool: pool.get_ref().clone(),
+            hb: Instant::now(),
+        };
+
+        return ws::start(ws, &req, stream);
+    }
+    
+    // ?mode=spectator sem token de espectador: só o host ou o criador da sala
+    let wants_spectator = query_param("mode").as_deref() == Some("spectator");
+    if spectator_room.is_none() && wants_spectator {
+        let Some(user_id) = user_id_from_token else {
+            return Err(actix_web::error::ErrorUnauthorized("Spectator token required"));
+        };
+        let is_room_host = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM game_rooms WHERE id = $1 AND created_by = $2)
+                 OR EXISTS(SELECT 1 FROM room_participants WHERE room_id = $1 AND user_id = $2 AND is_host = true)"
+        )
+        .bind(*room_id)
+        .bind(user_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            log::error!("Failed to check room host: {}", e);
+            actix_web::error::ErrorInternalServerError("Database error")
+        })?;
+        if !is_room_host {
+            return Err(actix_web::error::ErrorForbidden("Spectator token required"));
+        }
+    }
+    
+    if spectator_room.is_some() || wants_spectator {
+        let ws = GameWebSocket {
+            room_id: *room_id,
+            user_id: 0,
+            username: "Espectador".to_string(),
+            player_color: String::new(),
+            team_id: None,
+            session_id: format!("spectator_{}", uuid::Uuid::new_v4()),
+            registry: registry.get_ref().clone(),
+            quiz_clocks: quiz_clocks.get_ref().clone(),
+            role: RoomRole::Spectator,
+            pool: pool.get_ref().clone(),
+            hb: Instant::now(),
+        };
+        
+        return ws::start(ws, &req, stream);
+    }
+    
+    // Buscar participante (autenticado ou anônimo). O player_id é sempre room_participants.id,
+    // estável por sala e sem colisão entre usuários e anônimos.
+    let (player_id, username, player_color, team_id) = if let Some(user_id) = user_id_from_token {
         // Usuário autenticado: tentar buscar em room_participants primeiro
-        let from_participants = sqlx::query_as::<_, (String, Option<String>)>(
+        let from_participants = sqlx::query_as::<_, (i32, String, Option<String>, Option<i32>)>(
             r#"
-            SELECT u.name, rp.player_color
+            SELECT rp.id, u.name, rp.player_color, rp.team_id
             FROM room_participants rp
             JOIN users u ON u.id = rp.user_id
             WHERE rp.room_id = $1 AND rp.user_id = $2
@@ -561,11 +1162,10 @@ pub async fn room_websocket(
         .await;
 
         match from_participants {
-            Ok(Some((name, color))) => {
-                log::info!("✅ Jogador encontrado em room_participants: {} (cor: {:?})", name, color);
-                (name, color.unwrap_or_else(|| "#10B981".to_string()))
+            Ok(Some((participant_id, name, color, team_id))) => {
+                (participant_id, name, color.unwrap_or_else(|| "#10B981".to_string()), team_id)
             },
-            _ => {
+            Ok(None) => {
                 // Fallback: buscar nome diretamente em users e inserir em room_participants
                 log::warn!("⚠️ Participante não encontrado em room_participants, usando fallback de users");
                 let user_row = sqlx::query_as::<_, (String,)>(
@@ -577,7 +1177,7 @@ pub async fn room_websocket(
 
                 let name = match user_row {
                     Ok(Some((n,))) => n,
-                    _ => format!("Jogador {}", user_id),
+                    _ => return Err(actix_web::error::ErrorUnauthorized("User not found")),
                 };
 
                 // Atribuir cor e inserir em room_participants para futuras conexões
@@ -585,27 +1185,35 @@ pub async fn room_websocket(
                 // Usar user_id para cor determinística (sem race condition)
                 let color = colors[(user_id as usize) % colors.len()].to_string();
 
-                let _ = sqlx::query(
+                let inserted = sqlx::query_as::<_, (i32, Option<String>, Option<i32>)>(
                     "INSERT INTO room_participants (room_id, user_id, is_host, player_color)
                      VALUES ($1, $2, false, $3)
-                     ON CONFLICT DO NOTHING"
+                     ON CONFLICT (room_id, user_id) DO UPDATE SET player_color = COALESCE(room_participants.player_color, EXCLUDED.player_color)
+                     RETURNING id, player_color, team_id"
                 )
                 .bind(*room_id)
                 .bind(user_id)
                 .bind(&color)
-                .execute(pool.get_ref())
-                .await;
+                .fetch_one(pool.get_ref())
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to register participant: {}", e);
+                    actix_web::error::ErrorInternalServerError("Database error")
+                })?;
 
                 log::info!("✅ Jogador inserido via fallback: {} (cor: {})", name, color);
-                (name, color)
+                (inserted.0, name, inserted.1.unwrap_or(color), inserted.2)
+            },
+            Err(e) => {
+                log::error!("Failed to load participant: {}", e);
+                return Err(actix_web::error::ErrorInternalServerError("Database error"));
             }
         }
     } else if let Some(ref sid) = session_id {
-        // Jogador anônimo
-        log::info!("🔍 Buscando jogador anônimo - room_id: {}, session_id: {}", room_id, sid);
-        let result = sqlx::query_as::<_, (String, Option<String>)>(
+        // Jogador anônimo: a sessão precisa ter entrado nesta sala (join-anonymous)
+        let result = sqlx::query_as::<_, (i32, Option<String>, Option<String>, Option<i32>)>(
             r#"
-            SELECT COALESCE(rp.player_name, 'Anônimo'), rp.player_color
+            SELECT rp.id, rp.player_name, rp.player_color, rp.team_id
             FROM room_participants rp
             WHERE rp.room_id = $1 AND rp.session_id = $2
             "#
@@ -615,10 +1223,21 @@ pub async fn room_websocket(
         .fetch_optional(pool.get_ref())
         .await;
 
-        log::info!("🔍 Resultado da busca: {:?}", result);
         match result {
-            Ok(Some((name, color))) => (name, color.unwrap_or_else(|| "#10B981".to_string())),
-            _ => ("Anônimo".to_string(), "#10B981".to_string()),
+            Ok(Some((participant_id, name, color, team_id))) => (
+                participant_id,
+                name.unwrap_or_else(|| format!("Jogador {}", participant_id)),
+                color.unwrap_or_else(|| "#10B981".to_string()),
+                team_id,
+            ),
+            Ok(None) => {
+                log::warn!("🚫 Sessão {} não pertence à sala {}", sid, room_id);
+                return Err(actix_web::error::ErrorForbidden("Session does not belong to this room"));
+            }
+            Err(e) => {
+                log::error!("Failed to load anonymous participant: {}", e);
+                return Err(actix_web::error::ErrorInternalServerError("Database error"));
+            }
         }
     } else {
         return Err(actix_web::error::ErrorUnauthorized("No authentication provided"));
@@ -626,25 +1245,40 @@ pub async fn room_websocket(
     
     let session_id_str = session_id.clone().unwrap_or_else(|| format!("user_{}", user_id_from_token.unwrap_or(0)));
     
-    // Para anônimos, gerar um player_id único baseado no session_id
-    let player_id = if let Some(uid) = user_id_from_token {
-        uid
-    } else if let Some(ref sid) = session_id {
-        // Gerar ID único do hash do session_id
-        let hash = sid.chars().fold(0i32, |acc, c| acc.wrapping_add(c as i32));
-        hash.abs() % 1000000 + 1 // ID entre 1 e 1000000
+    // Host: participante marcado como host ou criador da sala
+    let is_host = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(
+            SELECT 1 FROM room_participants
+            WHERE room_id = $1 AND is_host = true AND (user_id = $2 OR session_id = $3)
+         ) OR EXISTS(
+            SELECT 1 FROM game_rooms WHERE id = $1 AND created_by = $2
+         )"
+    )
+    .bind(*room_id)
+    .bind(user_id_from_token)
+    .bind(&session_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .unwrap_or(false);
+    
+    let role = if is_host {
+        RoomRole::Host
     } else {
-        0
+        RoomRole::Player
     };
     
     let ws = GameWebSocket {
         room_id: *room_id,
-        user_id: player_id,  // Usar player_id gerado
+        user_id: player_id,  // room_participants.id
         username,
         player_color,
+        team_id,
         session_id: session_id_str,
-        room_manager: room_manager.get_ref().clone(),
+        registry: registry.get_ref().clone(),
+        quiz_clocks: quiz_clocks.get_ref().clone(),
+        role,
         pool: pool.get_ref().clone(),
+        hb: Instant::now(),
     };
     
     ws::start(ws, &req, stream)
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::models::WordSearchConfig;
+use crate::word_search_grid;
+use crate::word_search_words;
+
+// Modo pistas (hide_words): cada palavra tem um conceito em word_search_configs.concepts
+// ({"PALAVRA": "definição"}) e o cliente recebe só os conceitos e a grade pronta.
+
+// Maior texto aceito por conceito
+const MAX_CONCEPT_LENGTH: usize = 500;
+
+// Pista enviada ao cliente no lugar da palavra
+#[derive(Debug, Clone, Serialize)]
+pub struct Clue {
+    pub number: usize,
+    // Letras da palavra, sem espaços
+    pub length: usize,
+    pub concept: String,
+}
+
+// Valida os conceitos do autor e devolve o JSON salvo, com as chaves iguais às palavras
+// (já normalizadas). Com hide_words toda palavra precisa de um conceito.
+pub fn validate_concepts(
+    words: &[String],
+    concepts: Option<&serde_json::Value>,
+    hide_words: bool,
+    strip_accents: bool,
+) -> Result<serde_json::Value, String> {
+    let entries = match concepts {
+        None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+        Some(serde_json::Value::Object(entries)) => entries.clone(),
+        Some(_) => return Err("concepts must be an object mapping each word to its concept".to_string()),
+    };
+
+    let mut validated = serde_json::Map::new();
+    for (word, concept) in entries {
+        let concept = match concept.as_str().map(str::trim) {
+            Some("") => continue,
+            Some(concept) => concept,
+            None => return Err(format!("Concept for \"{}\" must be a string", word)),
+        };
+        if concept.chars().count() > MAX_CONCEPT_LENGTH {
+            return Err(format!("Concept for \"{}\" must have at most {} characters", word, MAX_CONCEPT_LENGTH));
+        }
+        let normalized = word_search_words::normalize(&word, strip_accents);
+        let word = match words.iter().find(|candidate| word_search_words::same_word(candidate, &normalized)) {
+            Some(word) => word.clone(),
+            None => return Err(format!("Concept given for \"{}\", which is not one of the words", word)),
+        };
+        validated.insert(word, json!(concept));
+    }
+
+    if hide_words {
+        let missing = words.iter()
+            .filter(|word| !validated.contains_key(word.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(format!("Clue mode (hide_words) requires a concept for every word; missing: {}", missing.join(", ")));
+        }
+    }
+
+    Ok(serde_json::Value::Object(validated))
+}
+
+// Conceito de uma palavra
+pub fn concept_for(concepts: Option<&serde_json::Value>, word: &str) -> Option<String> {
+    concepts?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| word_search_words::same_word(key, word))
+        .and_then(|(_, concept)| concept.as_str())
+        .map(str::to_string)
+}
+
+// Conceitos das palavras já encontradas (revelados no snapshot da sala)
+pub fn revealed_concepts(concepts: Option<&serde_json::Value>, found: &[String]) -> BTreeMap<String, String> {
+    found.iter()
+        .filter_map(|word| concept_for(concepts, word).map(|concept| (word.clone(), concept)))
+        .collect()
+}
+
+pub fn clues(config: &WordSearchConfig) -> Vec<Clue> {
+    config.words.iter()
+        .enumerate()
+        .map(|(index, word)| Clue {
+            number: index + 1,
+            length: word_search_words::key(word).chars().count(),
+            concept: concept_for(config.concepts.as_ref(), word).unwrap_or_default(),
+        })
+        .collect()
+}
+
+// Configuração para quem vai jogar. No modo pistas as palavras e o mapa de conceitos
+// saem da resposta; o cliente recebe as pistas e a grade montada com a seed da sala
+// (ou uma seed nova no jogo individual).
+pub fn public_config(config: WordSearchConfig, seed: Option<&str>) -> serde_json::Value {
+    if !config.hide_words {
+        return json!(config);
+    }
+
+    let seed = seed.map(str::to_string).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let grid = word_search_grid::letters(&seed, config.grid_size, &config.words, config.allowed_directions.as_ref());
+    let clues = clues(&config);
+
+    let mut value = json!(config);
+    value["words"] = json!([]);
+    value["concepts"] = json!({});
+    value["word_count"] = json!(clues.len());
+    value["clues"] = json!(clues);
+    value["grid"] = json!(grid);
+    value
+}
+
+// Conceitos de um jogo (None se não for caça-palavras ou não tiver conceitos)
+pub async fn load_concepts(pool: &sqlx::PgPool, game_id: i32) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    let concepts = sqlx::query_scalar::<_, Option<serde_json::Value>>(
+        "SELECT concepts FROM word_search_configs WHERE game_id = $1"
+    )
+    .bind(game_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(concepts.flatten())
+}
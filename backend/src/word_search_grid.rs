@@ -0,0 +1,276 @@
+use crate::models::GameRoom;
+use crate::websocket::CellPosition;
+
+// Posição das palavras na grade da sala, reproduzindo o gerador do frontend
+// (WordSearchGame.jsx: Mulberry32 com a game_seed da sala). Qualquer mudança no
+// gerador do frontend precisa ser refletida aqui.
+
+// Direções na mesma ordem e com os mesmos nomes do frontend (dx = linha, dy = coluna)
+pub const DIRECTIONS: [(&str, i32, i32); 8] = [
+    ("right", 0, 1),
+    ("left", 0, -1),
+    ("down", 1, 0),
+    ("up", -1, 0),
+    ("downRight", 1, 1),
+    ("downLeft", 1, -1),
+    ("upRight", -1, 1),
+    ("upLeft", -1, -1),
+];
+
+// Tentativas de posicionamento por palavra
+const MAX_ATTEMPTS: usize = 100;
+
+// Letras usadas para preencher as células vazias
+const FILL_LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Mulberry32 com a aritmética de 32 bits do JavaScript
+struct SeededRandom {
+    state: u32,
+}
+
+impl SeededRandom {
+    fn new(seed: &str) -> Self {
+        let state = seed.encode_utf16().fold(0u32, |state, unit| state.wrapping_add(unit as u32));
+        SeededRandom { state }
+    }
+
+    fn next(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x6D2B_79F5);
+        let state = self.state;
+        let mut t = (state ^ (state >> 15)).wrapping_mul(1 | state);
+        t = t.wrapping_add((t ^ (t >> 7)).wrapping_mul(61 | t)) ^ t;
+        (t ^ (t >> 14)) as f64 / 4_294_967_296.0
+    }
+
+    // Math.floor(random() * len)
+    fn index(&mut self, len: usize) -> usize {
+        (self.next() * len as f64).floor() as usize
+    }
+}
+
+// Palavra posicionada na grade
+#[derive(Debug, Clone)]
+pub struct PlacedWord {
+    pub word: String,
+    pub direction: &'static str,
+    pub cells: Vec<CellPosition>,
+}
+
+impl PlacedWord {
+    // Células marcadas pelo jogador, do início ao fim da palavra ou ao contrário
+    pub fn matches(&self, cells: &[CellPosition]) -> bool {
+        let same = |a: &CellPosition, b: &CellPosition| a.row == b.row && a.col == b.col;
+        cells.len() == self.cells.len()
+            && (cells.iter().zip(&self.cells).all(|(a, b)| same(a, b))
+                || cells.iter().zip(self.cells.iter().rev()).all(|(a, b)| same(a, b)))
+    }
+}
+
+// Seed da grade da sala, como no frontend: a game_seed ou, sem ela, o código da sala
+pub fn room_seed(room: &GameRoom) -> &str {
+    room.game_seed.as_deref().filter(|seed| !seed.is_empty()).unwrap_or(&room.room_code)
+}
+
+// Direções permitidas (NULL = todas). Nomes desconhecidos são ignorados.
+pub fn directions(allowed_directions: Option<&serde_json::Value>) -> Vec<(&'static str, i32, i32)> {
+    match allowed_directions.and_then(|value| value.as_array()) {
+        Some(keys) => keys.iter()
+            .filter_map(|key| key.as_str())
+            .filter_map(|key| DIRECTIONS.iter().find(|(name, _, _)| *name == key))
+            .copied()
+            .collect(),
+        None => DIRECTIONS.to_vec(),
+    }
+}
+
+// Posiciona as palavras como o frontend. Palavras que não couberam ficam de fora.
+pub fn place_words(
+    seed: &str,
+    grid_size: i32,
+    words: &[String],
+    allowed_directions: Option<&serde_json::Value>,
+) -> Vec<PlacedWord> {
+    generate(seed, grid_size, words, allowed_directions).1
+}
+
+// Letras da grade (uma String por linha), com as células vazias preenchidas como no frontend
+pub fn letters(
+    seed: &str,
+    grid_size: i32,
+    words: &[String],
+    allowed_directions: Option<&serde_json::Value>,
+) -> Vec<String> {
+    let (grid, _, mut random) = generate(seed, grid_size, words, allowed_directions);
+    grid.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|letter| letter.unwrap_or_else(|| FILL_LETTERS[random.index(FILL_LETTERS.len())] as char))
+                .collect()
+        })
+        .collect()
+}
+
+fn generate(
+    seed: &str,
+    grid_size: i32,
+    words: &[String],
+    allowed_directions: Option<&serde_json::Value>,
+) -> (Vec<Vec<Option<char>>>, Vec<PlacedWord>, SeededRandom) {
+    let size = grid_size.max(1);
+    let directions = directions(allowed_directions);
+    let mut random = SeededRandom::new(seed);
+    let mut grid = vec![vec![None::<char>; size as usize]; size as usize];
+    if directions.is_empty() {
+        return (grid, Vec::new(), random);
+    }
+
+    let mut placed = Vec::new();
+
+    for word in words {
+        let letters = word.to_uppercase().chars().collect::<Vec<_>>();
+        if letters.is_empty() {
+            continue;
+        }
+
+        for _ in 0..MAX_ATTEMPTS {
+            let (name, dx, dy) = directions[random.index(directions.len())];
+            let start_row = random.index(size as usize) as i32;
+            let start_col = random.index(size as usize) as i32;
+
+            let cells = (0..letters.len() as i32)
+                .map(|i| CellPosition { row: start_row + dx * i, col: start_col + dy * i })
+                .collect::<Vec<_>>();
+            let end = &cells[cells.len() - 1];
+            if end.row < 0 || end.row >= size || end.col < 0 || end.col >= size {
+                continue;
+            }
+            let fits = cells.iter().zip(&letters).all(|(cell, letter)| {
+                grid[cell.row as usize][cell.col as usize].is_none_or(|current| current == *letter)
+            });
+            if !fits {
+                continue;
+            }
+
+            for (cell, letter) in cells.iter().zip(&letters) {
+                grid[cell.row as usize][cell.col as usize] = Some(*letter);
+            }
+            placed.push(PlacedWord { word: letters.iter().collect(), direction: name, cells });
+            break;
+        }
+    }
+
+    (grid, placed, random)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saídas capturadas do gerador de WordSearchGame.jsx (seededRandom + generateGrid)
+    // rodando no Node com as mesmas seeds, tamanhos, palavras e direções.
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    // (palavra, direção, linha inicial, coluna inicial)
+    fn assert_placed(placed: &[PlacedWord], expected: &[(&str, &str, i32, i32)]) {
+        assert_eq!(placed.len(), expected.len());
+        for (placed, (word, direction, row, col)) in placed.iter().zip(expected) {
+            let (_, dx, dy) = DIRECTIONS.iter().find(|(name, _, _)| name == direction).unwrap();
+            let cells = (0..word.chars().count() as i32)
+                .map(|i| (row + dx * i, col + dy * i))
+                .collect::<Vec<_>>();
+            assert_eq!(placed.word, *word);
+            assert_eq!(placed.direction, *direction);
+            assert_eq!(placed.cells.iter().map(|cell| (cell.row, cell.col)).collect::<Vec<_>>(), cells);
+        }
+    }
+
+    #[test]
+    fn random_sequence_matches_frontend() {
+        let mut random = SeededRandom::new("ABC123");
+        assert_eq!(random.next(), 0.9318338285665959);
+        assert_eq!(random.next(), 0.3539558534976095);
+        assert_eq!(random.next(), 0.3978095834609121);
+    }
+
+    #[test]
+    fn room_code_seed_all_directions() {
+        let words = strings(&["GATO", "CACHORRO", "PEIXE"]);
+
+        assert_eq!(letters("ABC123", 10, &words, None), strings(&[
+            "OMEVGYYHWE",
+            "KTNOZIUKAD",
+            "IMAVMRILJY",
+            "WEYGYZUXEF",
+            "GAORROHCAC",
+            "IAIPOGEKEK",
+            "EVGHFNUFUS",
+            "MEXIEPYFMM",
+            "KDHCYTGHSZ",
+            "CIKKVUQCMC",
+        ]));
+        assert_placed(&place_words("ABC123", 10, &words, None), &[
+            ("GATO", "upLeft", 3, 3),
+            ("CACHORRO", "left", 4, 9),
+            ("PEIXE", "left", 7, 5),
+        ]);
+    }
+
+    #[test]
+    fn game_seed_with_allowed_directions() {
+        let seed = "5f0c1e2a-9b7d-4c3e-8a61-2d4f7b9e0c13";
+        let words = strings(&["SOL", "LUA", "ESTRELA"]);
+        let allowed = serde_json::json!(["right", "down"]);
+
+        assert_eq!(letters(seed, 8, &words, Some(&allowed)), strings(&[
+            "VQRRZWJZ",
+            "NHZPWUDO",
+            "WJOHNAHO",
+            "LLCSEGVL",
+            "UJXZZCWG",
+            "AXJTRSOL",
+            "DESTRELA",
+            "MZDNWZXS",
+        ]));
+        assert_placed(&place_words(seed, 8, &words, Some(&allowed)), &[
+            ("SOL", "right", 5, 5),
+            ("LUA", "down", 3, 0),
+            ("ESTRELA", "right", 6, 1),
+        ]);
+    }
+
+    // Seed com acentos (soma das unidades UTF-16, como charCodeAt) e palavras que se cruzam
+    #[test]
+    fn accented_seed_and_overlapping_words() {
+        let words = strings(&["ARARA", "ARA", "OSSO"]);
+        let allowed = serde_json::json!(["upLeft", "downLeft", "left"]);
+
+        assert_eq!(letters("ação", 6, &words, Some(&allowed)), strings(&[
+            "OCOSXX",
+            "IAAFPJ",
+            "DORRMF",
+            "LFSAAG",
+            "CYASRK",
+            "DSMXOA",
+        ]));
+        assert_placed(&place_words("ação", 6, &words, Some(&allowed)), &[
+            ("ARARA", "upLeft", 5, 5),
+            ("ARA", "upLeft", 3, 4),
+            ("OSSO", "upLeft", 5, 4),
+        ]);
+    }
+
+    #[test]
+    fn placed_word_matches_cells_in_either_direction() {
+        let placed = place_words("ABC123", 10, &strings(&["GATO"]), None).remove(0);
+        let cells = |coords: &[(i32, i32)]| coords.iter().map(|&(row, col)| CellPosition { row, col }).collect::<Vec<_>>();
+
+        assert!(placed.matches(&cells(&[(3, 3), (2, 2), (1, 1), (0, 0)])));
+        assert!(placed.matches(&cells(&[(0, 0), (1, 1), (2, 2), (3, 3)])));
+        assert!(!placed.matches(&cells(&[(3, 3), (2, 2), (1, 1)])));
+        assert!(!placed.matches(&cells(&[(0, 0), (0, 1), (0, 2), (0, 3)])));
+        assert!(!placed.matches(&[]));
+    }
+}
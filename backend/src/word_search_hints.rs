@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::GameRoom;
+use crate::websocket::CellPosition;
+use crate::word_search_grid;
+use crate::word_search_mode::{self, WordSearchMode};
+use crate::word_search_scoring::ScoringConfig;
+use crate::word_search_words;
+
+// Raio da região revelada em volta da célula do meio da palavra (3x3)
+const REGION_RADIUS: i32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintKind {
+    // Primeira célula da palavra
+    FirstLetter,
+    // Só a direção da palavra
+    Direction,
+    // Região da grade onde a palavra passa
+    Region,
+}
+
+impl HintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HintKind::FirstLetter => "first_letter",
+            HintKind::Direction => "direction",
+            HintKind::Region => "region",
+        }
+    }
+}
+
+// Dica calculada pelo servidor a partir da grade da sala
+#[derive(Debug, Clone)]
+pub struct Hint {
+    // Palavra da dica; no modo pistas (hide_words), o número da pista ("#3")
+    pub word: String,
+    pub kind: HintKind,
+    pub cells: Vec<CellPosition>,
+    pub direction: Option<String>,
+    // Pontos descontados (0 se a mesma dica já tinha sido dada)
+    pub penalty: i32,
+}
+
+#[derive(Debug)]
+pub enum HintError {
+    // Palavra inexistente, já encontrada ou sem grade para calcular a dica
+    Unavailable(String),
+    Database(sqlx::Error),
+}
+
+impl HintError {
+    pub fn message(&self) -> String {
+        match self {
+            HintError::Unavailable(message) => message.clone(),
+            HintError::Database(e) => format!("Database error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for HintError {
+    fn from(e: sqlx::Error) -> Self {
+        HintError::Database(e)
+    }
+}
+
+// Jogador que pediu a dica
+pub struct HintPlayer<'a> {
+    // room_participants.id
+    pub participant_id: i32,
+    // Chave do jogador em room_player_scores
+    pub session_id: &'a str,
+    pub player_name: &'a str,
+    pub player_color: &'a str,
+}
+
+// Calcula a dica para a palavra pedida (ou a primeira que falta no tabuleiro do jogador),
+// desconta a penalidade da pontuação e registra o uso em room_hints
+pub async fn request_hint(
+    pool: &sqlx::PgPool,
+    room: &GameRoom,
+    player: &HintPlayer<'_>,
+    word: Option<&str>,
+    kind: HintKind,
+) -> Result<Hint, HintError> {
+    let seed = word_search_grid::room_seed(room);
+
+    let config = sqlx::query_as::<_, (i32, Vec<String>, Option<serde_json::Value>, Option<serde_json::Value>, bool)>(
+        "SELECT grid_size, words, allowed_directions, scoring, hide_words FROM word_search_configs WHERE game_id = $1"
+    )
+    .bind(room.game_id)
+    .fetch_optional(pool)
+    .await?;
+    let (grid_size, words, allowed_directions, scoring, hide_words) = config
+        .ok_or_else(|| HintError::Unavailable("Este jogo não é um caça-palavras".to_string()))?;
+    let penalties = ScoringConfig::from_json(scoring.as_ref()).hints;
+
+    let mode = WordSearchMode::parse(&room.word_search_mode).unwrap_or_default();
+    let found = word_search_mode::found_on_board(pool, mode, room.id, player.session_id).await?;
+    let placed = word_search_grid::place_words(seed, grid_size, &words, allowed_directions.as_ref());
+    let is_found = |value: &str| found.iter().any(|found| word_search_words::same_word(found, value));
+
+    // Número da pista da palavra (a ordem das palavras na configuração, como em clues())
+    let clue_number = |value: &str| words.iter()
+        .position(|word| word_search_words::same_word(word, value))
+        .map(|index| index + 1);
+
+    // No modo pistas o jogador só conhece o número da pista ("#3")
+    let word = match word {
+        Some(word) if hide_words => {
+            let number = word.strip_prefix('#').and_then(|number| number.trim().parse::<usize>().ok());
+            Some(number.and_then(|number| number.checked_sub(1)).and_then(|index| words.get(index)).map(String::as_str)
+                .ok_or_else(|| HintError::Unavailable(format!("\"{}\" não é uma pista deste jogo", word)))?)
+        }
+        word => word,
+    };
+
+    let target = match word {
+        Some(word) => {
+            let target = placed.iter()
+                .find(|placed| word_search_words::same_word(&placed.word, word))
+                .ok_or_else(|| HintError::Unavailable(format!("\"{}\" não é uma palavra deste jogo", word_search_words::key(word))))?;
+            if is_found(&target.word) {
+                return Err(HintError::Unavailable("Esta palavra já foi encontrada".to_string()));
+            }
+            target
+        }
+        None => placed.iter()
+            .find(|placed| !is_found(&placed.word))
+            .ok_or_else(|| HintError::Unavailable("Não há palavras para encontrar".to_string()))?,
+    };
+
+    let (cells, direction) = match kind {
+        HintKind::FirstLetter => (target.cells[..1].to_vec(), None),
+        HintKind::Direction => (Vec::new(), Some(target.direction.to_string())),
+        HintKind::Region => {
+            let middle = &target.cells[target.cells.len() / 2];
+            let cells = (middle.row - REGION_RADIUS..=middle.row + REGION_RADIUS)
+                .flat_map(|row| (middle.col - REGION_RADIUS..=middle.col + REGION_RADIUS).map(move |col| CellPosition { row, col }))
+                .filter(|cell| (0..grid_size).contains(&cell.row) && (0..grid_size).contains(&cell.col))
+                .collect();
+            (cells, None)
+        }
+    };
+
+    let penalty = charge_hint(pool, room.id, player, &target.word, kind, penalties.penalty(kind)).await?;
+
+    let word = match clue_number(&target.word) {
+        Some(number) if hide_words => format!("#{}", number),
+        _ => target.word.clone(),
+    };
+
+    Ok(Hint { word, kind, cells, direction, penalty })
+}
+
+// Registra a dica e desconta a penalidade. A mesma dica para a mesma palavra não é cobrada de novo.
+async fn charge_hint(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    player: &HintPlayer<'_>,
+    word: &str,
+    kind: HintKind,
+    penalty: i32,
+) -> Result<i32, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let logged = sqlx::query(
+        "INSERT INTO room_hints (room_id, session_id, player_name, word, kind, penalty)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (room_id, session_id, word, kind) DO NOTHING"
+    )
+    .bind(room_id)
+    .bind(player.session_id)
+    .bind(player.player_name)
+    .bind(word)
+    .bind(kind.as_str())
+    .bind(penalty)
+    .execute(&mut *tx)
+    .await?;
+
+    if logged.rows_affected() == 0 {
+        tx.commit().await?;
+        return Ok(0);
+    }
+
+    sqlx::query(
+        "INSERT INTO room_player_scores
+         (room_id, session_id, player_name, player_color, words_found, total_score, team_id, hints_used)
+         VALUES ($1, $2, $3, $4, 0, -$5, (SELECT team_id FROM room_participants WHERE id = $6), 1)
+         ON CONFLICT (room_id, session_id)
+         DO UPDATE SET
+            total_score = room_player_scores.total_score - $5,
+            hints_used = room_player_scores.hints_used + 1,
+            last_updated = NOW()"
+    )
+    .bind(room_id)
+    .bind(player.session_id)
+    .bind(player.player_name)
+    .bind(player.player_color)
+    .bind(penalty)
+    .bind(player.participant_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE game_rooms SET total_score = COALESCE(total_score, 0) - $1 WHERE id = $2")
+        .bind(penalty)
+        .bind(room_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(penalty)
+}
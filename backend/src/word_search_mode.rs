@@ -0,0 +1,239 @@
+use crate::models::{RoomFoundWord, RoomPlayerScore};
+
+// Chave do tabuleiro compartilhado em room_found_words.board_key
+const SHARED_BOARD: &str = "";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WordSearchMode {
+    // Tabuleiro único: cada palavra é de quem a encontrar primeiro (comportamento original)
+    #[default]
+    Cooperative,
+    // Cada jogador tem o seu tabuleiro; vence quem encontrar mais palavras em menos tempo
+    Race,
+    // Cada jogador tem o seu tabuleiro, mas só o primeiro a encontrar a palavra pontua
+    Steal,
+}
+
+impl WordSearchMode {
+    pub const ALL: [&'static str; 3] = ["cooperative", "race", "steal"];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cooperative" => Some(WordSearchMode::Cooperative),
+            "race" => Some(WordSearchMode::Race),
+            "steal" => Some(WordSearchMode::Steal),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WordSearchMode::Cooperative => "cooperative",
+            WordSearchMode::Race => "race",
+            WordSearchMode::Steal => "steal",
+        }
+    }
+
+    // Tabuleiros separados: um jogador não vê as células encontradas pelos outros
+    pub fn is_competitive(&self) -> bool {
+        *self != WordSearchMode::Cooperative
+    }
+
+    // Ordem do placar: na corrida, mais palavras e, no empate, quem chegou antes
+    fn score_order(&self) -> &'static str {
+        match self {
+            WordSearchMode::Race => "words_found DESC, last_found_seconds ASC NULLS LAST, total_score DESC",
+            _ => "total_score DESC",
+        }
+    }
+}
+
+pub async fn load_mode(pool: &sqlx::PgPool, room_id: i32) -> Result<WordSearchMode, sqlx::Error> {
+    let mode = sqlx::query_scalar::<_, String>("SELECT word_search_mode FROM game_rooms WHERE id = $1")
+        .bind(room_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(WordSearchMode::parse(&mode).unwrap_or_default())
+}
+
+pub async fn load_scores(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    mode: WordSearchMode,
+) -> Result<Vec<RoomPlayerScore>, sqlx::Error> {
+    sqlx::query_as::<_, RoomPlayerScore>(&format!(
+        "SELECT * FROM room_player_scores WHERE room_id = $1 ORDER BY {}",
+        mode.score_order()
+    ))
+    .bind(room_id)
+    .fetch_all(pool)
+    .await
+}
+
+// Palavras visíveis para quem está olhando: no modo cooperativo o tabuleiro inteiro,
+// nos competitivos só o tabuleiro do próprio jogador (viewer = chave em room_player_scores)
+pub async fn load_found_words(
+    pool: &sqlx::PgPool,
+    room_id: i32,
+    mode: WordSearchMode,
+    viewer: Option<&str>,
+) -> Result<Vec<RoomFoundWord>, sqlx::Error> {
+    if !mode.is_competitive() {
+        return sqlx::query_as::<_, RoomFoundWord>(
+            "SELECT * FROM room_found_words WHERE room_id = $1 ORDER BY found_at ASC"
+        )
+        .bind(room_id)
+        .fetch_all(pool)
+        .await;
+    }
+
+    let viewer = match viewer {
+        Some(viewer) => viewer,
+        None => return Ok(Vec::new()),
+    };
+    sqlx::query_as::<_, RoomFoundWord>(
+        "SELECT * FROM room_found_words WHERE room_id = $1 AND found_by_session_id = $2 ORDER BY found_at ASC"
+    )
+    .bind(room_id)
+    .bind(viewer)
+    .fetch_all(pool)
+    .await
+}
+
+// Todas as palavras do tabuleiro encontradas (o compartilhado ou o do jogador)
+pub async fn board_complete(
+    pool: &sqlx::PgPool,
+    mode: WordSearchMode,
+    room_id: i32,
+    session_id: &str,
+    word_count: usize,
+) -> Result<bool, sqlx::Error> {
+    let found = found_on_board(pool, mode, room_id, session_id).await?;
+    Ok(found.len() >= word_count)
+}
+
+// Palavras já encontradas no tabuleiro do jogador (o compartilhado no modo cooperativo)
+pub async fn found_on_board(
+    pool: &sqlx::PgPool,
+    mode: WordSearchMode,
+    room_id: i32,
+    session_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    if mode.is_competitive() {
+        sqlx::query_scalar::<_, String>(
+            "SELECT word FROM room_found_words WHERE room_id = $1 AND found_by_session_id = $2"
+        )
+        .bind(room_id)
+        .bind(session_id)
+        .fetch_all(pool)
+        .await
+    } else {
+        sqlx::query_scalar::<_, String>(
+            "SELECT word FROM room_found_words WHERE room_id = $1 AND board_key = $2"
+        )
+        .bind(room_id)
+        .bind(SHARED_BOARD)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+// Palavra encontrada por um jogador
+pub struct Find<'a> {
+    pub room_id: i32,
+    // room_participants.id (equipe creditada)
+    pub participant_id: i32,
+    // Chave do jogador em room_player_scores
+    pub session_id: &'a str,
+    pub player_name: &'a str,
+    pub player_color: &'a str,
+    pub word: &'a str,
+    pub cells: &'a serde_json::Value,
+    // Tempo de jogo do servidor
+    pub elapsed_seconds: Option<i32>,
+    pub points: i32,
+}
+
+pub enum FindOutcome {
+    // Registrada no tabuleiro do jogador com os pontos ganhos
+    // (0 no modo steal quando outro jogador encontrou antes)
+    Found { team_id: Option<i32>, points: i32 },
+    // Já estava no tabuleiro; by = quem encontrou (None = o próprio jogador)
+    AlreadyFound { by: Option<String> },
+}
+
+// Registra a palavra conforme o modo da sala. A unicidade (room_id, word, board_key)
+// decide quem chegou primeiro.
+pub async fn record_find(
+    pool: &sqlx::PgPool,
+    mode: WordSearchMode,
+    find: &Find<'_>,
+) -> Result<FindOutcome, sqlx::Error> {
+    match mode {
+        WordSearchMode::Cooperative => match insert_find(pool, find, SHARED_BOARD, find.points).await? {
+            Some(team_id) => Ok(FindOutcome::Found { team_id, points: find.points }),
+            None => {
+                let by = claim_owner(pool, find).await?.map(|(_, name)| name);
+                Ok(FindOutcome::AlreadyFound { by: Some(by.unwrap_or_else(|| "outro jogador".to_string())) })
+            }
+        },
+        WordSearchMode::Race => match insert_find(pool, find, find.session_id, find.points).await? {
+            Some(team_id) => Ok(FindOutcome::Found { team_id, points: find.points }),
+            None => Ok(FindOutcome::AlreadyFound { by: None }),
+        },
+        WordSearchMode::Steal => {
+            if let Some(team_id) = insert_find(pool, find, SHARED_BOARD, find.points).await? {
+                return Ok(FindOutcome::Found { team_id, points: find.points });
+            }
+            // Outro jogador levou os pontos: a palavra vai só para o tabuleiro do jogador
+            match claim_owner(pool, find).await? {
+                Some((owner, _)) if owner == find.session_id => Ok(FindOutcome::AlreadyFound { by: None }),
+                _ => match insert_find(pool, find, find.session_id, 0).await? {
+                    Some(team_id) => Ok(FindOutcome::Found { team_id, points: 0 }),
+                    None => Ok(FindOutcome::AlreadyFound { by: None }),
+                },
+            }
+        }
+    }
+}
+
+// Retorna a equipe creditada, ou None se a palavra já estava nesse tabuleiro
+async fn insert_find(
+    pool: &sqlx::PgPool,
+    find: &Find<'_>,
+    board_key: &str,
+    points: i32,
+) -> Result<Option<Option<i32>>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<i32>>(
+        "INSERT INTO room_found_words
+         (room_id, word, found_by_session_id, found_by_name, player_color, cells, team_id, board_key, points, elapsed_seconds)
+         VALUES ($1, $2, $3, $4, $5, $6, (SELECT team_id FROM room_participants WHERE id = $7), $8, $9, $10)
+         ON CONFLICT (room_id, word, board_key) DO NOTHING
+         RETURNING team_id"
+    )
+    .bind(find.room_id)
+    .bind(find.word)
+    .bind(find.session_id)
+    .bind(find.player_name)
+    .bind(find.player_color)
+    .bind(find.cells)
+    .bind(find.participant_id)
+    .bind(board_key)
+    .bind(points)
+    .bind(find.elapsed_seconds)
+    .fetch_optional(pool)
+    .await
+}
+
+// Quem encontrou a palavra primeiro (sessão, nome)
+async fn claim_owner(pool: &sqlx::PgPool, find: &Find<'_>) -> Result<Option<(String, String)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, String)>(
+        "SELECT found_by_session_id, found_by_name FROM room_found_words
+         WHERE room_id = $1 AND word = $2 AND board_key = $3"
+    )
+    .bind(find.room_id)
+    .bind(find.word)
+    .bind(SHARED_BOARD)
+    .fetch_optional(pool)
+    .await
+}
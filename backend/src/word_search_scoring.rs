@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+
+use crate::word_search_hints::HintKind;
+
+// Pontuação do caça-palavras, salva em word_search_configs.scoring (NULL = curva padrão).
+// Exemplo: {"curve": {"type": "linear", "max_points": 100, "min_points": 10, "decay_seconds": 600},
+//           "bonuses": {"per_extra_letter": 5, "hidden_words": 20, "completion": 200},
+//           "hints": {"first_letter": 30, "direction": 10, "region": 20}}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoringConfig {
+    #[serde(default)]
+    pub curve: ScoringCurve,
+    #[serde(default)]
+    pub bonuses: ScoringBonuses,
+    #[serde(default)]
+    pub hints: HintPenalties,
+}
+
+// Pontos de uma palavra em função do tempo de jogo (segundos)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScoringCurve {
+    // Mesma pontuação a qualquer momento
+    Flat { points: i32 },
+    // Cai linearmente de max_points até min_points em decay_seconds
+    Linear { max_points: i32, min_points: i32, decay_seconds: i32 },
+    // Faixas de tempo: a primeira vale points até until_seconds; as seguintes caem linearmente
+    // dos pontos da faixa anterior até os seus. Depois da última faixa vale after_points.
+    Stepped { steps: Vec<ScoreStep>, after_points: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreStep {
+    pub until_seconds: i32,
+    pub points: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoringBonuses {
+    // Pontos por letra acima de min_length
+    #[serde(default)]
+    pub per_extra_letter: i32,
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    // Pontos extras por palavra quando a lista de palavras fica oculta (hide_words)
+    #[serde(default)]
+    pub hidden_words: i32,
+    // Pontos extras para quem completa o tabuleiro
+    #[serde(default)]
+    pub completion: i32,
+}
+
+fn default_min_length() -> usize {
+    5
+}
+
+impl Default for ScoringBonuses {
+    fn default() -> Self {
+        ScoringBonuses { per_extra_letter: 0, min_length: default_min_length(), hidden_words: 0, completion: 0 }
+    }
+}
+
+// Pontos descontados por dica pedida na sala
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HintPenalties {
+    #[serde(default = "default_first_letter_penalty")]
+    pub first_letter: i32,
+    #[serde(default = "default_direction_penalty")]
+    pub direction: i32,
+    #[serde(default = "default_region_penalty")]
+    pub region: i32,
+}
+
+fn default_first_letter_penalty() -> i32 {
+    30
+}
+
+fn default_direction_penalty() -> i32 {
+    10
+}
+
+fn default_region_penalty() -> i32 {
+    20
+}
+
+impl Default for HintPenalties {
+    fn default() -> Self {
+        HintPenalties {
+            first_letter: default_first_letter_penalty(),
+            direction: default_direction_penalty(),
+            region: default_region_penalty(),
+        }
+    }
+}
+
+impl HintPenalties {
+    pub fn penalty(&self, kind: HintKind) -> i32 {
+        match kind {
+            HintKind::FirstLetter => self.first_letter,
+            HintKind::Direction => self.direction,
+            HintKind::Region => self.region,
+        }
+    }
+}
+
+// Maior pontuação aceita por palavra/bônus
+const MAX_POINTS: i32 = 10_000;
+
+impl Default for ScoringCurve {
+    // Curva original: 100 até 1min, 50 em 5min, 20 em 10min e 10 depois disso
+    fn default() -> Self {
+        ScoringCurve::Stepped {
+            steps: vec![
+                ScoreStep { until_seconds: 60, points: 100 },
+                ScoreStep { until_seconds: 300, points: 50 },
+                ScoreStep { until_seconds: 600, points: 20 },
+            ],
+            after_points: 10,
+        }
+    }
+}
+
+impl ScoringCurve {
+    // Sem tempo conhecido vale a pontuação máxima
+    pub fn points(&self, elapsed_seconds: Option<i32>) -> i32 {
+        match self {
+            ScoringCurve::Flat { points } => *points,
+            ScoringCurve::Linear { max_points, min_points, decay_seconds } => {
+                let elapsed = match elapsed_seconds {
+                    Some(elapsed) => elapsed.max(0),
+                    None => return *max_points,
+                };
+                if elapsed >= *decay_seconds {
+                    return *min_points;
+                }
+                let reduction = (elapsed as f32 / *decay_seconds as f32) * (*max_points - *min_points) as f32;
+                (*max_points as f32 - reduction).floor() as i32
+            }
+            ScoringCurve::Stepped { steps, after_points } => {
+                let first = match steps.first() {
+                    Some(first) => first,
+                    None => return *after_points,
+                };
+                let elapsed = match elapsed_seconds {
+                    Some(elapsed) => elapsed.max(0),
+                    None => return first.points,
+                };
+                if elapsed <= first.until_seconds {
+                    return first.points;
+                }
+                for pair in steps.windows(2) {
+                    let (previous, step) = (&pair[0], &pair[1]);
+                    if elapsed <= step.until_seconds {
+                        let span = (step.until_seconds - previous.until_seconds) as f32;
+                        let reduction = ((elapsed - previous.until_seconds) as f32 / span) * (previous.points - step.points) as f32;
+                        return (previous.points as f32 - reduction).floor() as i32;
+                    }
+                }
+                *after_points
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let in_range = |points: i32| (0..=MAX_POINTS).contains(&points);
+        match self {
+            ScoringCurve::Flat { points } if !in_range(*points) => {
+                Err(format!("Flat points must be between 0 and {}", MAX_POINTS))
+            }
+            ScoringCurve::Linear { max_points, min_points, decay_seconds } => {
+                if !in_range(*max_points) || !in_range(*min_points) || min_points > max_points {
+                    return Err(format!("Linear curve needs 0 <= min_points <= max_points <= {}", MAX_POINTS));
+                }
+                if *decay_seconds <= 0 {
+                    return Err("Linear curve decay_seconds must be positive".to_string());
+                }
+                Ok(())
+            }
+            ScoringCurve::Stepped { steps, after_points } => {
+                if steps.is_empty() || steps.len() > 10 {
+                    return Err("Stepped curve must have between 1 and 10 steps".to_string());
+                }
+                if !in_range(*after_points) || steps.iter().any(|step| !in_range(step.points)) {
+                    return Err(format!("Stepped curve points must be between 0 and {}", MAX_POINTS));
+                }
+                let mut previous = 0;
+                for step in steps {
+                    if step.until_seconds <= previous {
+                        return Err("Stepped curve until_seconds must be positive and increasing".to_string());
+                    }
+                    previous = step.until_seconds;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ScoringBonuses {
+    // Bônus por palavra (tamanho e lista oculta)
+    pub fn word_bonus(&self, word: &str, hidden: bool) -> i32 {
+        let letters = word.chars().filter(|c| c.is_alphanumeric()).count();
+        let length_bonus = letters.saturating_sub(self.min_length) as i32 * self.per_extra_letter;
+        let hidden_bonus = if hidden { self.hidden_words } else { 0 };
+        length_bonus + hidden_bonus
+    }
+}
+
+impl ScoringConfig {
+    // Configuração salva (None ou JSON inválido = padrão)
+    pub fn from_json(value: Option<&serde_json::Value>) -> Self {
+        value
+            .filter(|value| !value.is_null())
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.curve.validate()?;
+        let bonuses = &self.bonuses;
+        if [bonuses.per_extra_letter, bonuses.hidden_words, bonuses.completion]
+            .iter()
+            .any(|points| !(0..=MAX_POINTS).contains(points))
+        {
+            return Err(format!("Scoring bonuses must be between 0 and {}", MAX_POINTS));
+        }
+        let hints = &self.hints;
+        if [hints.first_letter, hints.direction, hints.region]
+            .iter()
+            .any(|points| !(0..=MAX_POINTS).contains(points))
+        {
+            return Err(format!("Hint penalties must be between 0 and {}", MAX_POINTS));
+        }
+        Ok(())
+    }
+
+    // Pontos de uma palavra encontrada
+    pub fn word_points(&self, word: &str, elapsed_seconds: Option<i32>, hidden: bool) -> i32 {
+        self.curve.points(elapsed_seconds) + self.bonuses.word_bonus(word, hidden)
+    }
+
+    // Resultado do jogo individual: curva no tempo total e, se completou, os bônus de
+    // todas as palavras mais o de tabuleiro completo
+    pub fn result_points(&self, words: &[String], time_seconds: i32, completed: bool, hidden: bool) -> i32 {
+        let mut points = self.curve.points(Some(time_seconds));
+        if completed {
+            points += words.iter().map(|word| self.bonuses.word_bonus(word, hidden)).sum::<i32>();
+            points += self.bonuses.completion;
+        }
+        points
+    }
+}
+
+// Palavras, lista oculta e pontuação do caça-palavras de um jogo
+pub async fn load_game_scoring(
+    pool: &sqlx::PgPool,
+    game_id: i32,
+) -> Result<Option<(Vec<String>, bool, ScoringConfig)>, sqlx::Error> {
+    let row = sqlx::query_as::<_, (Vec<String>, bool, Option<serde_json::Value>)>(
+        "SELECT words, hide_words, scoring FROM word_search_configs WHERE game_id = $1"
+    )
+    .bind(game_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|(words, hidden, scoring)| (words, hidden, ScoringConfig::from_json(scoring.as_ref()))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Curva fixa que existia antes da pontuação configurável (websocket.rs e game_results.rs)
+    fn original_curve(found_at: Option<i32>) -> i32 {
+        match found_at {
+            None => 100,
+            Some(found_at) if found_at <= 60 => 100,
+            Some(found_at) if found_at <= 300 => {
+                let reduction = ((found_at - 60) as f32 / 240.0) * 50.0;
+                (100.0 - reduction).floor() as i32
+            }
+            Some(found_at) if found_at <= 600 => {
+                let reduction = ((found_at - 300) as f32 / 300.0) * 30.0;
+                (50.0 - reduction).floor() as i32
+            }
+            Some(_) => 10,
+        }
+    }
+
+    #[test]
+    fn default_curve_matches_original() {
+        let curve = ScoringCurve::default();
+        let expected = [(0, 100), (60, 100), (61, 99), (180, 75), (300, 50), (450, 35), (600, 20), (601, 10)];
+        for (seconds, points) in expected {
+            assert_eq!(curve.points(Some(seconds)), points, "{}s", seconds);
+        }
+        assert_eq!(curve.points(None), 100);
+
+        for seconds in 0..=700 {
+            assert_eq!(curve.points(Some(seconds)), original_curve(Some(seconds)), "{}s", seconds);
+        }
+    }
+
+    #[test]
+    fn linear_curve() {
+        let curve = ScoringCurve::Linear { max_points: 100, min_points: 10, decay_seconds: 600 };
+        assert_eq!(curve.points(None), 100);
+        assert_eq!(curve.points(Some(-5)), 100);
+        assert_eq!(curve.points(Some(0)), 100);
+        assert_eq!(curve.points(Some(300)), 55);
+        assert_eq!(curve.points(Some(599)), 10);
+        assert_eq!(curve.points(Some(600)), 10);
+        assert_eq!(curve.points(Some(900)), 10);
+    }
+
+    #[test]
+    fn validate_rejects_invalid_configs() {
+        assert!(ScoringConfig::default().validate().is_ok());
+
+        let with_curve = |curve: ScoringCurve| ScoringConfig { curve, ..ScoringConfig::default() };
+        let step = |until_seconds, points| ScoreStep { until_seconds, points };
+        let invalid = [
+            with_curve(ScoringCurve::Flat { points: -1 }),
+            with_curve(ScoringCurve::Flat { points: MAX_POINTS + 1 }),
+            with_curve(ScoringCurve::Linear { max_points: 10, min_points: 20, decay_seconds: 60 }),
+            with_curve(ScoringCurve::Linear { max_points: 100, min_points: 10, decay_seconds: 0 }),
+            with_curve(ScoringCurve::Stepped { steps: Vec::new(), after_points: 10 }),
+            with_curve(ScoringCurve::Stepped { steps: (1..=11).map(|i| step(i * 10, 10)).collect(), after_points: 10 }),
+            with_curve(ScoringCurve::Stepped { steps: vec![step(60, 100), step(60, 50)], after_points: 10 }),
+            with_curve(ScoringCurve::Stepped { steps: vec![step(0, 100)], after_points: 10 }),
+            with_curve(ScoringCurve::Stepped { steps: vec![step(60, 100)], after_points: -1 }),
+            ScoringConfig { bonuses: ScoringBonuses { completion: -1, ..ScoringBonuses::default() }, ..ScoringConfig::default() },
+            ScoringConfig { hints: HintPenalties { region: MAX_POINTS + 1, ..HintPenalties::default() }, ..ScoringConfig::default() },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn word_bonus() {
+        let bonuses = ScoringBonuses { per_extra_letter: 5, min_length: 5, hidden_words: 20, completion: 0 };
+        assert_eq!(bonuses.word_bonus("GATO", false), 0);
+        assert_eq!(bonuses.word_bonus("PEIXE", false), 0);
+        assert_eq!(bonuses.word_bonus("ABACAXI", false), 10);
+        assert_eq!(bonuses.word_bonus("ABACAXI", true), 30);
+        assert_eq!(bonuses.word_bonus("GATO", true), 20);
+        assert_eq!(ScoringBonuses::default().word_bonus("ABACAXI", true), 0);
+
+        let config = ScoringConfig { bonuses, ..ScoringConfig::default() };
+        assert_eq!(config.word_points("ABACAXI", Some(0), false), 110);
+    }
+}
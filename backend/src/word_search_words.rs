@@ -0,0 +1,240 @@
+use crate::word_search_grid;
+
+// Normalização e validação das palavras do caça-palavras. As palavras são salvas já
+// normalizadas, então a grade (frontend e servidor) só tem letras.
+
+// Menor palavra aceita
+const MIN_WORD_LENGTH: usize = 2;
+
+// Limite de tentativas da busca de posicionamento; acima disso o conjunto é aceito sem prova
+const PLACEMENT_BUDGET: usize = 1_000_000;
+
+// Maiúsculas, sem espaços nem hífens e, se strip_accents, sem acentos (ç, ã, é...)
+pub fn normalize(word: &str, strip_accents: bool) -> String {
+    word.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .map(|c| if strip_accents { strip_accent(c) } else { c })
+        .collect()
+}
+
+// Chave de comparação entre a palavra enviada pelo jogador e a salva
+pub fn key(word: &str) -> String {
+    normalize(word, false)
+}
+
+pub fn same_word(a: &str, b: &str) -> bool {
+    key(a) == key(b)
+}
+
+fn strip_accent(c: char) -> char {
+    match c {
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+        'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'Ç' => 'C',
+        'Ñ' => 'N',
+        _ => c,
+    }
+}
+
+// Valida a lista do autor e devolve as palavras normalizadas, na mesma ordem
+pub fn validate_words(
+    words: &[String],
+    grid_size: i32,
+    allowed_directions: Option<&serde_json::Value>,
+    strip_accents: bool,
+) -> Result<Vec<String>, String> {
+    if let Some(allowed) = allowed_directions.filter(|value| !value.is_null()) {
+        let keys = allowed.as_array().ok_or("allowed_directions must be a list of directions")?;
+        for key in keys {
+            let known = key.as_str()
+                .is_some_and(|key| word_search_grid::DIRECTIONS.iter().any(|(name, _, _)| *name == key));
+            if !known {
+                return Err(format!("Unknown direction {}", key));
+            }
+        }
+    }
+    let directions = word_search_grid::directions(allowed_directions);
+    if directions.is_empty() {
+        return Err("At least one direction must be allowed".to_string());
+    }
+
+    let mut normalized: Vec<String> = Vec::with_capacity(words.len());
+    for word in words {
+        let value = normalize(word, strip_accents);
+        if let Some(invalid) = value.chars().find(|c| !c.is_alphabetic()) {
+            return Err(format!("Word \"{}\" has an invalid character '{}'", word.trim(), invalid));
+        }
+        let length = value.chars().count();
+        if length < MIN_WORD_LENGTH {
+            return Err(format!("Word \"{}\" must have at least {} letters", word.trim(), MIN_WORD_LENGTH));
+        }
+        if length > grid_size as usize {
+            return Err(format!("Word \"{}\" has {} letters and does not fit a {}x{} grid", value, length, grid_size, grid_size));
+        }
+        if normalized.contains(&value) {
+            return Err(format!("Word \"{}\" is duplicated", value));
+        }
+        normalized.push(value);
+    }
+
+    // Uma palavra dentro de outra (em qualquer sentido) seria encontrada junto com ela
+    for word in &normalized {
+        let reversed = word.chars().rev().collect::<String>();
+        if let Some(longer) = normalized.iter().find(|other| *other != word && (other.contains(word.as_str()) || other.contains(&reversed))) {
+            return Err(format!("Word \"{}\" is contained in \"{}\"", word, longer));
+        }
+    }
+
+    let mut letters = normalized.iter()
+        .map(|word| word.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    letters.sort_by_key(|word| std::cmp::Reverse(word.len()));
+    let mut search = Placement {
+        size: grid_size,
+        directions,
+        grid: vec![vec![None; grid_size.max(0) as usize]; grid_size.max(0) as usize],
+        budget: PLACEMENT_BUDGET,
+    };
+    if search.place(&letters) == Some(false) {
+        return Err(format!("The words cannot all be placed in a {}x{} grid with the allowed directions", grid_size, grid_size));
+    }
+
+    Ok(normalized)
+}
+
+// Busca com retrocesso: existe alguma grade com todas as palavras?
+struct Placement {
+    size: i32,
+    directions: Vec<(&'static str, i32, i32)>,
+    grid: Vec<Vec<Option<char>>>,
+    budget: usize,
+}
+
+impl Placement {
+    // Some(true) = cabe, Some(false) = impossível, None = limite de tentativas atingido
+    fn place(&mut self, words: &[Vec<char>]) -> Option<bool> {
+        let (word, rest) = match words.split_first() {
+            Some(split) => split,
+            None => return Some(true),
+        };
+        let last = word.len() as i32 - 1;
+
+        for (_, dx, dy) in self.directions.clone() {
+            for row in 0..self.size {
+                for col in 0..self.size {
+                    if self.budget == 0 {
+                        return None;
+                    }
+                    self.budget -= 1;
+
+                    let (end_row, end_col) = (row + dx * last, col + dy * last);
+                    if end_row < 0 || end_row >= self.size || end_col < 0 || end_col >= self.size {
+                        continue;
+                    }
+                    let cells = (0..word.len() as i32)
+                        .map(|i| ((row + dx * i) as usize, (col + dy * i) as usize))
+                        .collect::<Vec<_>>();
+                    let fits = cells.iter().zip(word).all(|(&(r, c), letter)| {
+                        self.grid[r][c].is_none_or(|current| current == *letter)
+                    });
+                    if !fits {
+                        continue;
+                    }
+
+                    let written = cells.iter().zip(word)
+                        .filter(|(&(r, c), _)| self.grid[r][c].is_none())
+                        .map(|(&cell, letter)| (cell, *letter))
+                        .collect::<Vec<_>>();
+                    for &((r, c), letter) in &written {
+                        self.grid[r][c] = Some(letter);
+                    }
+                    match self.place(rest) {
+                        Some(false) => {}
+                        result => return result,
+                    }
+                    for &((r, c), _) in &written {
+                        self.grid[r][c] = None;
+                    }
+                }
+            }
+        }
+
+        Some(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn validate(words: &[&str], grid_size: i32, allowed_directions: Option<serde_json::Value>, strip_accents: bool) -> Result<Vec<String>, String> {
+        validate_words(&strings(words), grid_size, allowed_directions.as_ref(), strip_accents)
+    }
+
+    #[test]
+    fn normalizes_words() {
+        assert_eq!(validate(&["ação", "guarda-chuva", "pé de moleque"], 12, None, true).unwrap(), strings(&["ACAO", "GUARDACHUVA", "PEDEMOLEQUE"]));
+        assert_eq!(validate(&["ação"], 12, None, false).unwrap(), strings(&["AÇÃO"]));
+        assert_eq!(normalize("Coração", true), "CORACAO");
+        assert!(same_word("guarda-chuva", "GUARDA CHUVA"));
+    }
+
+    #[test]
+    fn rejects_invalid_words() {
+        let error = |words: &[&str], grid_size: i32| validate(words, grid_size, None, false).unwrap_err();
+
+        assert_eq!(error(&["GAT0"], 10), "Word \"GAT0\" has an invalid character '0'");
+        assert_eq!(error(&["A"], 10), "Word \"A\" must have at least 2 letters");
+        assert_eq!(error(&["ELEFANTE"], 5), "Word \"ELEFANTE\" has 8 letters and does not fit a 5x5 grid");
+        assert_eq!(error(&["Gato", "gato"], 10), "Word \"GATO\" is duplicated");
+    }
+
+    #[test]
+    fn rejects_contained_words() {
+        let error = |words: &[&str]| validate(words, 10, None, false).unwrap_err();
+
+        assert_eq!(error(&["CASAMENTO", "CASA"]), "Word \"CASA\" is contained in \"CASAMENTO\"");
+        // Ao contrário: SAC aparece como CAS em CASAMENTO
+        assert_eq!(error(&["SAC", "CASAMENTO"]), "Word \"SAC\" is contained in \"CASAMENTO\"");
+        assert!(validate(&["CASA", "MESA"], 10, None, false).is_ok());
+    }
+
+    #[test]
+    fn checks_directions() {
+        assert_eq!(
+            validate(&["GATO"], 10, Some(serde_json::json!(["right", "sideways"])), false).unwrap_err(),
+            "Unknown direction \"sideways\""
+        );
+        assert_eq!(
+            validate(&["GATO"], 10, Some(serde_json::json!("right")), false).unwrap_err(),
+            "allowed_directions must be a list of directions"
+        );
+        assert_eq!(
+            validate(&["GATO"], 10, Some(serde_json::json!([])), false).unwrap_err(),
+            "At least one direction must be allowed"
+        );
+    }
+
+    #[test]
+    fn placement_search() {
+        let right = || Some(serde_json::json!(["right"]));
+
+        // Uma palavra por linha: cabe exatamente
+        assert!(validate(&["ABC", "DEF", "GHI"], 3, right(), false).is_ok());
+        // Só cabem cruzando as letras em comum
+        assert!(validate(&["ABC", "ADG", "CFI"], 3, Some(serde_json::json!(["right", "down"])), false).is_ok());
+        assert_eq!(
+            validate(&["ABC", "DEF", "GHI", "JKL"], 3, right(), false).unwrap_err(),
+            "The words cannot all be placed in a 3x3 grid with the allowed directions"
+        );
+        assert!(validate(&["AB", "CD", "EF"], 2, right(), false).is_err());
+    }
+}
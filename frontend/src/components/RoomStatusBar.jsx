@@ -0,0 +1,104 @@
+import { useEffect, useState } from 'react';
+import { Play, Pause, Square, Timer } from 'lucide-react';
+
+const STATUS_LABELS = {
+  lobby: 'Aguardando o host iniciar',
+  countdown: 'Começando em',
+  running: 'Em andamento',
+  paused: 'Pausada',
+  finished: 'Partida encerrada',
+  archived: 'Sala fechada',
+};
+
+// Estado da sala e controles do host (iniciar, pausar, retomar, encerrar).
+// No lobby de salas com equipes, o jogador escolhe a equipe (TeamJoin).
+const RoomStatusBar = ({ status, role, countdownEndsAt, sendMessage, teams = [], myTeamId = null }) => {
+  const [countdown, setCountdown] = useState(null);
+
+  useEffect(() => {
+    if (status !== 'countdown' || !countdownEndsAt) {
+      setCountdown(null);
+      return;
+    }
+    const update = () => {
+      const seconds = Math.max(0, Math.ceil((new Date(countdownEndsAt).getTime() - Date.now()) / 1000));
+      setCountdown(seconds);
+    };
+    update();
+    const interval = setInterval(update, 250);
+    return () => clearInterval(interval);
+  }, [status, countdownEndsAt]);
+
+  if (!status || (status === 'running' && role !== 'host')) {
+    return null;
+  }
+
+  const isHost = role === 'host';
+  const send = (type, extra = {}) => sendMessage({ type, request_id: `status:${type}`, ...extra });
+  const buttonClass = 'flex items-center gap-1 px-3 py-1.5 rounded-lg text-sm font-medium text-white transition-colors';
+
+  return (
+    <div className="flex flex-wrap items-center justify-between gap-2 mb-4 px-4 py-3 rounded-lg bg-indigo-50 dark:bg-indigo-900/20 border border-indigo-200 dark:border-indigo-800">
+      <div className="flex items-center gap-2 text-indigo-700 dark:text-indigo-300 font-medium">
+        <Timer className="w-4 h-4" />
+        <span>
+          {STATUS_LABELS[status] || status}
+          {status === 'countdown' && countdown !== null && ` ${countdown}s`}
+        </span>
+      </div>
+
+      {isHost && (
+        <div className="flex flex-wrap gap-2">
+          {status === 'lobby' && (
+            <>
+              <button onClick={() => send('RoomStart', { countdown_seconds: 5 })} className={`${buttonClass} bg-indigo-600 hover:bg-indigo-700`}>
+                <Timer className="w-4 h-4" /> Contagem (5s)
+              </button>
+              <button onClick={() => send('RoomStart')} className={`${buttonClass} bg-green-600 hover:bg-green-700`}>
+                <Play className="w-4 h-4" /> Iniciar
+              </button>
+            </>
+          )}
+          {status === 'countdown' && (
+            <button onClick={() => send('RoomStart')} className={`${buttonClass} bg-green-600 hover:bg-green-700`}>
+              <Play className="w-4 h-4" /> Iniciar agora
+            </button>
+          )}
+          {status === 'running' && (
+            <button onClick={() => send('TimerPause')} className={`${buttonClass} bg-yellow-600 hover:bg-yellow-700`}>
+              <Pause className="w-4 h-4" /> Pausar
+            </button>
+          )}
+          {status === 'paused' && (
+            <button onClick={() => send('TimerResume')} className={`${buttonClass} bg-green-600 hover:bg-green-700`}>
+              <Play className="w-4 h-4" /> Retomar
+            </button>
+          )}
+          {(status === 'running' || status === 'paused') && (
+            <button onClick={() => send('RoomEnd')} className={`${buttonClass} bg-red-600 hover:bg-red-700`}>
+              <Square className="w-4 h-4" /> Encerrar
+            </button>
+          )}
+        </div>
+      )}
+
+      {role === 'player' && status === 'lobby' && teams.length > 0 && (
+        <div className="flex flex-wrap items-center gap-2 w-full">
+          <span className="text-sm text-indigo-700 dark:text-indigo-300">Sua equipe:</span>
+          {teams.map((team) => (
+            <button
+              key={team.id}
+              onClick={() => sendMessage({ type: 'TeamJoin', team_id: team.id, request_id: `team:${team.id}` })}
+              className={`${buttonClass} ${team.id === myTeamId ? 'ring-2 ring-offset-2 ring-indigo-500' : 'opacity-70 hover:opacity-100'}`}
+              style={{ backgroundColor: team.color }}
+            >
+              {team.name}
+            </button>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default RoomStatusBar;
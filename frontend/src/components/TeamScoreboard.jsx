@@ -0,0 +1,47 @@
+import { Users } from 'lucide-react';
+
+// Placar das equipes (modo equipes): GET /rooms/:id/scores?by=team ou team_scores do snapshot
+const TeamScoreboard = ({ teamScores, myTeamId, unitLabel = 'palavra' }) => {
+  if (!teamScores || teamScores.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="mt-6 p-4 bg-indigo-50 dark:bg-indigo-900/20 rounded-lg">
+      <h4 className="font-bold text-indigo-900 dark:text-indigo-300 mb-3 flex items-center gap-2">
+        <Users className="w-5 h-5" /> Equipes
+      </h4>
+      <div className="space-y-2">
+        {teamScores.map((team) => {
+          const isMine = myTeamId != null && team.team_id === myTeamId;
+          return (
+            <div
+              key={team.team_id}
+              className={`flex items-center justify-between p-3 rounded-lg ${
+                isMine
+                  ? 'bg-yellow-100/70 dark:bg-yellow-900/40 border-2 border-yellow-400 dark:border-yellow-600'
+                  : 'bg-white/60 dark:bg-white/10'
+              }`}
+            >
+              <div className="flex items-center gap-2">
+                <span className="font-bold text-gray-700 dark:text-dark-text-primary w-6">#{team.rank}</span>
+                <div className="w-5 h-5 rounded-full border-2 border-white shadow-md" style={{ backgroundColor: team.color }} />
+                <span className={`text-sm font-medium text-gray-900 dark:text-dark-text-primary ${isMine ? 'font-bold' : ''}`}>
+                  {team.name} {isMine && '(Sua equipe)'}
+                </span>
+              </div>
+              <div className="text-right">
+                <div className="font-bold text-indigo-900 dark:text-indigo-300 text-base">{team.total_score} pts</div>
+                <div className="text-xs text-gray-600 dark:text-dark-text-secondary font-medium">
+                  {team.words_found} {unitLabel}{team.words_found !== 1 ? 's' : ''} · {team.players} jogador{team.players !== 1 ? 'es' : ''}
+                </div>
+              </div>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+};
+
+export default TeamScoreboard;
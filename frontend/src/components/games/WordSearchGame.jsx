@@ -1,7 +1,10 @@
 import { useState, useEffect, useRef } from 'react';
 import { Clock, Star, CheckCircle, ChevronDown, ChevronLeft, ChevronRight, ZoomIn, Trophy, EyeOff } from 'lucide-react';
 import { useRoomWebSocket } from '../../hooks/useRoomWebSocket';
+import RoomStatusBar from '../RoomStatusBar';
+import TeamScoreboard from '../TeamScoreboard';
 import axios from 'axios';
+import { getDeviceId } from '../../services/api';
 
 const API_URL = import.meta.env.VITE_API_URL || 'http://localhost:8080/api';
 
@@ -23,6 +26,7 @@ api.interceptors.request.use((config) => {
   } else if (sessionId) {
     config.headers['X-Session-Id'] = sessionId;
   }
+  config.headers['X-Device-Id'] = getDeviceId();
   
   return config;
 });
@@ -61,10 +65,18 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
   const [score, setScore] = useState(0);
   const [roomScores, setRoomScores] = useState([]);
   const [totalRoomScore, setTotalRoomScore] = useState(0);
+  const [teamScores, setTeamScores] = useState([]); // Placar das equipes (modo equipes)
+  const [myTeamId, setMyTeamId] = useState(null);
+  const [teams, setTeams] = useState([]);
   const [expandedConcept, setExpandedConcept] = useState(null);
   const [loadingResult, setLoadingResult] = useState(true); // Flag para carregar resultado antes
   const [onlinePlayers, setOnlinePlayers] = useState([]); // Lista de jogadores online
+  const [roomStatus, setRoomStatus] = useState(null); // lobby, countdown, running, paused, finished
+  const [countdownEndsAt, setCountdownEndsAt] = useState(null);
+  const [myRole, setMyRole] = useState(null);
   const [showHowToPlay, setShowHowToPlay] = useState(true); // Modal Como Jogar ao iniciar
+  const [hint, setHint] = useState(null); // Última dica recebida do servidor {word, kind, cells, direction}
+  const [revealedConcepts, setRevealedConcepts] = useState({}); // Modo pistas: conceitos das palavras encontradas
   
   const gridRef = useRef(null);
   const timerRef = useRef(null);
@@ -72,6 +84,9 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
   const gameFinishedRef = useRef(false); // Flag para evitar múltiplas chamadas de finishGame
   const [zoomLevel, setZoomLevel] = useState(1); // Zoom padrão normal para mobile (1x)
   const touchStartPosRef = useRef(null); // Posição inicial do toque
+  const hasTeamsRef = useRef(false);
+  // Modos race/steal: cada jogador tem o seu tabuleiro (palavras dos outros não aparecem)
+  const competitiveRef = useRef(false);
 
   // WebSocket para multiplayer
   const handleWebSocketMessage = (message) => {
@@ -81,7 +96,10 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
       const isOwnMessage = myPlayerId != null && message.player_id != null &&
                            message.player_id.toString() === myPlayerId.toString();
       
-      if (!isOwnMessage) {
+      if (!isOwnMessage && !competitiveRef.current) {
+        if (message.concept) {
+          revealClue(message.word.toUpperCase(), message.concept);
+        }
         
         // Adicionar palavra ao foundWords global
         setFoundWords(prev => {
@@ -116,7 +134,7 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
         if (prev.some(p => p.player_id === message.player_id)) {
           return prev;
         }
-        return [...prev, { player_id: message.player_id, username: message.username, player_color: message.player_color }];
+        return [...prev, { player_id: message.player_id, username: message.username, player_color: message.player_color, team_id: message.team_id }];
       });
     } else if (message.type === 'PlayerLeft') {
       setOnlinePlayers(prev => {
@@ -126,6 +144,98 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
     } else if (message.type === 'PlayersList') {
       // Substituir completamente a lista (não mesclar)
       setOnlinePlayers(message.players || []);
+    } else if (message.type === 'Error' && message.request_id?.startsWith('word:')) {
+      // Palavra rejeitada pelo servidor: desfazer a marcação local
+      const rejectedWord = message.request_id.slice('word:'.length);
+      console.warn(message.message);
+      setMyFoundWords(prev => {
+        const updated = new Set(prev);
+        updated.delete(rejectedWord);
+        return updated;
+      });
+      if (message.code === 'invalid_word' || message.code === 'invalid_state') {
+        setFoundWords(prev => {
+          const updated = new Set(prev);
+          updated.delete(rejectedWord);
+          return updated;
+        });
+      }
+    } else if (message.type === 'Hint') {
+      // Dica calculada pelo servidor; a penalidade já foi descontada do placar
+      setHint(message);
+      if (message.penalty > 0) {
+        setScore(prev => prev - message.penalty);
+      }
+      // A região só pisca por alguns segundos
+      if (message.kind === 'region') {
+        setTimeout(() => setHint(prev => (prev === message ? null : prev)), 3000);
+      }
+    } else if (message.type === 'Error' && message.request_id?.startsWith('hint:')) {
+      console.warn(message.message);
+    } else if (message.type === 'GameStateSnapshot') {
+      // Estado inicial da sala enviado pelo servidor ao conectar
+      const scores = message.state?.scores || [];
+      setRoomStatus(message.state?.status || null);
+      setCountdownEndsAt(message.state?.timer?.countdown_ends_at || null);
+      setMyRole(message.state?.you?.role || null);
+      setRoomScores(scores);
+      setTotalRoomScore(scores.reduce((sum, player) => sum + (player.total_score || 0), 0));
+      hasTeamsRef.current = (message.state?.teams || []).length > 0;
+      setTeams(message.state?.teams || []);
+      setTeamScores(message.state?.team_scores || []);
+      setMyTeamId(message.state?.you?.team_id ?? null);
+      Object.entries(message.state?.revealed_concepts || {}).forEach(([word, concept]) => {
+        revealClue(word.toUpperCase(), concept);
+      });
+      const mode = message.state?.word_search_mode || 'cooperative';
+      competitiveRef.current = mode !== 'cooperative';
+      if (competitiveRef.current) {
+        // Tabuleiro próprio: o snapshot traz só as palavras deste jogador
+        const ownWords = message.state?.found_words || [];
+        const ownSet = new Set(ownWords.map(w => w.word.toUpperCase()));
+        setFoundWords(ownSet);
+        setMyFoundWords(ownSet);
+        setOtherPlayersWords([]);
+        setGrid(prev => {
+          const newGrid = prev.map(row => row.map(cell => ({ ...cell, isFound: false })));
+          ownWords.forEach(w => {
+            (w.cells || []).forEach(({ row, col }, index) => {
+              if (newGrid[row] && newGrid[row][col]) {
+                newGrid[row][col].isFound = true;
+                newGrid[row][col].foundBy = w.found_by_name;
+                newGrid[row][col].foundByColor = w.player_color;
+                newGrid[row][col].isFirstCell = index === 0;
+              }
+            });
+          });
+          return newGrid;
+        });
+      }
+    } else if (message.type === 'TeamsUpdated') {
+      // Equipes redefinidas pelo host ou jogador trocou de equipe: atualizar cores
+      hasTeamsRef.current = (message.teams || []).length > 0;
+      setTeams(message.teams || []);
+      if (!hasTeamsRef.current) {
+        setTeamScores([]);
+      }
+      const assignments = message.assignments || [];
+      setOnlinePlayers(prev => prev.map(p => {
+        const assignment = assignments.find(a => a.player_id === p.player_id);
+        return assignment ? { ...p, player_color: assignment.player_color, team_id: assignment.team_id } : p;
+      }));
+      const mine = assignments.find(a => myPlayerId != null && a.player_id.toString() === myPlayerId.toString());
+      if (mine) {
+        setMyTeamId(mine.team_id);
+      }
+    } else if (message.type === 'RoomStatusChanged') {
+      setRoomStatus(message.status);
+      setCountdownEndsAt(message.countdown_ends_at || null);
+      // Encerrada pelo host ou por tempo esgotado: finalizar para todos
+      if (message.status === 'finished') {
+        finishGame();
+      }
+    } else if (message.type === 'TimerSync') {
+      setRoomStatus(message.status);
     } else if (message.type === 'RoomReset') {
       console.log('🔄 Sala resetada, limpando tudo...');
       // Limpar todos os states
@@ -134,6 +244,10 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
       setOtherPlayersWords([]);
       setOnlinePlayers([]);
       setScores([]);
+      setRevealedConcepts({});
+      if (gameConfig?.clues) {
+        setWords(gameConfig.clues.map(clue => `#${clue.number}`));
+      }
       foundWordsLoadedRef.current = false;
       
       // Recarregar palavras do servidor (agora vazias)
@@ -166,8 +280,12 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
       const loadFoundWords = async () => {
         try {
           const API_URL = import.meta.env.VITE_API_URL || 'http://localhost:8080/api';
-          const response = await fetch(`${API_URL}/rooms/${roomId}/found-words`);
-          if (response.ok) {
+          const sessionId = localStorage.getItem('session_id');
+          const response = await fetch(`${API_URL}/rooms/${roomId}/found-words`, {
+            headers: sessionId ? { 'X-Session-Id': sessionId } : {}
+          });
+          // Modo competitivo: o tabuleiro próprio já veio no snapshot
+          if (response.ok && !competitiveRef.current) {
             const foundWordsData = await response.json();
             const myColor = localStorage.getItem('player_color');
             
@@ -326,6 +444,13 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
               setScore(myScore.total_score);
             }
           }
+
+          if (hasTeamsRef.current) {
+            const teamResponse = await fetch(`${API_URL}/rooms/${roomId}/scores?by=team`);
+            if (teamResponse.ok) {
+              setTeamScores(await teamResponse.json());
+            }
+          }
         } catch (error) {
           // Erro ao carregar pontuações
         }
@@ -423,7 +548,9 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
       }
       
       generateGrid();
-      setWords(gameConfig.words || []);
+      // Modo pistas: o servidor manda só as pistas; cada uma vira um marcador (#1, #2...)
+      // até a palavra ser encontrada
+      setWords(gameConfig.clues ? gameConfig.clues.map(clue => `#${clue.number}`) : (gameConfig.words || []));
       
       // Só marcar como iniciado se não estiver finalizado
       if (!gameFinishedRef.current) {
@@ -503,6 +630,23 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
   }, [gameConfig, expandedConcept]);
 
   const generateGrid = () => {
+    // Modo pistas: grade já montada pelo servidor (sem a lista de palavras)
+    if (gameConfig.grid) {
+      setGrid(gameConfig.grid.map((row, i) =>
+        row.split('').map((letter, j) => ({
+          letter,
+          row: i,
+          col: j,
+          isFound: false,
+          isSelected: false,
+          foundBy: null,
+          foundByColor: null,
+          isFirstCell: false,
+        }))
+      ));
+      return;
+    }
+
     const size = gameConfig.grid_size || 15;
     const newGrid = Array(size).fill(null).map((_, i) =>
       Array(size).fill(null).map((_, j) => ({
@@ -675,6 +819,12 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
 
     const reversedWord = selectedWord.split('').reverse().join('');
 
+    // Modo pistas: só o servidor conhece as palavras
+    if (gameConfig.clues) {
+      checkClueWord(selectedWord, reversedWord);
+      return;
+    }
+
     const matchingWord = words.find(
       (word) => 
         word.toUpperCase() === selectedWord || 
@@ -692,6 +842,7 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
       if (roomId && sendMessage) {
         sendMessage({
           type: 'WordFound',
+          request_id: `word:${wordUpper}`,
           word: matchingWord.toUpperCase(),
           cells: selectedCells,
           foundAt: time  // Tempo em segundos quando a palavra foi encontrada
@@ -700,11 +851,60 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
     }
   };
 
-  const markFoundCells = () => {
+  // Modo pistas: troca o marcador da pista pela palavra e guarda o conceito revelado
+  const revealClue = (word, concept) => {
+    setRevealedConcepts(prev => ({ ...prev, [word]: concept }));
+    setWords(prev => {
+      if (prev.includes(word)) return prev;
+      const length = word.replace(/ /g, '').length;
+      const index = prev.findIndex(key => {
+        const clue = key.startsWith('#') && gameConfig.clues?.[Number(key.slice(1)) - 1];
+        return clue && clue.concept === concept && clue.length === length;
+      });
+      if (index < 0) return prev;
+      const updated = [...prev];
+      updated[index] = word;
+      return updated;
+    });
+  };
+
+  const checkClueWord = async (selectedWord, reversedWord) => {
+    const cells = selectedCells;
+    const gameId = gameConfig.game_id || gameConfig.id;
+    let confirmed = null;
+    for (const candidate of [selectedWord, reversedWord]) {
+      try {
+        const response = await api.post(`/word-search/${gameId}/check`, { word: candidate });
+        confirmed = response.data;
+        break;
+      } catch (error) {
+        // 404: não é uma palavra do jogo
+      }
+    }
+    if (!confirmed || foundWords.has(confirmed.word)) return;
+
+    const wordUpper = confirmed.word;
+    revealClue(wordUpper, confirmed.concept);
+    setFoundWords((prev) => new Set([...prev, wordUpper]));
+    setMyFoundWords((prev) => new Set([...prev, wordUpper]));
+    markFoundCells(cells);
+
+    if (roomId && sendMessage) {
+      sendMessage({
+        type: 'WordFound',
+        request_id: `word:${wordUpper}`,
+        word: wordUpper,
+        cells,
+        foundAt: time
+      });
+    }
+  };
+
+  const markFoundCells = (cells = selectedCells) => {
     const newGrid = [...grid];
     const playerName = localStorage.getItem('player_name') || 'Você';
     
-    selectedCells.forEach(({ row, col }, index) => {
+    cells.forEach(({ row, col }, index) => {
       newGrid[row][col].isFound = true;
       newGrid[row][col].foundBy = playerName;
       newGrid[row][col].foundByColor = playerColor;
@@ -803,6 +1003,17 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
     return selectedCells.some(cell => cell.row === row && cell.col === col);
   };
 
+  // Modo pistas: a dica vem pelo número da pista (#3), que sai da lista quando a palavra é encontrada
+  const hintActive = !!hint && (hint.word.startsWith('#') ? words.includes(hint.word) : !foundWords.has(hint.word));
+
+  const isHintCell = (row, col) => {
+    return hintActive && hint.cells.some(cell => cell.row === row && cell.col === col);
+  };
+
+  const requestHint = (kind) => {
+    sendMessage({ type: 'HintRequest', kind, request_id: `hint:${kind}:${Date.now()}` });
+  };
+
   const getOtherPlayerCellColor = (row, col) => {
     // Verifica se esta célula foi encontrada por outro jogador
     for (const playerWord of otherPlayersWords) {
@@ -871,6 +1082,37 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                 </div>
               </div>
 
+              {roomId && (
+                <RoomStatusBar
+                  status={roomStatus}
+                  role={myRole}
+                  countdownEndsAt={countdownEndsAt}
+                  sendMessage={sendMessage}
+                  teams={teams}
+                  myTeamId={myTeamId}
+                />
+              )}
+
+              {roomId && roomStatus === 'running' && myRole && myRole !== 'spectator' && (
+                <div className="flex flex-wrap items-center gap-2 mb-3">
+                  <span className="text-sm text-gray-600 dark:text-dark-text-secondary">Dicas:</span>
+                  <button onClick={() => requestHint('first_letter')} className="px-3 py-1 text-sm rounded-lg bg-gray-100 dark:bg-dark-elevated hover:bg-gray-200 dark:hover:bg-gray-700">
+                    Primeira letra
+                  </button>
+                  <button onClick={() => requestHint('direction')} className="px-3 py-1 text-sm rounded-lg bg-gray-100 dark:bg-dark-elevated hover:bg-gray-200 dark:hover:bg-gray-700">
+                    Direção
+                  </button>
+                  <button onClick={() => requestHint('region')} className="px-3 py-1 text-sm rounded-lg bg-gray-100 dark:bg-dark-elevated hover:bg-gray-200 dark:hover:bg-gray-700">
+                    Região
+                  </button>
+                  {hintActive && hint.direction && (
+                    <span className="text-sm text-gray-700 dark:text-gray-300">
+                      Direção de uma palavra: <strong>{hint.direction}</strong>
+                    </span>
+                  )}
+                </div>
+              )}
+
               {/* Controles Mobile (Zoom + Navegação) */}
               <div className="md:hidden flex justify-between items-center gap-2 mb-2">
                 <div className="flex gap-2">
@@ -969,6 +1211,7 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                       {row.map((cell, colIndex) => {
                         const otherPlayerColor = getOtherPlayerCellColor(rowIndex, colIndex);
                         const isSelected = isCellSelected(rowIndex, colIndex);
+                        const isHint = isHintCell(rowIndex, colIndex);
                         
                         // Tamanho adaptativo baseado no grid size
                         const gridSize = grid.length;
@@ -990,6 +1233,8 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                                   ? 'text-white dark:text-dark-text-primary'
                                   : isSelected
                                   ? 'bg-yellow-200 dark:bg-yellow-900/40 text-yellow-900 dark:text-yellow-300'
+                                  : isHint
+                                  ? 'bg-blue-200 dark:bg-blue-900/40 text-blue-900 dark:text-blue-300 animate-pulse'
                                   : 'bg-white dark:bg-dark-surface text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-800'
                               }
                             `}
@@ -1039,7 +1284,12 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                 {words.map((word, index) => {
                   const wordUpper = word.toUpperCase();
                   const isFound = foundWords.has(wordUpper);
-                  const hasConcept = gameConfig.concepts && gameConfig.concepts[wordUpper];
+                  // Modo pistas: marcador (#n) mostra a pista e o tamanho da palavra
+                  const clue = word.startsWith('#') ? gameConfig.clues?.[Number(word.slice(1)) - 1] : null;
+                  const concepts = gameConfig.clues
+                    ? { ...revealedConcepts, ...(clue ? { [wordUpper]: clue.concept } : {}) }
+                    : gameConfig.concepts;
+                  const hasConcept = concepts && concepts[wordUpper];
                   const isExpanded = expandedConcept === wordUpper;
                   // hide_words: blur word name until found; always show concept
                   const hideWordsMode = !!(gameConfig.hide_words);
@@ -1072,7 +1322,7 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                               }`}
                               style={isHidden ? { filter: 'blur(7px)', userSelect: 'none' } : undefined}
                             >
-                              {word}
+                              {clue ? '_ '.repeat(clue.length).trim() : word}
                             </span>
                           </div>
                           <div className="flex items-center gap-2">
@@ -1096,7 +1346,7 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                       {showConcept && (
                         <div className="px-4 pb-3 pt-1">
                           <div className="text-sm text-gray-600 dark:text-dark-text-secondary bg-blue-50 dark:bg-blue-900/20 p-3 rounded-lg border-l-4 border-blue-500">
-                            {gameConfig.concepts[wordUpper]}
+                            {concepts[wordUpper]}
                           </div>
                         </div>
                       )}
@@ -1185,6 +1435,8 @@ const WordSearchGame = ({ gameConfig, gameSeed, onComplete, roomId, playerColor,
                 </div>
               )}
 
+              {roomId && <TeamScoreboard teamScores={teamScores} myTeamId={myTeamId} />}
+
               <div className="mt-6 p-4 bg-blue-50 dark:bg-blue-900/20 rounded-lg">
                 <h4 className="font-bold text-blue-900 dark:text-blue-300 mb-2">
                   Como Jogar:
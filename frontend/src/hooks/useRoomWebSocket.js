@@ -1,11 +1,13 @@
 import { useEffect, useRef, useState, useCallback } from 'react';
 
-export const useRoomWebSocket = (roomId, onMessage) => {
+// spectatorToken (opcional): conecta como espectador/projetor (ver POST /rooms/spectate)
+export const useRoomWebSocket = (roomId, onMessage, spectatorToken = null) => {
   const wsRef = useRef(null);
   const [isConnected, setIsConnected] = useState(false);
   const reconnectTimeoutRef = useRef(null);
   const reconnectAttemptsRef = useRef(0);
   const onMessageRef = useRef(onMessage);
+  const lastSeqRef = useRef(0); // Último evento recebido (para retomar após reconexão)
   const maxReconnectAttempts = 5;
 
   // Atualizar ref do callback sem causar reconexão
@@ -26,7 +28,7 @@ export const useRoomWebSocket = (roomId, onMessage) => {
     const token = localStorage.getItem('token');
     const sessionId = localStorage.getItem('session_id');
     
-    if (!token && !sessionId) {
+    if (!token && !sessionId && !spectatorToken) {
       return;
     }
 
@@ -39,7 +41,9 @@ export const useRoomWebSocket = (roomId, onMessage) => {
     // Adicionar token ou session_id como query parameter
     // WebSocket não suporta headers customizados, então enviamos via query string
     let queryParam = '';
-    if (token) {
+    if (spectatorToken) {
+      queryParam = `?spectator_token=${spectatorToken}`;
+    } else if (token) {
       queryParam = `?token=${token}`;
     } else if (sessionId) {
       queryParam = `?session_id=${sessionId}`;
@@ -53,11 +57,27 @@ export const useRoomWebSocket = (roomId, onMessage) => {
       ws.onopen = () => {
         setIsConnected(true);
         reconnectAttemptsRef.current = 0;
+
+        // Reconexão: pedir ao servidor os eventos perdidos
+        if (lastSeqRef.current > 0) {
+          ws.send(JSON.stringify({ type: 'Resume', last_seq: lastSeqRef.current }));
+        }
       };
 
       ws.onmessage = (event) => {
         try {
           const message = JSON.parse(event.data);
+
+          if (message.type === 'GameStateSnapshot') {
+            // Snapshot reflete tudo até message.seq; eventos posteriores continuam valendo
+            lastSeqRef.current = Math.max(lastSeqRef.current, message.seq);
+          } else if (typeof message.seq === 'number') {
+            // Ignorar eventos repetidos no replay
+            if (message.seq <= lastSeqRef.current) {
+              return;
+            }
+            lastSeqRef.current = message.seq;
+          }
           
           if (onMessageRef.current) {
             onMessageRef.current(message);
@@ -71,10 +91,15 @@ export const useRoomWebSocket = (roomId, onMessage) => {
         // Silenciar erro
       };
 
-      ws.onclose = () => {
+      ws.onclose = (event) => {
         setIsConnected(false);
         wsRef.current = null;
 
+        // Sala fechada pelo servidor (encerrada ou expirada): não reconectar
+        if (event.code === 1000) {
+          return;
+        }
+
         // Tentar reconectar
         if (reconnectAttemptsRef.current < maxReconnectAttempts) {
           reconnectAttemptsRef.current += 1;
@@ -90,7 +115,7 @@ export const useRoomWebSocket = (roomId, onMessage) => {
     } catch (error) {
       // Silenciar erro
     }
-  }, [roomId]);
+  }, [roomId, spectatorToken]);
 
   // Enviar mensagem
   const sendMessage = useCallback((message) => {
@@ -14,6 +14,14 @@ const normalizarSenha = (senha) => {
     .replace(/Ç/g, 'c');
 };
 
+// Equipes padrão do modo equipes (nome e cor)
+const DEFAULT_TEAMS = [
+  { name: 'Equipe Vermelha', color: '#EF4444' },
+  { name: 'Equipe Azul', color: '#3B82F6' },
+  { name: 'Equipe Verde', color: '#10B981' },
+  { name: 'Equipe Amarela', color: '#F59E0B' },
+];
+
 function CreateRoom() {
   const navigate = useNavigate();
   const location = useLocation();
@@ -27,12 +35,17 @@ function CreateRoom() {
     password: '',
     max_players: 50,
     duration_hours: 24,
+    time_limit: '',
+    team_count: '',
+    word_search_mode: 'cooperative',
   });
 
   useEffect(() => {
     fetchMyGames();
   }, []);
 
+  const selectedGame = games.find(game => String(game.id) === String(formData.game_id));
+
   const fetchMyGames = async () => {
     try {
       const response = await api.get('/protected/games/my');
@@ -85,6 +98,9 @@ function CreateRoom() {
         password: normalizarSenha(formData.password),
         max_players: formData.max_players,
         duration_hours: formData.duration_hours,
+        time_limit: formData.time_limit ? parseInt(formData.time_limit) : null,
+        teams: formData.team_count ? DEFAULT_TEAMS.slice(0, parseInt(formData.team_count)) : null,
+        word_search_mode: selectedGame?.game_type === 'word_search' ? formData.word_search_mode : null,
       };
 
       const response = await api.post('/protected/rooms', payload);
@@ -247,6 +263,71 @@ function CreateRoom() {
               </p>
             </div>
 
+            {/* Tempo limite da partida */}
+            <div>
+              <label className="block text-sm font-medium text-gray-700 dark:text-dark-text-primary mb-2">
+                <Clock className="inline w-4 h-4 mr-1" />
+                Tempo Limite da Partida
+              </label>
+              <select
+                value={formData.time_limit}
+                onChange={(e) => setFormData({ ...formData, time_limit: e.target.value })}
+                className="w-full px-4 py-2 bg-white dark:bg-dark-elevated border border-gray-300 dark:border-dark-border rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent text-gray-900 dark:text-dark-text-primary"
+              >
+                <option value="">Sem limite</option>
+                <option value={300}>5 minutos</option>
+                <option value={600}>10 minutos</option>
+                <option value={900}>15 minutos</option>
+                <option value={1800}>30 minutos</option>
+                <option value={3600}>1 hora</option>
+              </select>
+              <p className="mt-1 text-xs text-gray-500 dark:text-dark-text-secondary">
+                Ao esgotar o tempo a partida é encerrada e a pontuação é congelada
+              </p>
+            </div>
+
+            {/* Modo equipes */}
+            <div>
+              <label className="block text-sm font-medium text-gray-700 dark:text-dark-text-primary mb-2">
+                <Users className="inline w-4 h-4 mr-1" />
+                Equipes
+              </label>
+              <select
+                value={formData.team_count}
+                onChange={(e) => setFormData({ ...formData, team_count: e.target.value })}
+                className="w-full px-4 py-2 bg-white dark:bg-dark-elevated border border-gray-300 dark:border-dark-border rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent text-gray-900 dark:text-dark-text-primary"
+              >
+                <option value="">Cada jogador por si</option>
+                <option value={2}>2 equipes</option>
+                <option value={3}>3 equipes</option>
+                <option value={4}>4 equipes</option>
+              </select>
+              <p className="mt-1 text-xs text-gray-500 dark:text-dark-text-secondary">
+                Os jogadores escolhem a equipe ao entrar ou são distribuídos automaticamente
+              </p>
+            </div>
+
+            {/* Modo do caça-palavras */}
+            {selectedGame?.game_type === 'word_search' && (
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-dark-text-primary mb-2">
+                  Modo de Jogo
+                </label>
+                <select
+                  value={formData.word_search_mode}
+                  onChange={(e) => setFormData({ ...formData, word_search_mode: e.target.value })}
+                  className="w-full px-4 py-2 bg-white dark:bg-dark-elevated border border-gray-300 dark:border-dark-border rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent text-gray-900 dark:text-dark-text-primary"
+                >
+                  <option value="cooperative">Cooperativo (tabuleiro compartilhado)</option>
+                  <option value="race">Corrida (cada um no seu tabuleiro)</option>
+                  <option value="steal">Quem achar primeiro pontua</option>
+                </select>
+                <p className="mt-1 text-xs text-gray-500 dark:text-dark-text-secondary">
+                  Nos modos competitivos cada jogador encontra todas as palavras no próprio tabuleiro
+                </p>
+              </div>
+            )}
+
             {/* Botões */}
             <div className="flex gap-4 pt-4">
               <button
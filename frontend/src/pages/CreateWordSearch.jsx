@@ -321,6 +321,8 @@ const CreateWordSearch = () => {
         allowed_directions: allowedDirections,
         concepts: concepts,
         hide_words: hideWords,
+        // O formulário só aceita A–Z; acentos vindos de cópia/colagem são removidos no servidor
+        strip_accents: true,
       });
 
       localStorage.removeItem('tempGameData');
@@ -50,7 +50,7 @@ const EditQuiz = () => {
       } catch (_) {
         // config may not exist yet, leave default
       }
-      const questionsResponse = await api.get(`/quiz/${gameId}/questions`);
+      const questionsResponse = await api.get(`/protected/quiz/${gameId}/questions`);
       console.log('Resposta das questões:', questionsResponse.data);
       
       // Converter correct_option ("A", "B", "C", "D") para correct_answer (0, 1, 2, 3)
@@ -27,6 +27,8 @@ const EditWordSearch = () => {
   const [endScreenButtonNewTab, setEndScreenButtonNewTab] = useState(true);
   const [showEndScreenButton, setShowEndScreenButton] = useState(false);
   const [hideWords, setHideWords] = useState(false);
+  const [stripAccents, setStripAccents] = useState(true);
+  const [scoring, setScoring] = useState(null); // Curva de pontuação (null = padrão), mantida ao salvar
   const [selectedDirections, setSelectedDirections] = useState({
     up: true,
     down: true,
@@ -56,7 +58,7 @@ const EditWordSearch = () => {
       });
 
       // Buscar configuração do caça-palavras
-      const configResponse = await api.get(`/word-search/${gameId}`);
+      const configResponse = await api.get(`/protected/word-search/${gameId}`);
       const config = configResponse.data;
       
       setGridSize(config.grid_size || 15);
@@ -77,6 +79,8 @@ const EditWordSearch = () => {
 
       // Carregar opção esconder palavras
       setHideWords(config.hide_words || false);
+      setStripAccents(config.strip_accents ?? true);
+      setScoring(config.scoring || null);
       
       // Carregar direções permitidas
       if (config.allowed_directions) {
@@ -338,6 +342,8 @@ const EditWordSearch = () => {
         allowed_directions: allowedDirections,
         concepts: concepts,
         hide_words: hideWords,
+        scoring: scoring,
+        strip_accents: stripAccents,
       });
 
       // Mostrar toast de sucesso
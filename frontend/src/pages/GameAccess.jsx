@@ -116,8 +116,9 @@ function GameAccess() {
       }
 
       // Armazenar session_id no localStorage
-      const { session_id, room_id, player_color, player_name, room_code } = roomData;
+      const { session_id, player_id, room_id, player_color, player_name, room_code } = roomData;
       localStorage.setItem('session_id', session_id);
+      localStorage.setItem('player_id', player_id);
       localStorage.setItem('player_name', player_name);
       localStorage.setItem('player_color', player_color);
 
@@ -3,6 +3,8 @@ import { useNavigate, useSearchParams } from 'react-router-dom';
 import { Clock, CheckCircle, XCircle, ArrowRight, Users, FileDown } from 'lucide-react';
 import api from '../services/api';
 import { useRoomWebSocket } from '../hooks/useRoomWebSocket';
+import RoomStatusBar from '../components/RoomStatusBar';
+import TeamScoreboard from '../components/TeamScoreboard';
 
 function QuizPlay() {
   const navigate = useNavigate();
@@ -26,6 +28,13 @@ function QuizPlay() {
   // Estados para sincronização multiplayer
   const [votes, setVotes] = useState({}); // { questionIndex: { 'A': [player1, player2], 'B': [...] } }
   const [connectedPlayers, setConnectedPlayers] = useState([]);
+  const [roomStatus, setRoomStatus] = useState(null);
+  const [countdownEndsAt, setCountdownEndsAt] = useState(null);
+  const [myRole, setMyRole] = useState(null);
+  const [teams, setTeams] = useState([]); // Modo equipes
+  const [myTeamId, setMyTeamId] = useState(null);
+  const [teamScores, setTeamScores] = useState([]);
+  const [soloSummary, setSoloSummary] = useState(null); // Fora de sala: só o total de acertos e pontos
   const myPlayerIdRef = useRef(null);
   const [elapsedTime, setElapsedTime] = useState(0); // Timer crescente em segundos
   const questionStartTimeRef = useRef(0); // Tempo em que a questão atual começou
@@ -61,6 +70,36 @@ function QuizPlay() {
   // WebSocket para modo sala
   const handleWebSocketMessage = (message) => {
     switch (message.type) {
+      case 'GameStateSnapshot':
+        if (message.state?.you?.player_id != null) {
+          myPlayerIdRef.current = message.state.you.player_id;
+        }
+        setRoomStatus(message.state?.status || null);
+        setCountdownEndsAt(message.state?.timer?.countdown_ends_at || null);
+        setMyRole(message.state?.you?.role || null);
+        setTeams(message.state?.teams || []);
+        setTeamScores(message.state?.team_scores || []);
+        setMyTeamId(message.state?.you?.team_id ?? null);
+        break;
+      case 'TeamsUpdated': {
+        setTeams(message.teams || []);
+        const mine = (message.assignments || []).find(a => a.player_id === myPlayerIdRef.current);
+        if (mine) {
+          setMyTeamId(mine.team_id);
+        }
+        break;
+      }
+      case 'RoomStatusChanged':
+        setRoomStatus(message.status);
+        setCountdownEndsAt(message.countdown_ends_at || null);
+        // Encerrada pelo host ou por tempo esgotado
+        if (message.status === 'finished') {
+          setShowResults(true);
+        }
+        break;
+      case 'TimerSync':
+        setRoomStatus(message.status);
+        break;
       case 'QuizAnswer':
         // Registrar voto
         setVotes(prev => {
@@ -173,28 +212,11 @@ function QuizPlay() {
     handleWebSocketMessage
   );
 
-  // Obter player_id da sessão (usando MESMA LÓGICA do backend)
+  // player_id = id do participante na sala (confirmado pelo snapshot do WebSocket)
   useEffect(() => {
-    const sessionId = localStorage.getItem('session_id');
-    const token = localStorage.getItem('token');
-    
-    if (token) {
-      // Para usuários autenticados: decodificar o JWT e usar o campo 'sub' (user_id real)
-      try {
-        const payload = JSON.parse(atob(token.split('.')[1]));
-        if (payload.sub) {
-          myPlayerIdRef.current = payload.sub;
-        }
-      } catch (e) {
-        // fallback ignorado
-      }
-    } else if (sessionId) {
-      // Gerar ID único usando MESMA LÓGICA do backend: wrapping_add
-      let hash = 0;
-      for (let i = 0; i < sessionId.length; i++) {
-        hash = (hash + sessionId.charCodeAt(i)) | 0; // wrapping_add em JS
-      }
-      myPlayerIdRef.current = (Math.abs(hash) % 1000000) + 1;
+    const storedPlayerId = localStorage.getItem('player_id');
+    if (storedPlayerId && !localStorage.getItem('token')) {
+      myPlayerIdRef.current = parseInt(storedPlayerId);
     }
     
     // NÃO adicionar manualmente - deixar o backend enviar PlayerJoined
@@ -417,17 +439,52 @@ function QuizPlay() {
     }
   };
 
-  const handleFinishQuiz = async () => {
-    console.log('🏁 handleFinishQuiz chamado');
-    
-    const correctAnswers = questions.reduce((acc, question, index) => {
-      if (selectedAnswers[index] === question.correct_option) {
-        return acc + 1;
+  // Buscar gabarito e justificativas no servidor (a API de jogo não envia correct_option)
+  const loadReview = async (answers) => {
+    try {
+      const sessionId = localStorage.getItem('session_id');
+      const response = roomId
+        ? await api.get(`/rooms/${roomId}/quiz-review`, {
+            headers: sessionId ? { 'X-Session-Id': sessionId } : {}
+          })
+        : await api.post(`/quiz/${gameId}/review`, { answers });
+
+      if (!roomId) {
+        setSoloSummary(response.data);
+        return response.data;
       }
-      return acc;
-    }, 0);
 
-    const score = Math.round((correctAnswers / questions.length) * 100);
+      const reviewed = response.data.questions || [];
+      setQuestions(prev => prev.map((question, index) => ({
+        ...question,
+        correct_option: reviewed[index]?.correct_option,
+        justification: reviewed[index]?.justification
+      })));
+      return response.data;
+    } catch (err) {
+      console.error('⚠️ Erro ao carregar revisão do quiz:', err);
+      return null;
+    }
+  };
+
+  // Ao mostrar resultados (inclusive quando outro jogador finaliza), carregar o gabarito
+  useEffect(() => {
+    if (showResults && questions.length > 0 && !soloSummary && questions.every(q => q.correct_option === undefined)) {
+      loadReview(selectedAnswers);
+    }
+  }, [showResults, questions.length]);
+
+  // Modo equipes: placar final das equipes
+  useEffect(() => {
+    if (showResults && roomId && teams.length > 0) {
+      api.get(`/rooms/${roomId}/scores`, { params: { by: 'team' } })
+        .then(response => setTeamScores(response.data))
+        .catch(() => {});
+    }
+  }, [showResults, roomId, teams.length]);
+
+  const handleFinishQuiz = async () => {
+    console.log('🏁 handleFinishQuiz chamado');
 
     if (roomId) {
       try {
@@ -462,6 +519,11 @@ function QuizPlay() {
           console.error('⚠️ Erro ao salvar progresso (continuando):', progressErr);
         }
         
+        // Correção feita pelo servidor após marcar como finalizado
+        const review = await loadReview(selectedAnswers);
+        const correctAnswers = review?.correct_answers ?? 0;
+        const score = Math.round((correctAnswers / questions.length) * 100);
+        
         // DEPOIS: Tentar salvar resultado final (pode falhar, mas não importa)
         try {
           await api.post(`/rooms/${roomId}/answer`, {
@@ -563,22 +625,25 @@ function QuizPlay() {
       window.print();
     };
     
-    const correctAnswers = questions.reduce((acc, question, index) => {
-      if (selectedAnswers[index] === question.correct_option) {
-        return acc + 1;
-      }
-      return acc;
-    }, 0);
+    // Fora de sala o servidor devolve só os totais (sem correção por pergunta)
+    const graded = !!roomId;
+    const isAnswerCorrect = (question, index) => selectedAnswers[index] === question.correct_option;
+
+    const correctAnswers = graded
+      ? questions.reduce((acc, question, index) => isAnswerCorrect(question, index) ? acc + 1 : acc, 0)
+      : (soloSummary?.correct_answers ?? 0);
     
     // Calcular pontuação total baseada no tempo
-    const totalPoints = questions.reduce((acc, question, index) => {
-      if (selectedAnswers[index] === question.correct_option) {
-        const timeOnQuestion = answerTimes[index] || 0;
-        const points = calculatePoints(question.points || 100, timeOnQuestion);
-        return acc + points;
-      }
-      return acc;
-    }, 0);
+    const totalPoints = graded
+      ? questions.reduce((acc, question, index) => {
+          if (isAnswerCorrect(question, index)) {
+            const timeOnQuestion = answerTimes[index] || 0;
+            const points = calculatePoints(question.points || 100, timeOnQuestion);
+            return acc + points;
+          }
+          return acc;
+        }, 0)
+      : (soloSummary?.total_score ?? 0);
     
     const maxPossiblePoints = questions.reduce((acc, q) => acc + (q.points || 100), 0);
 
@@ -613,10 +678,16 @@ function QuizPlay() {
             </p>
           </div>
 
+          {roomId && (
+            <div className="mb-6">
+              <TeamScoreboard teamScores={teamScores} myTeamId={myTeamId} unitLabel="acerto" />
+            </div>
+          )}
+
           <div className="space-y-6 max-h-[500px] print:max-h-none overflow-y-auto print:overflow-visible mb-6">
             {questions.map((question, index) => {
               const userAnswer = selectedAnswers[index];
-              const isCorrect = userAnswer === question.correct_option;
+              const isCorrect = graded && isAnswerCorrect(question, index);
               
               // Pegar texto completo da opção selecionada e da correta
               const getOptionText = (letter) => {
@@ -640,9 +711,11 @@ function QuizPlay() {
               return (
                 <div key={question.id}
                   className={`p-4 rounded-lg border-2 print-keep-together ${
-                    isCorrect
-                      ? 'bg-green-50 dark:bg-green-900/20 border-green-500'
-                      : 'bg-red-50 dark:bg-red-900/20 border-red-500'
+                    !graded
+                      ? 'bg-gray-50 dark:bg-dark-surface border-gray-300 dark:border-gray-600'
+                      : isCorrect
+                        ? 'bg-green-50 dark:bg-green-900/20 border-green-500'
+                        : 'bg-red-50 dark:bg-red-900/20 border-red-500'
                   }`}
                 >
                   <p className="font-semibold text-gray-800 dark:text-gray-200 mb-3">
@@ -657,12 +730,12 @@ function QuizPlay() {
                   <div className="space-y-2">
                     <div className="text-sm">
                       <span className="font-medium text-gray-700 dark:text-gray-200">Sua resposta: </span>
-                      <span className={`${userAnswer ? (isCorrect ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400') : 'text-gray-500 dark:text-gray-400'}`}>
+                      <span className={`${userAnswer && graded ? (isCorrect ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400') : 'text-gray-500 dark:text-gray-400'}`}>
                         {userAnswer && `${userAnswer}) `}{userAnswerText}
                       </span>
                     </div>
                     
-                    {!isCorrect && userAnswer && (
+                    {!isCorrect && userAnswer && question.correct_option && (
                       <div className="text-sm">
                         <span className="font-medium text-gray-700 dark:text-gray-200">Resposta correta: </span>
                         <span className="text-green-600 dark:text-green-400">
@@ -764,6 +837,16 @@ function QuizPlay() {
               </div>
             </div>
           </div>
+          {roomId && (
+            <RoomStatusBar
+              status={roomStatus}
+              role={myRole}
+              countdownEndsAt={countdownEndsAt}
+              sendMessage={sendMessage}
+              teams={teams}
+              myTeamId={myTeamId}
+            />
+          )}
           <div className="flex items-center gap-2">
             <div className="flex-1 bg-gray-200 dark:bg-gray-700 rounded-full h-2">
               <div
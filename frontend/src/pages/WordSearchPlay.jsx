@@ -59,7 +59,8 @@ const WordSearchPlay = () => {
     try {
       const [gameResponse, configResponse] = await Promise.all([
         api.get(`/games/${gameId}`),
-        api.get(`/word-search/${gameId}`)
+        // Modo pistas: a seed da sala monta a mesma grade para todos
+        api.get(`/word-search/${gameId}`, { params: roomId && seed ? { seed } : {} })
       ]);
       
       setGameConfig({
@@ -77,12 +78,9 @@ const WordSearchPlay = () => {
           // Se for jogador anônimo e já tiver a cor armazenada
           if (sessionId && playerColorStored) {
             setPlayerColor(playerColorStored);
-            // Computar myPlayerId usando MESMA lógica do backend (hash do session_id)
-            let hash = 0;
-            for (let i = 0; i < sessionId.length; i++) {
-              hash = (hash + sessionId.charCodeAt(i)) | 0;
-            }
-            setMyPlayerId((Math.abs(hash) % 1000000) + 1);
+            // player_id = id do participante, retornado ao entrar na sala
+            const storedPlayerId = localStorage.getItem('player_id');
+            setMyPlayerId(storedPlayerId ? parseInt(storedPlayerId) : null);
             console.log('Cor do jogador (anônimo):', playerColorStored);
           } else if (!sessionId) {
             // Jogador autenticado - buscar do backend
@@ -93,10 +91,12 @@ const WordSearchPlay = () => {
             if (token) {
               const payload = JSON.parse(atob(token.split('.')[1]));
               const currentUserId = payload.sub; // 'sub' é o campo correto
-              setMyPlayerId(currentUserId);
               
-              // Encontrar participante atual
+              // Encontrar participante atual (player_id = id do participante)
               const participant = roomResponse.data.participants.find(p => p.user_id === currentUserId);
+              if (participant) {
+                setMyPlayerId(participant.id);
+              }
               if (participant && participant.player_color) {
                 setPlayerColor(participant.player_color);
                 console.log('Cor do jogador (autenticado):', participant.player_color);
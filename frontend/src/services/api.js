@@ -30,12 +30,23 @@ const sanitizeData = (data) => {
   return data;
 };
 
+// Identificador do aparelho: liga as partidas individuais de jogadores anônimos
+export const getDeviceId = () => {
+  let deviceId = localStorage.getItem('device_id');
+  if (!deviceId) {
+    deviceId = crypto.randomUUID();
+    localStorage.setItem('device_id', deviceId);
+  }
+  return deviceId;
+};
+
 // Interceptor para adicionar token de autenticação e sanitizar dados
 api.interceptors.request.use((config) => {
   const token = localStorage.getItem('token');
   if (token) {
     config.headers.Authorization = `Bearer ${token}`;
   }
+  config.headers['X-Device-Id'] = getDeviceId();
   
   // Sanitizar dados de POST/PUT
   if (config.data && (config.method === 'post' || config.method === 'put')) {
@@ -76,6 +87,12 @@ export const getMyGames = () => api.get('/protected/games/my');
 export const deleteGame = (id) => api.delete(`/protected/games/${id}`);
 
 // Word Search
+export const getResultsHistory = () => api.get('/game-results/history');
+export const getGameHistory = (gameId) => api.get(`/game-results/${gameId}/history`);
+export const getGlobalLeaderboard = (params) => api.get('/leaderboards', { params });
+export const getGameLeaderboard = (gameId, params) => api.get(`/leaderboards/${gameId}`, { params });
+export const optOutOfLeaderboards = () => api.post('/leaderboards/opt-out');
+export const optIntoLeaderboards = () => api.delete('/leaderboards/opt-out');
 export const getWordSearchConfig = (gameId) => api.get(`/word-search/${gameId}`);
 export const createWordSearchConfig = (data) => api.post('/protected/word-search', data);
 export const updateWordSearchConfig = (gameId, data) => api.put(`/protected/word-search/${gameId}`, data);